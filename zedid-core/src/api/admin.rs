@@ -0,0 +1,175 @@
+use crate::api::auth::{Caller, RequestMeta};
+use crate::state::{AppState, DemoSeedCounts};
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+use zedid_identity::jwt::PublishedKey;
+use zedid_identity::{AuditDecision, IdentityAuditEvent};
+
+/// Shortest accepted signing secret, in bytes (HS256 wants at least 256 bits)
+const MIN_SIGNING_SECRET_BYTES: usize = 32;
+
+#[derive(Deserialize)]
+pub struct RotateSigningKeyRequest {
+    /// New HMAC secret; every replica must be given the same one
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+pub struct RotateSigningKeyResponse {
+    /// `kid` new tokens are signed with
+    pub kid: String,
+    /// Keys that verify tokens now, as published in the JWKS
+    pub keys: Vec<PublishedKey>,
+}
+
+/// Admin actions need a Critical trust token. Such a token only proves
+/// anything while issuance is policy-gated (`ZEDID_GATE_TOKEN_ISSUANCE`);
+/// otherwise anyone can mint one for the seeded admin identity, so admin
+/// actions are refused outright.
+fn require_admin(state: &AppState, caller: &Caller, action: &str) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let refusal = if !state.config.gate_token_issuance {
+        format!("{} is disabled while token issuance is ungated (ZEDID_GATE_TOKEN_ISSUANCE)", action)
+    } else if !caller.is_admin() {
+        format!("{} requires a Critical trust token", action)
+    } else {
+        return Ok(());
+    };
+    Err((StatusCode::FORBIDDEN, Json(serde_json::json!({"error": refusal}))))
+}
+
+/// Wipe identities, policies, namespaces and the audit log and re-seed the
+/// demo data. Returns 404 unless `ZEDID_ENABLE_DEMO_RESET=true`, and 403 for
+/// non-admin callers (see `require_admin`). The reset is the first event in
+/// the fresh audit log.
+pub async fn reset_demo(
+    State(state): State<AppState>,
+    caller: Caller,
+    meta: RequestMeta,
+) -> Result<Json<DemoSeedCounts>, (StatusCode, Json<serde_json::Value>)> {
+    if !state.config.enable_demo_reset {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Demo reset is disabled (ZEDID_ENABLE_DEMO_RESET)"})),
+        ));
+    }
+    require_admin(&state, &caller, "Demo reset")?;
+    let seeded = state.reset_demo().await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?;
+
+    let mut audit = IdentityAuditEvent::new(
+        Uuid::nil(),
+        "admin.demo_reset",
+        caller.actor(),
+        "demo-data",
+        AuditDecision::Allow,
+        Some(format!(
+            "Demo data reset: {} identities, {} namespaces, {} policies seeded",
+            seeded.identities, seeded.namespaces, seeded.policies
+        )),
+    );
+    audit.metadata = meta.audit_metadata();
+    if let Err(e) = state.append_audit(audit).await {
+        warn!("Demo reset was not audited: {}", e);
+    }
+    Ok(Json(seeded))
+}
+
+/// Switch token signing to a new secret. Tokens signed with the previous key
+/// keep verifying for `ZEDID_JWT_KEY_GRACE_MINUTES`. Admin only (see
+/// `require_admin`); the rotation is audited as `admin.signing_key_rotated`.
+pub async fn rotate_signing_key(
+    State(state): State<AppState>,
+    caller: Caller,
+    meta: RequestMeta,
+    Json(req): Json<RotateSigningKeyRequest>,
+) -> Result<Json<RotateSigningKeyResponse>, (StatusCode, Json<serde_json::Value>)> {
+    require_admin(&state, &caller, "Key rotation")?;
+    if req.secret.len() < MIN_SIGNING_SECRET_BYTES {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "error": format!("secret must be at least {} bytes", MIN_SIGNING_SECRET_BYTES)
+            })),
+        ));
+    }
+    let previous = state.jwt_service.signing_kid();
+    let kid = state
+        .jwt_service
+        .rotate_key(&req.secret)
+        .map_err(|e| (StatusCode::CONFLICT, Json(serde_json::json!({"error": e.to_string()}))))?;
+
+    let mut audit = IdentityAuditEvent::new(
+        Uuid::nil(),
+        "admin.signing_key_rotated",
+        caller.actor(),
+        &kid,
+        AuditDecision::Allow,
+        Some(format!("Signing key {} replaced by {}", previous, kid)),
+    );
+    audit.metadata = meta.audit_metadata();
+    if let Err(e) = state.append_audit(audit).await {
+        warn!("Signing key rotation was not audited: {}", e);
+    }
+    Ok(Json(RotateSigningKeyResponse { kid, keys: state.jwt_service.published_keys() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    #[tokio::test]
+    async fn test_reset_demo_reseeds_and_is_gated() {
+        let mut config = AppConfig::load().unwrap();
+        config.enable_demo_reset = true;
+        config.gate_token_issuance = true;
+        let state = AppState::new(config.clone()).await.unwrap();
+        let seeded_identities = state.identities.read().await.len();
+        let seeded_policies = state.policy_engine.list_policies(None).await.len();
+        let admin = state.identities.read().await.iter().find(|i| i.name == "admin").cloned().unwrap();
+        let token = state
+            .jwt_service
+            .issue_token(&admin.id.to_string(), &admin.name, &admin.namespace, "human", 4, None, 5, None)
+            .unwrap();
+        let admin_caller = Caller::from_authorization(&state.jwt_service, Some(&format!("Bearer {}", token)));
+
+        let extra = state.spiffe_paths.workload("scratch", "production").unwrap();
+        state.identities.write().await.push(extra);
+        state
+            .append_audit(IdentityAuditEvent::new(Uuid::nil(), "test", "test", "test", AuditDecision::Allow, None))
+            .await
+            .unwrap();
+
+        let denied = reset_demo(State(state.clone()), Caller::default(), RequestMeta::default()).await;
+        assert_eq!(denied.unwrap_err().0, StatusCode::FORBIDDEN);
+        assert_eq!(state.identities.read().await.len(), seeded_identities + 1);
+
+        let Json(counts) = reset_demo(State(state.clone()), admin_caller.clone(), RequestMeta::default())
+            .await
+            .unwrap();
+        assert_eq!(counts.identities, seeded_identities);
+        assert_eq!(counts.policies, seeded_policies);
+        assert!(state.identities.read().await.iter().all(|i| i.name != "scratch"));
+        let audit_log = state.audit_log.read().await;
+        assert_eq!(audit_log.len(), 1);
+        assert_eq!(audit_log[0].action, "admin.demo_reset");
+        drop(audit_log);
+
+        // Without gated issuance anyone could mint the admin token
+        config.gate_token_issuance = false;
+        let ungated = AppState::new(config.clone()).await.unwrap();
+        let err = reset_demo(State(ungated), admin_caller.clone(), RequestMeta::default()).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::FORBIDDEN);
+
+        config.enable_demo_reset = false;
+        let disabled = AppState::new(config).await.unwrap();
+        let err = reset_demo(State(disabled), admin_caller, RequestMeta::default()).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,15 +1,45 @@
 use crate::state::AppState;
-use axum::{extract::State, Json};
-use serde::Serialize;
-use zedid_identity::{AuditDecision, IdentityAuditEvent};
+use super::pagination::paginate;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use zedid_identity::{AuditCheckpoint, AuditDecision, IdentityAuditEvent};
 
-pub async fn list_audit_events(State(state): State<AppState>) -> Json<serde_json::Value> {
+#[derive(Deserialize)]
+pub struct AuditListQuery {
+    /// Opaque cursor from a previous page's `next_cursor`
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 1000)
+    pub limit: Option<usize>,
+    /// Deprecated: shifts as events are appended, use `cursor`
+    pub offset: Option<usize>,
+}
+
+/// Audit events newest first, paged by (timestamp, id). Events appended after
+/// the first page sort ahead of the cursor, so later pages never repeat or skip.
+pub async fn list_audit_events(
+    State(state): State<AppState>,
+    Query(query): Query<AuditListQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
     let audit_log = state.audit_log.read().await;
-    let events: Vec<&IdentityAuditEvent> = audit_log.iter().rev().take(100).collect();
-    Json(serde_json::json!({
+    let mut events: Vec<&IdentityAuditEvent> = audit_log.iter().collect();
+    events.sort_by_key(|e| std::cmp::Reverse((e.timestamp, e.id)));
+    let (events, next_cursor) = paginate(
+        events,
+        |e| (e.timestamp, e.id),
+        true,
+        query.cursor.as_deref(),
+        query.offset,
+        query.limit,
+    )?;
+    Ok(Json(serde_json::json!({
         "events": events,
         "total": audit_log.len(),
-    }))
+        "next_cursor": next_cursor,
+    })))
 }
 
 #[derive(Serialize)]
@@ -19,6 +49,11 @@ pub struct AuditStats {
     pub deny_count: usize,
     pub error_count: usize,
     pub recent_actions: Vec<String>,
+    /// Retention settings currently in force
+    pub retention_days: Option<i64>,
+    pub max_events: usize,
+    /// Present once any events have been pruned
+    pub checkpoint: Option<AuditCheckpoint>,
 }
 
 pub async fn audit_stats(State(state): State<AppState>) -> Json<AuditStats> {
@@ -49,5 +84,8 @@ pub async fn audit_stats(State(state): State<AppState>) -> Json<AuditStats> {
         deny_count,
         error_count,
         recent_actions,
+        retention_days: state.config.audit_retention_days,
+        max_events: state.config.audit_max_events,
+        checkpoint: state.audit_checkpoint.read().await.clone(),
     })
 }
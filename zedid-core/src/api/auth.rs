@@ -0,0 +1,266 @@
+use crate::state::AppState;
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+    Json,
+};
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+use zedid_identity::jwt::{JwtService, ZedIdClaims};
+use zedid_identity::spiffe::normalize_spiffe_id;
+use zedid_identity::{AuditDecision, AuditMetadata, Identity, IdentityAuditEvent, TrustLevel};
+
+/// Envoy's forwarded client certificate header (set by the mesh on mTLS)
+const XFCC_HEADER: &str = "x-forwarded-client-cert";
+
+/// The authenticated caller, if the request carried a valid ZedID bearer token.
+/// Missing or invalid tokens yield an anonymous caller rather than a rejection;
+/// handlers decide what anonymous callers may see.
+#[derive(Debug, Clone, Default)]
+pub struct Caller {
+    pub claims: Option<ZedIdClaims>,
+}
+
+impl Caller {
+    /// Validate an `Authorization` header value (`Bearer <jwt>`). This checks
+    /// the token only; see `authenticate` for the identity's liveness.
+    pub fn from_authorization(jwt: &JwtService, authorization: Option<&str>) -> Self {
+        let claims = authorization
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .and_then(|token| jwt.validate_token(token.trim()).ok());
+        Self { claims }
+    }
+
+    /// `from_authorization`, then treat the caller as anonymous unless the
+    /// token's subject is still a live identity (deactivated identities and
+    /// expired leases revoke their outstanding tokens)
+    pub async fn authenticate(state: &AppState, authorization: Option<&str>) -> Self {
+        let mut caller = Self::from_authorization(&state.jwt_service, authorization);
+        if let Some(claims) = &caller.claims {
+            if !state.is_subject_live(&claims.sub).await {
+                caller.claims = None;
+            }
+        }
+        caller
+    }
+
+    /// Subject of the bearer token, or `anonymous`
+    pub fn actor(&self) -> &str {
+        self.claims.as_ref().map_or("anonymous", |c| c.sub.as_str())
+    }
+
+    /// Internal callers (High trust or above) get full decision details
+    pub fn is_trusted(&self) -> bool {
+        self.claims
+            .as_ref()
+            .is_some_and(|c| c.trust_level >= TrustLevel::High.as_u8())
+    }
+
+    /// Administrative operations require a Critical trust token
+    pub fn is_admin(&self) -> bool {
+        self.claims
+            .as_ref()
+            .is_some_and(|c| c.trust_level >= TrustLevel::Critical.as_u8())
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for Caller {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let authorization = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok());
+        Ok(Caller::authenticate(state, authorization).await)
+    }
+}
+
+/// Request details recorded in audit metadata
+#[derive(Debug, Clone, Default)]
+pub struct RequestMeta {
+    pub request_id: Option<String>,
+    pub source_ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+impl RequestMeta {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            request_id: value("x-request-id"),
+            source_ip: value("x-forwarded-for")
+                .and_then(|v| v.split(',').next().map(|ip| ip.trim().to_string()))
+                .or_else(|| value("x-real-ip")),
+            user_agent: value(header::USER_AGENT.as_str()),
+        }
+    }
+
+    /// Audit metadata with the request fields filled in
+    pub fn audit_metadata(&self) -> AuditMetadata {
+        AuditMetadata {
+            request_id: self.request_id.clone(),
+            source_ip: self.source_ip.clone(),
+            user_agent: self.user_agent.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for RequestMeta {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &AppState) -> Result<Self, Self::Rejection> {
+        Ok(RequestMeta::from_headers(&parts.headers))
+    }
+}
+
+/// Audit failed API access: 401/403 responses are recorded as `Deny`,
+/// 404 and server errors as `Error`, with the caller, route and status
+pub async fn audit_access_failures(
+    State(state): State<AppState>,
+    caller: Caller,
+    req: Request,
+    next: Next,
+) -> Response {
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let meta = RequestMeta::from_headers(req.headers());
+    let response = next.run(req).await;
+
+    let status = response.status();
+    let decision = match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AuditDecision::Deny,
+        StatusCode::NOT_FOUND => AuditDecision::Error,
+        s if s.is_server_error() => AuditDecision::Error,
+        _ => return response,
+    };
+    // Tie the event to an identity when the route names one
+    let identity_id = path
+        .strip_prefix("/identities/")
+        .and_then(|rest| rest.split('/').next())
+        .and_then(|id| id.parse::<Uuid>().ok())
+        .unwrap_or_default();
+    let mut event = IdentityAuditEvent::new(
+        identity_id,
+        "api.access_failed",
+        caller.actor(),
+        &path,
+        decision,
+        Some(format!("{} {} -> {}", method, path, status)),
+    );
+    event.metadata = meta
+        .audit_metadata()
+        .with("method", method.as_str())
+        .with("status", status.as_u16());
+    if let Err(e) = state.append_audit(event).await {
+        warn!("Access failure for {} {} was not audited: {}", method, path, e);
+    }
+    response
+}
+
+#[derive(Serialize)]
+pub struct WhoAmIResponse {
+    pub authenticated: bool,
+    /// Token subject, client-certificate SPIFFE ID, or `anonymous`
+    pub principal: String,
+    /// `bearer`, `client_cert` or `anonymous`
+    pub source: &'static str,
+    pub claims: Option<ZedIdClaims>,
+    /// SPIFFE ID from the mesh's forwarded client certificate, if any
+    pub client_cert_spiffe_id: Option<String>,
+    /// Identity record the principal resolved to
+    pub identity: Option<Identity>,
+    pub effective_trust_level: Option<TrustLevel>,
+    /// Roles from the identity's `role` label
+    pub roles: Vec<String>,
+    /// What the caller is granted beyond anonymous access
+    pub scopes: Vec<&'static str>,
+}
+
+/// `URI=` entry of the first element of an XFCC header
+fn client_cert_spiffe_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(XFCC_HEADER)?
+        .to_str()
+        .ok()?
+        .split(',')
+        .next()?
+        .split(';')
+        .find_map(|kv| kv.trim().strip_prefix("URI="))
+        .map(|uri| uri.trim_matches('"').to_string())
+        .filter(|uri| uri.starts_with("spiffe://"))
+}
+
+/// How ZedID sees the caller: decoded token claims (or the forwarded client
+/// certificate), the identity they resolve to, and what that grants
+pub async fn whoami(
+    State(state): State<AppState>,
+    caller: Caller,
+    headers: HeaderMap,
+) -> Json<WhoAmIResponse> {
+    let cert_id = client_cert_spiffe_id(&headers);
+    let (principal, source) = match (&caller.claims, &cert_id) {
+        (Some(claims), _) => (claims.sub.clone(), "bearer"),
+        (None, Some(spiffe_id)) => (spiffe_id.clone(), "client_cert"),
+        (None, None) => ("anonymous".to_string(), "anonymous"),
+    };
+
+    let identity = if source == "anonymous" {
+        None
+    } else {
+        let spiffe_id = caller
+            .claims
+            .as_ref()
+            .and_then(|c| c.spiffe_id.clone())
+            .or(cert_id.clone())
+            .map(|id| normalize_spiffe_id(&id));
+        state
+            .identities
+            .read()
+            .await
+            .iter()
+            .find(|i| i.id.to_string() == principal || (spiffe_id.is_some() && i.spiffe_id == spiffe_id))
+            .cloned()
+    };
+    let effective_trust_level = identity.as_ref().map(|i| {
+        if state.config.keep_trust_on_svid_expiry {
+            i.trust_level.clone()
+        } else {
+            i.effective_trust_level()
+        }
+    });
+    let roles = identity.as_ref().map(Identity::roles).unwrap_or_default();
+    let mut scopes = vec![];
+    if caller.claims.is_some() {
+        scopes.push("authenticated");
+    }
+    if caller.is_trusted() {
+        scopes.push("decision-details");
+    }
+
+    Json(WhoAmIResponse {
+        authenticated: source != "anonymous",
+        principal,
+        source,
+        claims: caller.claims,
+        client_cert_spiffe_id: cert_id,
+        identity,
+        effective_trust_level,
+        roles,
+        scopes,
+    })
+}
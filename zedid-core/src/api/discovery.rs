@@ -0,0 +1,111 @@
+use crate::state::AppState;
+use axum::{extract::State, http::HeaderMap, routing::get, Json, Router};
+use serde::Serialize;
+use zedid_identity::jwt::{PublishedKey, ISSUED_CLAIMS};
+
+/// OIDC discovery metadata. Only what ZedID implements is advertised: there
+/// is no authorization endpoint, and tokens are issued per identity rather
+/// than at a fixed token endpoint, so no response types or grant flows.
+#[derive(Debug, Serialize)]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    pub jwks_uri: String,
+    pub introspection_endpoint: String,
+    /// Required by OIDC discovery; empty because no flow returns tokens
+    pub response_types_supported: Vec<&'static str>,
+    pub grant_types_supported: Vec<&'static str>,
+    pub subject_types_supported: Vec<&'static str>,
+    pub id_token_signing_alg_values_supported: Vec<&'static str>,
+    pub claims_supported: Vec<&'static str>,
+}
+
+/// JSON Web Key Set: every key that currently verifies tokens, by `kid`.
+/// Keys are HMAC secrets and their material is never published: verify
+/// tokens by introspection.
+#[derive(Debug, Serialize)]
+pub struct JwkSet {
+    pub keys: Vec<PublishedKey>,
+}
+
+/// Routes served at the root, outside `/api/v1`, where OIDC clients look
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/.well-known/openid-configuration", get(openid_configuration))
+        .route("/.well-known/jwks.json", get(jwks))
+}
+
+pub async fn openid_configuration(State(state): State<AppState>, headers: HeaderMap) -> Json<OpenIdConfiguration> {
+    let base = base_url(&headers, state.config.port);
+    Json(OpenIdConfiguration {
+        issuer: state.config.jwt_issuer.clone(),
+        jwks_uri: format!("{}/.well-known/jwks.json", base),
+        introspection_endpoint: format!("{}/api/v1/token/introspect", base),
+        response_types_supported: Vec::new(),
+        grant_types_supported: Vec::new(),
+        subject_types_supported: vec!["public"],
+        id_token_signing_alg_values_supported: vec!["HS256"],
+        claims_supported: ISSUED_CLAIMS.to_vec(),
+    })
+}
+
+pub async fn jwks(State(state): State<AppState>) -> Json<JwkSet> {
+    Json(JwkSet { keys: state.jwt_service.published_keys() })
+}
+
+/// External base URL from `X-Forwarded-Proto`/`X-Forwarded-Host` (set by a
+/// proxy) or `Host`. A missing or malformed host falls back to localhost.
+fn base_url(headers: &HeaderMap, port: u16) -> String {
+    let value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let scheme = match value("x-forwarded-proto") {
+        Some("https") => "https",
+        _ => "http",
+    };
+    let host = value("x-forwarded-host")
+        .or_else(|| value("host"))
+        .filter(|h| h.chars().all(|c| c.is_ascii_alphanumeric() || "-.:[]".contains(c)))
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("localhost:{}", port));
+    format!("{}://{}", scheme, host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    #[tokio::test]
+    async fn test_discovery_document_uses_request_host() {
+        let mut config = AppConfig::load().unwrap();
+        config.jwt_issuer = "https://zedid.example.com".to_string();
+        let state = AppState::new(config).await.unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("host", "10.0.0.5:8080".parse().unwrap());
+        headers.insert("x-forwarded-host", "zedid.example.com".parse().unwrap());
+        headers.insert("x-forwarded-proto", "https".parse().unwrap());
+        let Json(doc) = openid_configuration(State(state.clone()), headers).await;
+        assert_eq!(doc.issuer, "https://zedid.example.com");
+        assert_eq!(doc.jwks_uri, "https://zedid.example.com/.well-known/jwks.json");
+        assert_eq!(doc.introspection_endpoint, "https://zedid.example.com/api/v1/token/introspect");
+        let json = serde_json::to_value(&doc).unwrap();
+        assert!(json.get("token_endpoint").is_none());
+        assert_eq!(json["response_types_supported"], serde_json::json!([]));
+        assert_eq!(json["subject_types_supported"], serde_json::json!(["public"]));
+        assert!(doc.claims_supported.contains(&"trust_level"));
+        let Json(set) = jwks(State(state.clone())).await;
+        assert_eq!(set.keys.len(), 1);
+        assert_eq!(set.keys[0].kid, state.jwt_service.signing_kid());
+
+        let mut spoofed = HeaderMap::new();
+        spoofed.insert("host", "evil.example/<script>".parse().unwrap());
+        let Json(doc) = openid_configuration(State(state), spoofed).await;
+        assert!(doc.introspection_endpoint.starts_with("http://localhost:"));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use uuid::Uuid;
+use zedid_policy::models::{
+    CreatePolicyGroupRequest, Policy, PolicyGroup, PolicyStatus, UpdateGroupMembersRequest,
+};
+use super::{auth::{Caller, RequestMeta}, policies::audit_policy_changes, policy_error};
+
+pub async fn list_groups(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let groups = state.policy_engine.list_groups().await;
+    Json(serde_json::json!({
+        "total": groups.len(),
+        "groups": groups,
+    }))
+}
+
+pub async fn get_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PolicyGroup>, StatusCode> {
+    state
+        .policy_engine
+        .get_group(id)
+        .await
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+pub async fn create_group(
+    State(state): State<AppState>,
+    Json(req): Json<CreatePolicyGroupRequest>,
+) -> Result<Json<PolicyGroup>, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .create_group(PolicyGroup::new(&req.name, &req.description, req.policy_ids))
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+pub async fn update_members(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateGroupMembersRequest>,
+) -> Result<Json<PolicyGroup>, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .update_group_members(id, req.add, req.remove)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+pub async fn activate_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
+) -> Result<Json<Vec<Policy>>, (StatusCode, Json<serde_json::Value>)> {
+    set_group_status(&state, id, PolicyStatus::Active, &caller, &meta).await.map(Json)
+}
+
+pub async fn disable_group(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
+) -> Result<Json<Vec<Policy>>, (StatusCode, Json<serde_json::Value>)> {
+    set_group_status(&state, id, PolicyStatus::Disabled, &caller, &meta).await.map(Json)
+}
+
+/// Move a group's members to `status`, auditing each member that changed
+async fn set_group_status(
+    state: &AppState,
+    id: Uuid,
+    status: PolicyStatus,
+    caller: &Caller,
+    meta: &RequestMeta,
+) -> Result<Vec<Policy>, (StatusCode, Json<serde_json::Value>)> {
+    let mut before = vec![];
+    for member in state.policy_engine.get_group(id).await.map(|g| g.policy_ids).unwrap_or_default() {
+        before.extend(state.policy_engine.get_policy(member).await);
+    }
+    let changed = state
+        .policy_engine
+        .update_group_status(id, status)
+        .await
+        .map_err(policy_error)?;
+    audit_policy_changes(state, caller, meta, &before).await;
+    Ok(changed)
+}
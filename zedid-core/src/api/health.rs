@@ -1,12 +1,16 @@
 use crate::state::AppState;
 use axum::{extract::State, Json};
 use serde::Serialize;
+use zedid_policy::tars::{BreakerState, BreakerStatus};
 
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: &'static str,
     pub service: &'static str,
     pub version: &'static str,
+    /// Live TARS circuit breaker; generation is degraded while it is not closed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tars_breaker: Option<BreakerStatus>,
     pub timestamp: String,
 }
 
@@ -17,20 +21,119 @@ pub struct SystemInfoResponse {
     pub trust_domain: String,
     pub tars_endpoint: String,
     pub tars_mode: String,
-    pub capabilities: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tars_breaker: Option<BreakerStatus>,
+    pub capabilities: Vec<Capability>,
+    /// Standards implemented by live capabilities
     pub standards: Vec<&'static str>,
     pub timestamp: String,
 }
 
-pub async fn health_check() -> Json<HealthResponse> {
+/// Always 200: decisions keep working while TARS is down, so an open
+/// breaker only reports `degraded`
+pub async fn health_check(State(state): State<AppState>) -> Json<HealthResponse> {
+    let tars_breaker = state.policy_generator.tars().breaker_status();
+    let degraded = tars_breaker.as_ref().is_some_and(|b| b.state != BreakerState::Closed);
     Json(HealthResponse {
-        status: "healthy",
+        status: if degraded { "degraded" } else { "healthy" },
         service: "ZedID",
         version: env!("CARGO_PKG_VERSION"),
+        tars_breaker,
         timestamp: chrono::Utc::now().to_rfc3339(),
     })
 }
 
+/// How a capability is backed in this running instance
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CapabilityMode {
+    /// Real implementation in use
+    Live,
+    /// Answers are produced by a stand-in (demo mode, mock data)
+    Simulated,
+    /// Not configured, or bypassed by another setting
+    Disabled,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Capability {
+    pub name: &'static str,
+    pub mode: CapabilityMode,
+    /// What backs the capability, or what enabling it takes
+    pub detail: String,
+    pub standards: Vec<&'static str>,
+}
+
+impl Capability {
+    fn new(name: &'static str, mode: CapabilityMode, detail: impl Into<String>, standards: &[&'static str]) -> Self {
+        Self { name, mode, detail: detail.into(), standards: standards.to_vec() }
+    }
+}
+
+/// Capabilities as configured: the LLM, OPA and optional features are read
+/// from the running state rather than assumed
+fn capabilities(state: &AppState) -> Vec<Capability> {
+    use CapabilityMode::{Disabled, Live, Simulated};
+    let config = &state.config;
+
+    let llm = if state.policy_generator.tars().is_simulated() {
+        (Simulated, "Template output; no LLM is called (set TARS_ENDPOINT and TARS_API_KEY)".to_string())
+    } else {
+        (Live, format!("Generated by the LLM behind {}", config.tars_endpoint))
+    };
+    // With OPA configured, every decision is made there and the local evaluators are bypassed
+    let local = |mode: CapabilityMode, detail: &str| match &config.opa_url {
+        Some(_) => (Disabled, "Decisions are delegated to OPA (ZEDID_OPA_URL)".to_string()),
+        None => (mode, detail.to_string()),
+    };
+    let rego = match &config.opa_url {
+        Some(url) => (Live, format!("Delegated to OPA at {}", url)),
+        None => (Simulated, "Matched on policy subjects, resources and actions; Rego is not executed (set ZEDID_OPA_URL)".to_string()),
+    };
+    let cedar = local(Simulated, "Matched on policy subjects, resources and actions; Cedar is not executed");
+    let istio = local(Live, "Evaluated natively");
+    let rbac = local(Live, "Evaluated natively");
+    let decision_log = match &config.decision_log_file {
+        Some(path) => (Live, format!("OPA-format JSON lines appended to {}", path)),
+        None => (Disabled, "Set ZEDID_DECISION_LOG_FILE".to_string()),
+    };
+    let git = match state.policy_git.as_ref().map(|git| git.status()) {
+        Some(status) => (Live, format!("{} ({})", status.url, status.branch)),
+        None => (Disabled, "Set ZEDID_POLICY_GIT_URL".to_string()),
+    };
+    let demo_reset = if config.enable_demo_reset {
+        (Live, "POST /admin/reset-demo".to_string())
+    } else {
+        (Disabled, "Set ZEDID_ENABLE_DEMO_RESET=true".to_string())
+    };
+
+    vec![
+        Capability::new("spiffe-id-management", Live, format!("Trust domain {}", config.trust_domain), &["SPIFFE"]),
+        Capability::new(
+            "spiffe-svid-issuance",
+            Simulated,
+            format!("Mock certificates with real {} keys; no SPIRE agent is contacted", state.spire_client.key_algorithm),
+            &["SPIFFE/SPIRE"],
+        ),
+        Capability::new("jwt-identity-tokens", Live, format!("HS256 tokens issued by {}", config.jwt_issuer), &["OAuth2/OIDC"]),
+        Capability::new("oidc-discovery", Live, "/.well-known/openid-configuration", &["OAuth2/OIDC"]),
+        Capability::new("client-certificate-identity", Live, "SPIFFE IDs from Envoy's X-Forwarded-Client-Cert", &["mTLS"]),
+        Capability::new("opa-policy-evaluation", rego.0, rego.1, &["OPA/Rego"]),
+        Capability::new("cedar-policy-evaluation", cedar.0, cedar.1, &["AWS Cedar"]),
+        Capability::new("istio-authz-evaluation", istio.0, istio.1, &["Istio AuthorizationPolicy"]),
+        Capability::new("rbac-yaml-evaluation", rbac.0, rbac.1, &[]),
+        Capability::new("rego-policy-generation", llm.0, llm.1.clone(), &["OPA/Rego"]),
+        Capability::new("cedar-policy-generation", llm.0, llm.1.clone(), &["AWS Cedar"]),
+        Capability::new("istio-authz-generation", llm.0, llm.1.clone(), &["Istio AuthorizationPolicy"]),
+        Capability::new("tars-llm-routing", llm.0, llm.1, &[]),
+        Capability::new("zero-trust-enforcement", Live, "Deny by default, per-namespace enforcement modes", &["NIST SP 800-207 (Zero Trust)"]),
+        Capability::new("audit-logging", Live, "In-memory, hash-chained on retention", &[]),
+        Capability::new("decision-log-export", decision_log.0, decision_log.1, &[]),
+        Capability::new("git-policy-sync", git.0, git.1, &[]),
+        Capability::new("demo-reset", demo_reset.0, demo_reset.1, &[]),
+    ]
+}
+
 pub async fn system_info(State(state): State<AppState>) -> Json<SystemInfoResponse> {
     let tars_mode = if state.config.tars_endpoint.contains("simulation") {
         "simulation (demo mode)"
@@ -40,32 +143,63 @@ pub async fn system_info(State(state): State<AppState>) -> Json<SystemInfoRespon
         "live-tars"
     };
 
+    let capabilities = capabilities(&state);
+    let mut standards: Vec<&'static str> = vec![];
+    for standard in capabilities.iter().filter(|c| c.mode == CapabilityMode::Live).flat_map(|c| &c.standards) {
+        if !standards.contains(standard) {
+            standards.push(standard);
+        }
+    }
+
     Json(SystemInfoResponse {
         service: "ZedID — Identity Dashboard & Policy Generator",
         version: env!("CARGO_PKG_VERSION"),
         trust_domain: state.config.trust_domain.clone(),
         tars_endpoint: state.config.tars_endpoint.clone(),
         tars_mode: tars_mode.to_string(),
-        capabilities: vec![
-            "spiffe-svid-issuance",
-            "jwt-identity-tokens",
-            "rego-policy-generation",
-            "cedar-policy-generation",
-            "istio-authz-generation",
-            "opa-policy-evaluation",
-            "zero-trust-enforcement",
-            "audit-logging",
-            "tars-llm-routing",
-        ],
-        standards: vec![
-            "SPIFFE/SPIRE",
-            "NIST SP 800-207 (Zero Trust)",
-            "OAuth2/OIDC",
-            "OPA/Rego",
-            "AWS Cedar",
-            "Istio AuthorizationPolicy",
-            "mTLS",
-        ],
+        tars_breaker: state.policy_generator.tars().breaker_status(),
+        capabilities,
+        standards,
         timestamp: chrono::Utc::now().to_rfc3339(),
     })
 }
+
+/// Counter snapshot for scraping and dashboards
+pub async fn metrics(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "counters": state.metrics.snapshot(),
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    #[tokio::test]
+    async fn test_capabilities_follow_configuration() {
+        let mode = |info: &SystemInfoResponse, name: &str| {
+            info.capabilities.iter().find(|c| c.name == name).map(|c| c.mode).unwrap()
+        };
+
+        let mut config = AppConfig::load().unwrap();
+        config.tars_endpoint = "simulation://tars.tetrate.io".to_string();
+        config.opa_url = None;
+        config.decision_log_file = None;
+        let Json(demo) = system_info(State(AppState::new(config.clone()).await.unwrap())).await;
+        assert_eq!(mode(&demo, "opa-policy-evaluation"), CapabilityMode::Simulated);
+        assert_eq!(mode(&demo, "cedar-policy-generation"), CapabilityMode::Simulated);
+        assert_eq!(mode(&demo, "spiffe-svid-issuance"), CapabilityMode::Simulated);
+        assert_eq!(mode(&demo, "istio-authz-evaluation"), CapabilityMode::Live);
+        assert_eq!(mode(&demo, "decision-log-export"), CapabilityMode::Disabled);
+        assert!(demo.standards.contains(&"OAuth2/OIDC"));
+        assert!(!demo.standards.contains(&"OPA/Rego") && !demo.standards.contains(&"AWS Cedar"));
+
+        config.opa_url = Some("http://opa.policy-system:8181".to_string());
+        let Json(opa) = system_info(State(AppState::new(config).await.unwrap())).await;
+        assert_eq!(mode(&opa, "opa-policy-evaluation"), CapabilityMode::Live);
+        assert_eq!(mode(&opa, "istio-authz-evaluation"), CapabilityMode::Disabled);
+        assert!(opa.standards.contains(&"OPA/Rego"));
+    }
+}
@@ -1,77 +1,371 @@
 use crate::state::AppState;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 use zedid_identity::{
-    AuditDecision, CreateIdentityRequest, CreateIdentityResponse, Identity, IdentityAuditEvent,
-    IdentityKind,
+    AuditDecision, AuditMetadata, CreateIdentityRequest, CreateIdentityResponse, Identity, IdentityAuditEvent,
+    IdentityError, IdentityKind, RenewLeaseRequest, TrustLevel,
 };
+use zedid_policy::models::{DecisionContextInput, GoverningPolicy, PolicyDecisionRequest, PolicyEffect, PolicyStatus};
 use tracing::{info, warn}; // warn used for SVID issuance failures
+use super::auth::RequestMeta;
+use super::namespaces::ensure_namespace;
+use super::pagination::paginate;
+
+/// Most labels accepted on a new identity
+const MAX_IDENTITY_LABELS: usize = 64;
+const MAX_LABEL_KEY_LEN: usize = 128;
+const MAX_LABEL_VALUE_LEN: usize = 256;
+/// Label prefix set by ZedID itself; clients may not assign it
+const RESERVED_LABEL_PREFIX: &str = "zedid.io/";
+/// Longest lease a client may request (one year)
+const MAX_LEASE_MINUTES: i64 = 365 * 24 * 60;
 
 #[derive(Serialize)]
 pub struct IdentityListResponse {
     pub identities: Vec<Identity>,
+    /// Matching identities across all pages
     pub total: usize,
     pub trust_domain: String,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct IdentityListQuery {
+    /// Only identities not seen within this many days
+    pub stale_days: Option<i64>,
+    /// Opaque cursor from a previous page's `next_cursor`
+    pub cursor: Option<String>,
+    /// Page size (default 100, max 1000)
+    pub limit: Option<usize>,
+    /// Deprecated: unstable under concurrent inserts, use `cursor`
+    pub offset: Option<usize>,
 }
 
-pub async fn list_identities(State(state): State<AppState>) -> Json<IdentityListResponse> {
+/// Identities oldest first, paged by (created_at, id)
+pub async fn list_identities(
+    State(state): State<AppState>,
+    Query(query): Query<IdentityListQuery>,
+) -> Result<Json<IdentityListResponse>, (StatusCode, Json<serde_json::Value>)> {
     let identities = state.identities.read().await;
+    let now = chrono::Utc::now();
+    let mut identities: Vec<Identity> = identities
+        .iter()
+        .filter(|i| {
+            query
+                .stale_days
+                .is_none_or(|days| now - i.last_seen > chrono::Duration::days(days))
+        })
+        .cloned()
+        .collect();
+    identities.sort_by_key(|i| (i.created_at, i.id));
     let total = identities.len();
-    Json(IdentityListResponse {
-        identities: identities.clone(),
+    let (identities, next_cursor) = paginate(
+        identities,
+        |i| (i.created_at, i.id),
+        false,
+        query.cursor.as_deref(),
+        query.offset,
+        query.limit,
+    )?;
+    Ok(Json(IdentityListResponse {
+        identities,
         total,
         trust_domain: state.config.trust_domain.clone(),
-    })
+        next_cursor,
+    }))
+}
+
+#[derive(Serialize)]
+pub struct IdentityPoliciesResponse {
+    pub identity_id: Uuid,
+    pub spiffe_id: Option<String>,
+    pub roles: Vec<String>,
+    /// Governing policies by effect, then by status
+    pub allow: BTreeMap<PolicyStatus, Vec<GoverningPolicy>>,
+    pub deny: BTreeMap<PolicyStatus, Vec<GoverningPolicy>>,
+    pub total: usize,
+}
+
+/// Reverse lookup: every policy whose subjects select this identity
+pub async fn identity_policies(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<IdentityPoliciesResponse>, StatusCode> {
+    let identity = state
+        .identities
+        .read()
+        .await
+        .iter()
+        .find(|i| i.id == id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let policies = state.policy_engine.policies_for_identity(&identity).await;
+    let total = policies.len();
+    let (mut allow, mut deny) = (BTreeMap::new(), BTreeMap::new());
+    for policy in policies {
+        let group = if policy.effect == PolicyEffect::Allow { &mut allow } else { &mut deny };
+        group.entry(policy.status.clone()).or_insert_with(Vec::new).push(policy);
+    }
+    Ok(Json(IdentityPoliciesResponse {
+        identity_id: identity.id,
+        roles: identity.roles(),
+        spiffe_id: identity.spiffe_id,
+        allow,
+        deny,
+        total,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct TrustLevelQuery {
+    /// SPIFFE IDs or identity UUIDs
+    pub subjects: Vec<String>,
+}
+
+/// Batch trust-level lookup. Every requested subject appears in `trust_levels`;
+/// unknown ones map to `null` and are also listed in `unknown` so callers can fail closed.
+pub async fn trust_levels(
+    State(state): State<AppState>,
+    Json(req): Json<TrustLevelQuery>,
+) -> Json<serde_json::Value> {
+    let identities = state.identities.read().await;
+    let mut levels = serde_json::Map::new();
+    let mut unknown = vec![];
+    for subject in &req.subjects {
+        let level = identities
+            .iter()
+            .find(|i| i.spiffe_id.as_deref() == Some(subject.as_str()) || i.id.to_string() == *subject)
+            .map(|i| i.trust_level.clone());
+        if level.is_none() {
+            unknown.push(subject.clone());
+        }
+        levels.insert(subject.clone(), serde_json::json!(level));
+    }
+    Json(serde_json::json!({
+        "trust_levels": levels,
+        "unknown": unknown,
+        "total": req.subjects.len(),
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct ExpiringQuery {
+    /// Look-ahead window (defaults to `ZEDID_SVID_EXPIRY_ALERT_MINUTES`)
+    pub within_minutes: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct ExpiringIdentity {
+    pub identity_id: Uuid,
+    pub name: String,
+    pub namespace: String,
+    pub spiffe_id: Option<String>,
+    pub svid_expiry: chrono::DateTime<chrono::Utc>,
+    /// Negative once the SVID has lapsed
+    pub seconds_remaining: i64,
+}
+
+/// Identities whose SVID expires within the window, soonest first
+pub async fn expiring_identities(
+    State(state): State<AppState>,
+    Query(query): Query<ExpiringQuery>,
+) -> Json<serde_json::Value> {
+    let within = query
+        .within_minutes
+        .unwrap_or(state.config.svid_expiry_alert_minutes);
+    let now = chrono::Utc::now();
+    let identities = state.identities.read().await;
+    let mut expiring: Vec<ExpiringIdentity> = identities
+        .iter()
+        .filter(|i| i.svid_expires_within(now, chrono::Duration::minutes(within)))
+        .filter_map(|i| {
+            let expiry = i.svid_expiry?;
+            Some(ExpiringIdentity {
+                identity_id: i.id,
+                name: i.name.clone(),
+                namespace: i.namespace.clone(),
+                spiffe_id: i.spiffe_id.clone(),
+                svid_expiry: expiry,
+                seconds_remaining: (expiry - now).num_seconds(),
+            })
+        })
+        .collect();
+    expiring.sort_by_key(|e| e.svid_expiry);
+    Json(serde_json::json!({
+        "within_minutes": within,
+        "total": expiring.len(),
+        "identities": expiring,
+    }))
+}
+
+#[derive(Serialize)]
+pub struct IdentityDetail {
+    #[serde(flatten)]
+    pub identity: Identity,
+    /// Trust level used for policy evaluation; lower than the nominal
+    /// `trust_level` while the SVID has lapsed
+    pub effective_trust_level: TrustLevel,
+    pub svid_valid: bool,
 }
 
 pub async fn get_identity(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<Identity>, StatusCode> {
+) -> Result<Json<IdentityDetail>, StatusCode> {
     let identities = state.identities.read().await;
-    identities
+    let identity = identities
         .iter()
         .find(|i| i.id == id)
         .cloned()
-        .map(Json)
-        .ok_or(StatusCode::NOT_FOUND)
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let effective_trust_level = if state.config.keep_trust_on_svid_expiry {
+        identity.trust_level.clone()
+    } else {
+        identity.effective_trust_level()
+    };
+    Ok(Json(IdentityDetail {
+        svid_valid: identity.is_svid_valid(),
+        effective_trust_level,
+        identity,
+    }))
+}
+
+#[derive(Deserialize)]
+pub struct CreateIdentityQuery {
+    /// Validate and preview the identity without storing it or issuing an SVID
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Skip the uniqueness check on (SPIFFE path kind, namespace, name)
+    #[serde(default)]
+    pub allow_duplicate: bool,
+}
+
+/// 409 pointing at an active identity that `candidate` would duplicate
+fn validate_labels(labels: &std::collections::HashMap<String, String>) -> Result<(), String> {
+    if labels.len() > MAX_IDENTITY_LABELS {
+        return Err(format!("Too many labels (max {})", MAX_IDENTITY_LABELS));
+    }
+    for (key, value) in labels {
+        if key.trim().is_empty() {
+            return Err("Label keys cannot be empty".to_string());
+        }
+        if key.len() > MAX_LABEL_KEY_LEN {
+            return Err(format!("Label key '{}' exceeds {} characters", key, MAX_LABEL_KEY_LEN));
+        }
+        if value.len() > MAX_LABEL_VALUE_LEN {
+            return Err(format!("Value of label '{}' exceeds {} characters", key, MAX_LABEL_VALUE_LEN));
+        }
+        if key.starts_with(RESERVED_LABEL_PREFIX) {
+            return Err(format!("Label prefix '{}' is reserved", RESERVED_LABEL_PREFIX));
+        }
+    }
+    Ok(())
+}
+
+fn check_duplicate(
+    identities: &[Identity],
+    candidate: &Identity,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    match identities.iter().find(|i| i.is_active && i.is_duplicate_of(candidate)) {
+        Some(existing) => Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!(
+                    "Identity '{}' already exists in namespace '{}'",
+                    existing.name, existing.namespace
+                ),
+                "existing_id": existing.id,
+            })),
+        )),
+        None => Ok(()),
+    }
 }
 
 pub async fn create_identity(
     State(state): State<AppState>,
+    meta: RequestMeta,
+    Query(query): Query<CreateIdentityQuery>,
     Json(req): Json<CreateIdentityRequest>,
-) -> Result<Json<CreateIdentityResponse>, StatusCode> {
+) -> Result<Json<CreateIdentityResponse>, (StatusCode, Json<serde_json::Value>)> {
     info!("Creating identity: {} ({:?})", req.name, req.kind);
+    if req.name.trim().is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({"error": "Identity name cannot be empty"})),
+        ));
+    }
+    if let Some(labels) = &req.labels {
+        validate_labels(labels).map_err(|e| {
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(serde_json::json!({"error": e})))
+        })?;
+    }
+    let namespace = if req.namespace.is_empty() {
+        state.config.default_namespace.clone()
+    } else {
+        req.namespace
+    };
+    ensure_namespace(&state, &namespace).await?;
 
-    let identity = match req.kind {
-        IdentityKind::Workload => {
-            Identity::new_workload(&req.name, &req.namespace, &state.config.trust_domain)
-        }
-        IdentityKind::Human => {
-            let email = req.email.unwrap_or_else(|| format!("{}@{}", req.name, state.config.trust_domain));
-            Identity::new_human(&req.name, &email, &req.namespace)
-        }
-        IdentityKind::AiAgent => {
-            Identity::new_ai_agent(&req.name, &req.namespace, &state.config.trust_domain)
+    let paths = match req.trust_domain.as_deref() {
+        None => state.spiffe_paths.as_ref().clone(),
+        Some(domain) if domain == state.config.trust_domain || state.config.trust_domains.iter().any(|d| d == domain) => {
+            state.spiffe_paths.for_trust_domain(domain)
         }
-        IdentityKind::ServiceAccount => {
-            Identity::new_workload(&req.name, &req.namespace, &state.config.trust_domain)
+        Some(domain) => {
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({
+                    "error": format!("Trust domain '{}' is not in ZEDID_TRUST_DOMAINS", domain),
+                })),
+            ))
         }
     };
+    let mut builder = paths
+        .builder(req.kind.clone(), &req.name, &namespace)
+        .map_err(|e| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+        })?;
+    if req.kind == IdentityKind::Human {
+        let email = req.email.unwrap_or_else(|| format!("{}@{}", req.name, paths.trust_domain()));
+        builder = builder.email(&email);
+    }
+    let svid_ttl = state.config.svid_ttl(&req.kind);
+    if let Some(lease) = lease_length(&state, &req.kind, req.lease_minutes)? {
+        builder = builder.lease(lease);
+    }
+    let mut identity = builder.labels(req.labels.unwrap_or_default()).svid_ttl(svid_ttl).build();
+
+    if !query.allow_duplicate {
+        check_duplicate(&state.identities.read().await, &identity)?;
+    }
+
+    if query.dry_run {
+        return Ok(Json(CreateIdentityResponse {
+            message: format!("Dry run: identity '{}' is valid and was not created", identity.name),
+            svid: None,
+            identity,
+            dry_run: true,
+        }));
+    }
 
     // Issue SVID for workload identities
-    let svid = if identity.spiffe_id.is_some() {
-        match state
-            .spire_client
-            .issue_svid(identity.spiffe_id.as_ref().unwrap(), 1)
-            .await
-        {
-            Ok(svid) => Some(svid),
+    let svid = if let Some(spiffe_id) = identity.spiffe_id.clone() {
+        match state.spire_client.issue_svid(&spiffe_id, svid_ttl.num_hours()).await {
+            Ok(svid) => {
+                state.record_svid(identity.id, &svid).await;
+                identity.svid_expiry = Some(svid.expires_at);
+                Some(svid)
+            }
             Err(e) => {
                 warn!("SVID issuance failed: {}", e);
                 None
@@ -81,8 +375,16 @@ pub async fn create_identity(
         None
     };
 
+    // Re-check under the write lock: a concurrent create may have won the race
+    let mut identities = state.identities.write().await;
+    if !query.allow_duplicate {
+        check_duplicate(&identities, &identity)?;
+    }
+    identities.push(identity.clone());
+    drop(identities);
+
     // Record audit event
-    let audit = IdentityAuditEvent::new(
+    let mut audit = IdentityAuditEvent::new(
         identity.id,
         "identity.create",
         "zedid-api",
@@ -90,17 +392,16 @@ pub async fn create_identity(
         AuditDecision::Allow,
         Some(format!("Identity created: {} ({:?})", identity.name, identity.kind)),
     );
+    audit.metadata = meta.audit_metadata();
 
     let mut audit_log = state.audit_log.write().await;
     audit_log.push(audit);
 
-    let mut identities = state.identities.write().await;
-    identities.push(identity.clone());
-
     Ok(Json(CreateIdentityResponse {
         message: format!("Identity '{}' created successfully", identity.name),
         svid,
         identity,
+        dry_run: false,
     }))
 }
 
@@ -126,16 +427,18 @@ pub async fn get_svid(
             )
         })?;
 
-    let spiffe_id = identity.spiffe_id.as_ref().ok_or_else(|| {
+    let spiffe_id = identity.spiffe_id.clone().ok_or_else(|| {
         (
             StatusCode::BAD_REQUEST,
             Json(serde_json::json!({"error": "Identity does not have a SPIFFE ID (human identities use JWT tokens)"})),
         )
     })?;
+    let ttl_hours = state.config.svid_ttl(&identity.kind).num_hours();
+    drop(identities);
 
     let svid = state
         .spire_client
-        .issue_svid(spiffe_id, 1)
+        .issue_svid(&spiffe_id, ttl_hours)
         .await
         .map_err(|e| {
             (
@@ -144,9 +447,16 @@ pub async fn get_svid(
             )
         })?;
 
+    // Re-issuance renews the identity's current SVID
+    state.record_svid(id, &svid).await;
+    if let Some(identity) = state.identities.write().await.iter_mut().find(|i| i.id == id) {
+        identity.svid_expiry = Some(svid.expires_at);
+        identity.last_seen = chrono::Utc::now();
+    }
+
     Ok(Json(SvidResponse {
         identity_id: id,
-        spiffe_id: spiffe_id.clone(),
+        spiffe_id,
         svid,
     }))
 }
@@ -154,6 +464,20 @@ pub async fn get_svid(
 #[derive(Deserialize)]
 pub struct IssueTokenRequest {
     pub ttl_minutes: Option<i64>,
+    /// Post-date the token: it becomes valid (`nbf`) this many minutes from
+    /// now, and its TTL counts from then
+    #[serde(default)]
+    pub not_before_minutes: i64,
+    /// Token audience (defaults to `zedid-api`)
+    pub audience: Option<String>,
+    /// Custom claims added to the token, on top of (and overriding) the
+    /// identity's `claim.<name>` labels
+    #[serde(default)]
+    pub extra_claims: HashMap<String, serde_json::Value>,
+    /// Decision context for the `token.issue` evaluation (e.g. `mfa_verified`),
+    /// subject to the namespace's context allowlist
+    #[serde(default)]
+    pub context: DecisionContextInput,
 }
 
 #[derive(Serialize)]
@@ -164,15 +488,86 @@ pub struct TokenResponse {
     pub kind: String,
 }
 
+/// Policy action asked about before a token is issued, with
+/// `ZEDID_GATE_TOKEN_ISSUANCE`
+pub const TOKEN_ISSUE_ACTION: &str = "token.issue";
+
+/// With `ZEDID_GATE_TOKEN_ISSUANCE`, evaluate whether `identity` may be
+/// issued a token: the subject is the identity, the action `token.issue` and
+/// the resource its trust level (`critical`, `high`, ...), with the caller's
+/// `context`. Every decision is audited as `token.issue`. Returns the deny
+/// reason when issuance is refused; an evaluation error, or with
+/// `ZEDID_AUDIT_REQUIRED` an unaudited decision, also refuses it. The gate
+/// always enforces: a namespace in monitor or dry-run mode does not relax it.
+pub(crate) async fn authorize_token_issue(
+    state: &AppState,
+    identity: &Identity,
+    context: DecisionContextInput,
+    meta: &RequestMeta,
+) -> Result<(), String> {
+    if !state.config.gate_token_issuance {
+        return Ok(());
+    }
+    let req = PolicyDecisionRequest {
+        subject: identity.spiffe_id.clone().unwrap_or_else(|| identity.id.to_string()),
+        resource: identity.trust_level.as_str().to_string(),
+        action: TOKEN_ISSUE_ACTION.to_string(),
+        namespace: identity.namespace.clone(),
+        context,
+        skip_enrichment: false,
+        group: None,
+        subject_token: None,
+        identity_snapshot: None,
+        identity: None,
+    };
+    let decision = state
+        .policy_engine
+        .evaluate(&req)
+        .await
+        .map_err(|e| format!("Token issuance could not be evaluated — fail closed: {}", e))?;
+    let allowed = decision.allowed && !decision.would_deny;
+
+    let mut audit = IdentityAuditEvent::new(
+        identity.id,
+        TOKEN_ISSUE_ACTION,
+        &req.subject,
+        &req.resource,
+        if allowed { AuditDecision::Allow } else { AuditDecision::Deny },
+        Some(decision.reason.clone()),
+    );
+    audit.metadata = AuditMetadata {
+        policy_id: decision.policy_id,
+        decision_id: Some(decision.decision_id),
+        ..meta.audit_metadata()
+    }
+    .with("namespace", req.namespace.as_str());
+    if let Err(e) = state.append_audit(audit).await {
+        warn!("Token issuance decision for {} was not audited: {}", identity.id, e);
+        if state.config.audit_required {
+            return Err("Token issuance could not be audited — fail closed".to_string());
+        }
+    }
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(decision.reason)
+    }
+}
+
 pub async fn issue_token(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    meta: RequestMeta,
     Json(req): Json<IssueTokenRequest>,
 ) -> Result<Json<TokenResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let identities = state.identities.read().await;
-    let identity = identities
+    let identity = state
+        .identities
+        .read()
+        .await
         .iter()
         .find(|i| i.id == id)
+        .cloned()
         .ok_or_else(|| {
             (
                 StatusCode::NOT_FOUND,
@@ -180,18 +575,34 @@ pub async fn issue_token(
             )
         })?;
 
+    if !identity.is_live(chrono::Utc::now()) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "Identity is inactive or its lease has expired"})),
+        ));
+    }
+    // Evaluated without the identity lock held: enrichment reads the store
+    authorize_token_issue(&state, &identity, req.context, &meta)
+        .await
+        .map_err(|reason| (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": reason}))))?;
     let ttl = req.ttl_minutes.unwrap_or(60);
-    let trust_level = match identity.trust_level {
-        zedid_identity::TrustLevel::Untrusted => 0,
-        zedid_identity::TrustLevel::Low => 1,
-        zedid_identity::TrustLevel::Medium => 2,
-        zedid_identity::TrustLevel::High => 3,
-        zedid_identity::TrustLevel::Critical => 4,
-    };
+    let expires_in_seconds = req
+        .not_before_minutes
+        .checked_add(ttl)
+        .and_then(|minutes| minutes.checked_mul(60))
+        .ok_or_else(|| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({"error": "Token lifetime is out of range"})),
+            )
+        })?;
+    let trust_level = identity.trust_level.as_u8();
+    let mut extra = identity.label_claims();
+    extra.extend(req.extra_claims);
 
     let token = state
         .jwt_service
-        .issue_token(
+        .issue_token_with_claims(
             &identity.id.to_string(),
             &identity.name,
             &identity.namespace,
@@ -199,18 +610,385 @@ pub async fn issue_token(
             trust_level,
             identity.spiffe_id.clone(),
             ttl,
+            req.not_before_minutes,
+            req.audience.as_deref(),
+            extra,
         )
         .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": e.to_string()})),
-            )
+            let status = match e {
+                IdentityError::ReservedClaim(_) | IdentityError::InvalidTokenLifetime(_) => {
+                    StatusCode::UNPROCESSABLE_ENTITY
+                }
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, Json(serde_json::json!({"error": e.to_string()})))
         })?;
 
+    info!(
+        actor = %identity.id,
+        identity = %identity.name,
+        namespace = %identity.namespace,
+        trust_level,
+        ttl_minutes = ttl,
+        not_before_minutes = req.not_before_minutes,
+        "Token issued"
+    );
+    let kind = format!("{:?}", identity.kind).to_lowercase();
+    state.mark_seen(id).await;
+
     Ok(Json(TokenResponse {
         token,
-        expires_in_seconds: ttl * 60,
+        expires_in_seconds,
         identity_id: id,
-        kind: format!("{:?}", identity.kind).to_lowercase(),
+        kind,
     }))
 }
+
+/// Requested lease, else the server default for `kind`. Non-positive
+/// requests and requests over `MAX_LEASE_MINUTES` are rejected.
+fn lease_length(
+    state: &AppState,
+    kind: &IdentityKind,
+    lease_minutes: Option<i64>,
+) -> Result<Option<chrono::Duration>, (StatusCode, Json<serde_json::Value>)> {
+    match lease_minutes {
+        Some(minutes) if minutes <= 0 => Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({"error": "lease_minutes must be positive"})),
+        )),
+        Some(minutes) if minutes > MAX_LEASE_MINUTES => Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "error": format!("lease_minutes must be at most {}", MAX_LEASE_MINUTES)
+            })),
+        )),
+        Some(minutes) => Ok(chrono::TimeDelta::try_minutes(minutes)),
+        None => Ok(state.config.lease(kind)),
+    }
+}
+
+/// Extend an identity's lease to `lease_minutes` (or the server default for
+/// its kind) from now. A lapsed lease cannot be renewed: the identity has
+/// been deactivated and must be re-registered.
+pub async fn renew_lease(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    meta: RequestMeta,
+    req: Option<Json<RenewLeaseRequest>>,
+) -> Result<Json<Identity>, (StatusCode, Json<serde_json::Value>)> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let mut identities = state.identities.write().await;
+    let identity = identities.iter_mut().find(|i| i.id == id).ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Identity not found"})),
+        )
+    })?;
+    let now = chrono::Utc::now();
+    if !identity.is_live(now) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({"error": "Identity is inactive or its lease has expired"})),
+        ));
+    }
+    let lease = lease_length(&state, &identity.kind, req.lease_minutes)?.ok_or_else(|| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({"error": "lease_minutes is required: no default lease for this kind"})),
+        )
+    })?;
+    let expires_at = now.checked_add_signed(lease).ok_or_else(|| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({"error": "lease_minutes is out of range"})),
+        )
+    })?;
+    identity.expires_at = Some(expires_at);
+    let renewed = identity.clone();
+    drop(identities);
+
+    let mut audit = IdentityAuditEvent::new(
+        renewed.id,
+        "identity.lease_renewed",
+        "zedid-api",
+        &format!("identity/{}", renewed.id),
+        AuditDecision::Allow,
+        Some(format!("Lease extended by {} minutes", lease.num_minutes())),
+    );
+    audit.metadata = meta.audit_metadata().with("expires_at", renewed.expires_at.map(|t| t.to_rfc3339()));
+    if let Err(e) = state.append_audit(audit).await {
+        warn!(identity = %renewed.id, "Lease renewal was not audited: {}", e);
+    }
+    Ok(Json(renewed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn checkout_request() -> CreateIdentityRequest {
+        CreateIdentityRequest {
+            name: "checkout-v2".to_string(),
+            kind: IdentityKind::Workload,
+            namespace: "production".to_string(),
+            trust_domain: None,
+            email: None,
+            labels: None,
+            lease_minutes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cursor_pages_are_stable_across_inserts() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let create = |name: &str| {
+            let mut req = checkout_request();
+            req.name = name.to_string();
+            create_identity(
+                State(state.clone()),
+                RequestMeta::default(),
+                Query(CreateIdentityQuery { dry_run: false, allow_duplicate: false }),
+                Json(req),
+            )
+        };
+        for name in ["pager-a", "pager-b", "pager-c"] {
+            let _ = create(name).await.unwrap();
+        }
+        let page = |cursor: Option<String>| {
+            list_identities(
+                State(state.clone()),
+                Query(IdentityListQuery { stale_days: None, cursor, limit: Some(2), offset: None }),
+            )
+        };
+
+        let Json(first) = page(None).await.unwrap();
+        let total = first.total;
+        let mut seen: Vec<Uuid> = first.identities.iter().map(|i| i.id).collect();
+        // An insert between pages lands after the cursor instead of shifting it
+        let _ = create("pager-d").await.unwrap();
+        let mut cursor = first.next_cursor;
+        while let Some(c) = cursor {
+            let Json(next) = page(Some(c)).await.unwrap();
+            seen.extend(next.identities.iter().map(|i| i.id));
+            cursor = next.next_cursor;
+        }
+        let unique: std::collections::HashSet<_> = seen.iter().collect();
+        assert_eq!(unique.len(), seen.len());
+        assert_eq!(seen.len(), total + 1);
+
+        let invalid = page(Some("not-a-cursor".to_string())).await;
+        assert!(matches!(invalid, Err((StatusCode::BAD_REQUEST, _))));
+    }
+
+    #[tokio::test]
+    async fn test_agent_svid_uses_configured_agent_ttl() {
+        let mut config = AppConfig::load().unwrap();
+        config.svid_ttl_agent_hours = 6;
+        let state = AppState::new(config).await.unwrap();
+        let req = CreateIdentityRequest {
+            name: "ttl-agent".to_string(),
+            kind: IdentityKind::AiAgent,
+            ..checkout_request()
+        };
+        let Json(created) = create_identity(
+            State(state.clone()),
+            RequestMeta::default(),
+            Query(CreateIdentityQuery { dry_run: false, allow_duplicate: false }),
+            Json(req),
+        )
+        .await
+        .unwrap();
+        let svid = created.svid.unwrap();
+        assert_eq!(svid.expires_at - svid.issued_at, chrono::Duration::hours(6));
+        assert_eq!(created.identity.svid_expiry, Some(svid.expires_at));
+
+        // Re-issuance keeps the agent TTL instead of falling back to 1h
+        let Json(renewed) = get_svid(State(state), Path(created.identity.id)).await.unwrap();
+        assert_eq!(renewed.svid.expires_at - renewed.svid.issued_at, chrono::Duration::hours(6));
+    }
+
+    #[tokio::test]
+    async fn test_token_issuance_is_gated_by_policy() {
+        let mut config = AppConfig::load().unwrap();
+        config.gate_token_issuance = true;
+        let state = AppState::new(config).await.unwrap();
+        let admin = state.identities.read().await.iter().find(|i| i.name == "admin").cloned().unwrap();
+        let issue = || {
+            issue_token(
+                State(state.clone()),
+                Path(admin.id),
+                RequestMeta::default(),
+                Json(IssueTokenRequest {
+                    ttl_minutes: None,
+                    not_before_minutes: 0,
+                    audience: None,
+                    extra_claims: HashMap::new(),
+                    context: Default::default(),
+                }),
+            )
+        };
+
+        // No policy allows `token.issue` yet, and monitor mode doesn't relax the gate
+        let with_mode = |mode| zedid_policy::models::UpdateNamespaceRequest {
+            description: None,
+            owner: None,
+            default_trust_level: None,
+            enforcement_mode: Some(mode),
+            variables: None,
+            reason_verbosity: None,
+            context_allowlist: None,
+            combining_algorithm: None,
+        };
+        state
+            .policy_engine
+            .update_namespace(&admin.namespace, with_mode(zedid_policy::models::EnforcementMode::Monitor))
+            .await
+            .unwrap();
+        let Err((status, _)) = issue().await else { panic!("issued without a policy") };
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let mut policy = zedid_policy::models::Policy::new(
+            "critical-tokens-for-admin",
+            "",
+            zedid_policy::models::PolicyKind::Rego,
+            zedid_policy::models::AccessModel::ZeroTrust,
+            "package zedid.tokens",
+            &admin.namespace,
+            "test",
+        );
+        policy.status = PolicyStatus::Active;
+        policy.subjects = vec![admin.id.to_string()];
+        policy.resources = vec!["critical".to_string()];
+        policy.actions = vec![TOKEN_ISSUE_ACTION.to_string()];
+        state.policy_engine.add_policy(policy).await.unwrap();
+        assert!(issue().await.is_ok());
+
+        let audit_log = state.audit_log.read().await;
+        let decisions: Vec<_> = audit_log
+            .iter()
+            .filter(|e| e.action == TOKEN_ISSUE_ACTION)
+            .map(|e| (e.decision.clone(), e.resource.as_str()))
+            .collect();
+        assert_eq!(decisions, [(AuditDecision::Deny, "critical"), (AuditDecision::Allow, "critical")]);
+    }
+
+    #[tokio::test]
+    async fn test_namespace_and_trust_domain_are_validated() {
+        let mut config = AppConfig::load().unwrap();
+        config.trust_domains = vec!["eu.tetrate.io".to_string()];
+        let state = AppState::new(config).await.unwrap();
+        let create = |req: CreateIdentityRequest| {
+            create_identity(
+                State(state.clone()),
+                RequestMeta::default(),
+                Query(CreateIdentityQuery { dry_run: true, allow_duplicate: false }),
+                Json(req),
+            )
+        };
+
+        for namespace in ["../../etc", "production/sa/admin"] {
+            let req = CreateIdentityRequest { namespace: namespace.to_string(), ..checkout_request() };
+            let (status, _) = create(req).await.unwrap_err();
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", namespace);
+        }
+
+        let eu = CreateIdentityRequest { trust_domain: Some("eu.tetrate.io".to_string()), ..checkout_request() };
+        let created = create(eu).await.unwrap();
+        assert_eq!(
+            created.identity.spiffe_id.as_deref(),
+            Some("spiffe://eu.tetrate.io/ns/production/sa/checkout-v2")
+        );
+        let foreign = CreateIdentityRequest { trust_domain: Some("evil.example".to_string()), ..checkout_request() };
+        assert!(create(foreign).await.is_err());
+
+        let defaulted = CreateIdentityRequest { namespace: String::new(), ..checkout_request() };
+        assert_eq!(create(defaulted).await.unwrap().identity.namespace, "default");
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_workload_is_rejected() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let query = || Query(CreateIdentityQuery { dry_run: false, allow_duplicate: false });
+
+        let first = create_identity(State(state.clone()), RequestMeta::default(), query(), Json(checkout_request()))
+            .await
+            .unwrap();
+        let (status, Json(body)) = create_identity(State(state.clone()), RequestMeta::default(), query(), Json(checkout_request()))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["existing_id"], first.identity.id.to_string());
+
+        let escape = Query(CreateIdentityQuery { dry_run: false, allow_duplicate: true });
+        assert!(create_identity(State(state), RequestMeta::default(), escape, Json(checkout_request())).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_identity_keeps_labels() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let query = || Query(CreateIdentityQuery { dry_run: false, allow_duplicate: false });
+        let labels = std::collections::HashMap::from([("team".to_string(), "payments".to_string())]);
+        let req = CreateIdentityRequest { labels: Some(labels.clone()), ..checkout_request() };
+
+        let created = create_identity(State(state.clone()), RequestMeta::default(), query(), Json(req)).await.unwrap();
+        assert_eq!(created.identity.labels, labels);
+        let Json(fetched) = get_identity(State(state.clone()), Path(created.identity.id)).await.unwrap();
+        assert_eq!(fetched.identity.labels, labels);
+
+        let reserved = std::collections::HashMap::from([("zedid.io/owner".to_string(), "me".to_string())]);
+        let req = CreateIdentityRequest { labels: Some(reserved), ..checkout_request() };
+        let (status, _) = create_identity(State(state), RequestMeta::default(), query(), Json(req)).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_expired_leases_deactivate_and_cannot_be_renewed() {
+        let mut config = AppConfig::load().unwrap();
+        config.agent_lease_minutes = 30;
+        let state = AppState::new(config).await.unwrap();
+        let query = || Query(CreateIdentityQuery { dry_run: false, allow_duplicate: false });
+        let agent = |name: &str| CreateIdentityRequest {
+            name: name.to_string(),
+            kind: IdentityKind::AiAgent,
+            ..checkout_request()
+        };
+        let live = create_identity(State(state.clone()), RequestMeta::default(), query(), Json(agent("lease-live")))
+            .await
+            .unwrap()
+            .identity
+            .clone();
+        let lapsed = create_identity(State(state.clone()), RequestMeta::default(), query(), Json(agent("lease-lapsed")))
+            .await
+            .unwrap()
+            .identity
+            .clone();
+        let lease = live.expires_at.unwrap() - live.created_at;
+        assert!((lease - chrono::Duration::minutes(30)).num_seconds().abs() < 5);
+        state.identities.write().await.iter_mut().find(|i| i.id == lapsed.id).unwrap().expires_at =
+            Some(chrono::Utc::now() - chrono::Duration::minutes(1));
+
+        assert_eq!(state.expire_leases().await, 1);
+        assert_eq!(state.expire_leases().await, 0);
+        let Json(fetched) = get_identity(State(state.clone()), Path(lapsed.id)).await.unwrap();
+        assert!(!fetched.identity.is_active);
+        assert!(state.audit_log.read().await.iter().any(|e| e.identity_id == lapsed.id && e.action == "identity.lease_expired"));
+        assert_eq!(state.metrics.get(zedid_policy::metrics::IDENTITY_LEASES_EXPIRED_TOTAL), 1);
+
+        let renew = |id: Uuid, lease_minutes: Option<i64>| {
+            renew_lease(State(state.clone()), Path(id), RequestMeta::default(), Some(Json(RenewLeaseRequest { lease_minutes })))
+        };
+        let (status, _) = renew(lapsed.id, None).await.unwrap_err();
+        assert_eq!(status, StatusCode::CONFLICT);
+        let Json(renewed) = renew(live.id, Some(120)).await.unwrap();
+        assert!(renewed.expires_at.unwrap() > live.expires_at.unwrap() + chrono::Duration::minutes(60));
+
+        let (status, _) = renew(live.id, Some(i64::MAX)).await.unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let unbounded = CreateIdentityRequest { lease_minutes: Some(i64::MAX), ..agent("lease-unbounded") };
+        let (status, _) = create_identity(State(state.clone()), RequestMeta::default(), query(), Json(unbounded))
+            .await
+            .unwrap_err();
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
@@ -1,34 +1,112 @@
+pub mod admin;
+pub mod auth;
+pub mod discovery;
+pub mod groups;
 pub mod health;
 pub mod identities;
 pub mod policies;
 pub mod audit;
+pub mod namespaces;
+pub mod pagination;
+pub mod spiffe;
+pub mod svids;
+pub mod tokens;
 
 use crate::state::AppState;
-use axum::{routing::get, routing::post, Router};
+use axum::{http::StatusCode, routing::delete, routing::get, routing::patch, routing::post, routing::put, Json, Router};
+use zedid_policy::PolicyError;
+
+/// Map engine errors onto HTTP status codes
+pub fn policy_error(e: PolicyError) -> (StatusCode, Json<serde_json::Value>) {
+    let status = match e {
+        PolicyError::NotFound(_) => StatusCode::NOT_FOUND,
+        PolicyError::Conflict(_) => StatusCode::CONFLICT,
+        PolicyError::ValidationFailed(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        PolicyError::Unauthorized(_) => StatusCode::FORBIDDEN,
+        PolicyError::QuotaExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+        PolicyError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(serde_json::json!({"error": e.to_string()})))
+}
 
 pub fn router() -> Router<AppState> {
     Router::new()
         // Health & system
         .route("/health", get(health::health_check))
         .route("/system/info", get(health::system_info))
+        .route("/metrics", get(health::metrics))
+        .route("/whoami", get(auth::whoami))
         // Identity management
         .route("/identities", get(identities::list_identities))
         .route("/identities", post(identities::create_identity))
+        .route("/identities/trust-levels", post(identities::trust_levels))
+        .route("/identities/expiring", get(identities::expiring_identities))
         .route("/identities/:id", get(identities::get_identity))
         .route("/identities/:id/svid", get(identities::get_svid))
+        .route("/identities/:id/policies", get(identities::identity_policies))
         .route("/identities/:id/token", post(identities::issue_token))
+        .route("/identities/:id/renew-lease", post(identities::renew_lease))
+        .route("/token/introspect", post(tokens::introspect))
+        // Namespaces
+        .route("/namespaces", get(namespaces::list_namespaces))
+        .route("/namespaces", post(namespaces::create_namespace))
+        .route("/namespaces/:name", get(namespaces::get_namespace))
+        .route("/namespaces/:name", patch(namespaces::update_namespace))
+        .route("/namespaces/:name", delete(namespaces::delete_namespace))
+        .route("/namespaces/:name/default-policy", put(namespaces::set_default_policy))
+        // SVID registry
+        .route("/svids", get(svids::list_svids))
+        .route("/spiffe/validate", get(spiffe::validate_spiffe_id))
         // Policy management
-        // IMPORTANT: static sub-paths (/generate, /evaluate) MUST be registered
+        // IMPORTANT: static sub-paths (/generate, /evaluate, ...) MUST be registered
         // before the dynamic /:id route, otherwise Axum will try to parse
         // "generate"/"evaluate" as UUIDs and return 422 Unprocessable Entity.
         .route("/policies", get(policies::list_policies))
         .route("/policies", post(policies::create_policy))
         .route("/policies/generate", post(policies::generate_policy))
         .route("/policies/evaluate", post(policies::evaluate_policy))
+        .route("/policies/evaluate/report", post(policies::evaluation_report))
+        .route("/policies/evaluate/proposed", post(policies::evaluate_proposed))
+        .route("/policies/sandbox", post(policies::sandbox_policy))
+        .route("/policies/search", get(policies::search_policies))
+        .route("/policies/export", get(policies::export_policies))
+        .route("/policies/import", post(policies::import_policies))
+        .route("/policies/activate", post(policies::bulk_activate))
+        .route("/policies/stats", get(policies::evaluation_stats))
+        .route("/policies/heatmap", get(policies::coverage_heatmap))
+        .route("/policies/git-sync", get(policies::git_sync_status))
         .route("/policies/:id", get(policies::get_policy))
+        .route("/policies/:id/history", get(policies::policy_history))
+        .route("/policies/:id/dependencies", get(policies::policy_dependencies))
+        .route(
+            "/policies/:id/stats",
+            get(policies::policy_stats).delete(policies::reset_policy_stats),
+        )
+        .route("/policies/:id/impact/identity/:identity_id", post(policies::identity_impact))
+        .route("/policies/:id/explain", post(policies::explain_policy))
+        .route("/policies/:id/tests", put(policies::set_policy_tests))
+        .route("/policies/:id/run-tests", post(policies::run_policy_tests))
+        .route("/policies/:id/statements", get(policies::policy_statements))
+        .route("/policies/:id/statements/:name", patch(policies::toggle_statement))
         .route("/policies/:id/activate", post(policies::activate_policy))
         .route("/policies/:id/disable", post(policies::disable_policy))
+        .route("/policies/:id/submit-review", post(policies::submit_review))
+        .route("/policies/:id/approve", post(policies::approve_policy))
+        .route("/policies/:id/reject", post(policies::reject_policy))
+        // Subjects
+        .route("/subjects/:subject/permissions", get(policies::subject_permissions))
+        // Policy groups
+        .route("/policy-groups", get(groups::list_groups))
+        .route("/policy-groups", post(groups::create_group))
+        .route("/policy-groups/:id", get(groups::get_group))
+        .route("/policy-groups/:id/members", patch(groups::update_members))
+        .route("/policy-groups/:id/activate", post(groups::activate_group))
+        .route("/policy-groups/:id/disable", post(groups::disable_group))
         // Audit log
         .route("/audit", get(audit::list_audit_events))
         .route("/audit/stats", get(audit::audit_stats))
+        // Admin
+        .route("/admin/reset-demo", post(admin::reset_demo))
+        .route("/admin/rotate-signing-key", post(admin::rotate_signing_key))
 }
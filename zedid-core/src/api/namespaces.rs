@@ -0,0 +1,174 @@
+use crate::state::AppState;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use zedid_identity::spiffe::validate_namespace;
+use zedid_policy::models::{CreateNamespaceRequest, Namespace, SetDefaultPolicyRequest, UpdateNamespaceRequest};
+use super::policy_error;
+
+#[derive(Serialize)]
+pub struct NamespaceSummary {
+    pub name: String,
+    /// False for namespaces referenced by identities/policies but never registered
+    pub registered: bool,
+    pub namespace: Option<Namespace>,
+    pub identity_count: usize,
+    pub policy_count: usize,
+}
+
+pub async fn list_namespaces(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let registered = state.policy_engine.list_namespaces().await;
+    let policies = state.policy_engine.list_policies(None).await;
+    let identities = state.identities.read().await;
+
+    let names: BTreeSet<String> = registered
+        .iter()
+        .map(|n| n.name.clone())
+        .chain(identities.iter().map(|i| i.namespace.clone()))
+        .chain(policies.iter().map(|p| p.namespace.clone()))
+        .collect();
+
+    let namespaces: Vec<NamespaceSummary> = names
+        .into_iter()
+        .map(|name| {
+            let namespace = registered.iter().find(|n| n.name == name).cloned();
+            NamespaceSummary {
+                registered: namespace.is_some(),
+                identity_count: identities.iter().filter(|i| i.namespace == name).count(),
+                policy_count: policies.iter().filter(|p| p.namespace == name).count(),
+                namespace,
+                name,
+            }
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "total": namespaces.len(),
+        "namespaces": namespaces,
+    }))
+}
+
+pub async fn get_namespace(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Namespace>, StatusCode> {
+    state
+        .policy_engine
+        .get_namespace(&name)
+        .await
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+pub async fn create_namespace(
+    State(state): State<AppState>,
+    Json(req): Json<CreateNamespaceRequest>,
+) -> Result<Json<Namespace>, (StatusCode, Json<serde_json::Value>)> {
+    validate_namespace(&req.name).map_err(|e| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+    })?;
+    let mut namespace = Namespace::new(
+        &req.name,
+        req.description.as_deref().unwrap_or_default(),
+        req.owner.as_deref(),
+    );
+    if let Some(level) = req.default_trust_level {
+        namespace.default_trust_level = level;
+    }
+    if let Some(mode) = req.enforcement_mode {
+        namespace.enforcement_mode = mode;
+    }
+    if let Some(variables) = req.variables {
+        namespace.variables = variables;
+    }
+    namespace.reason_verbosity = req.reason_verbosity;
+    namespace.context_allowlist = req.context_allowlist;
+    namespace.combining_algorithm = req.combining_algorithm;
+
+    state
+        .policy_engine
+        .create_namespace(namespace)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+pub async fn update_namespace(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(req): Json<UpdateNamespaceRequest>,
+) -> Result<Json<Namespace>, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .update_namespace(&name, req)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+pub async fn set_default_policy(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(req): Json<SetDefaultPolicyRequest>,
+) -> Result<Json<Namespace>, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .set_default_policy(&name, req.policy_id)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+pub async fn delete_namespace(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<Namespace>, (StatusCode, Json<serde_json::Value>)> {
+    let identity_count = state
+        .identities
+        .read()
+        .await
+        .iter()
+        .filter(|i| i.namespace == name)
+        .count();
+    let policy_count = state.policy_engine.list_policies(Some(&name)).await.len();
+    if identity_count > 0 || policy_count > 0 {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(serde_json::json!({
+                "error": format!("Namespace '{}' is still in use", name),
+                "identity_count": identity_count,
+                "policy_count": policy_count,
+            })),
+        ));
+    }
+
+    state
+        .policy_engine
+        .delete_namespace(&name)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+/// In strict mode, reject references to namespaces that were never registered
+pub async fn ensure_namespace(
+    state: &AppState,
+    namespace: &str,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    if state.config.strict_namespaces && state.policy_engine.get_namespace(namespace).await.is_none() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({
+                "error": format!("Namespace '{}' is not registered", namespace),
+            })),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use axum::{http::StatusCode, Json};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+pub const MAX_PAGE_LIMIT: usize = 1000;
+
+/// Sort key a cursor points at: timestamp with the id as tie-breaker, so
+/// pages stay stable when entries share a timestamp or new ones are inserted
+pub type SortKey = (DateTime<Utc>, Uuid);
+
+type ApiError = (StatusCode, Json<serde_json::Value>);
+
+/// Opaque cursor for the last entry of a page
+pub fn encode_cursor((at, id): SortKey) -> String {
+    URL_SAFE_NO_PAD.encode(format!("{}|{}", at.to_rfc3339(), id))
+}
+
+pub fn decode_cursor(cursor: &str) -> Result<SortKey, ApiError> {
+    let invalid = || {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Invalid pagination cursor"})),
+        )
+    };
+    let raw = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+    let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+    let (at, id) = raw.split_once('|').ok_or_else(invalid)?;
+    let at = DateTime::parse_from_rfc3339(at).map_err(|_| invalid())?;
+    Ok((at.with_timezone(&Utc), id.parse().map_err(|_| invalid())?))
+}
+
+/// One page of `items`, which must already be sorted by `key` in the
+/// listing's order (`descending` for newest-first). Entries after `cursor`
+/// are returned; without a cursor the deprecated `offset` is applied instead.
+/// `next_cursor` is set only when more entries follow the page.
+pub fn paginate<T>(
+    items: Vec<T>,
+    key: impl Fn(&T) -> SortKey,
+    descending: bool,
+    cursor: Option<&str>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<(Vec<T>, Option<String>), ApiError> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+    let after = cursor.map(decode_cursor).transpose()?;
+    let mut page: Vec<T> = items
+        .into_iter()
+        .filter(|item| {
+            after.is_none_or(|after| {
+                let k = key(item);
+                if descending {
+                    k < after
+                } else {
+                    k > after
+                }
+            })
+        })
+        .skip(if after.is_some() { 0 } else { offset.unwrap_or(0) })
+        .take(limit + 1)
+        .collect();
+    let next_cursor = if page.len() > limit {
+        page.truncate(limit);
+        page.last().map(|last| encode_cursor(key(last)))
+    } else {
+        None
+    };
+    Ok((page, next_cursor))
+}
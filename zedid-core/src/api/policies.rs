@@ -1,16 +1,24 @@
 use crate::state::AppState;
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use zedid_policy::engine::check_policy_limits;
+use zedid_policy::statements;
 use zedid_policy::models::{
-    GeneratePolicyRequest, GeneratePolicyResponse, Policy, PolicyDecisionRequest,
-    PolicyDecisionResponse, PolicyStatus,
+    AccessModel, BulkActivateRequest, BulkActivateResponse, CoverageHeatmap, CreatePolicyRequest, ExplainPolicyResponse, GeneratePolicyRequest, GeneratePolicyResponse,
+    HistoricalDecisionResponse, IdentityImpact, Policy, PolicyBundle, PolicyDecisionRequest,
+    EvaluationReport, EvaluationReportRequest, ProposedDecisionResponse, ProposedEvaluationRequest, EvaluationStats, GitSyncStatus, PolicyDecisionResponse, PolicyStatement, PolicyStats, PolicyStatus, PolicyTestReport, SetPolicyTestsRequest, ToggleStatementRequest,
+    ReasonVerbosity, SandboxRequest, SandboxResponse, SubjectPermissions,
 };
-use tracing::info;
+use zedid_identity::{AuditDecision, AuditMetadata, IdentityAuditEvent, IdentityKind, TrustLevel};
+use zedid_policy::metrics;
+use super::{auth::{Caller, RequestMeta}, namespaces::ensure_namespace, policy_error};
+use tracing::{info, warn};
 
 #[derive(Deserialize)]
 pub struct PolicyListQuery {
@@ -32,31 +40,128 @@ pub async fn list_policies(
     }))
 }
 
+#[derive(Deserialize)]
+pub struct PolicySearchQuery {
+    pub q: String,
+    pub namespace: Option<String>,
+    pub tag: Option<String>,
+    pub status: Option<PolicyStatus>,
+}
+
+pub async fn search_policies(
+    State(state): State<AppState>,
+    Query(query): Query<PolicySearchQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    if query.q.trim().is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "Search query `q` cannot be empty"})),
+        ));
+    }
+    let results = state
+        .policy_engine
+        .search_policies(
+            &query.q,
+            query.namespace.as_deref(),
+            query.tag.as_deref(),
+            query.status.as_ref(),
+        )
+        .await;
+    Ok(Json(serde_json::json!({
+        "query": query.q,
+        "total": results.len(),
+        "results": results,
+    })))
+}
+
+#[derive(Deserialize)]
+pub struct PolicyFormatQuery {
+    /// `raw` returns just the (rendered) policy content in its native format
+    pub format: Option<String>,
+}
+
+/// A policy with the statuses it can move to next
+#[derive(Serialize)]
+pub struct PolicyDetail {
+    #[serde(flatten)]
+    pub policy: Policy,
+    pub next_statuses: Vec<PolicyStatus>,
+}
+
 pub async fn get_policy(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<Policy>, StatusCode> {
-    state
+    Query(query): Query<PolicyFormatQuery>,
+    headers: HeaderMap,
+) -> Result<Response, StatusCode> {
+    let policy = state
         .policy_engine
         .get_policy(id)
         .await
-        .map(Json)
-        .ok_or(StatusCode::NOT_FOUND)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // JSON stays the default; raw is opted into explicitly or via a non-JSON Accept
+    let accept = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let wants_raw = query.format.as_deref() == Some("raw")
+        || accept.contains("text/plain")
+        || accept.contains("application/yaml");
+    if !wants_raw {
+        let next_statuses = state.policy_engine.next_statuses(&policy);
+        return Ok(Json(PolicyDetail { policy, next_statuses }).into_response());
+    }
+
+    let policy = state
+        .policy_engine
+        .render_policies(vec![policy])
+        .await
+        .remove(0);
+    // Raw content is what gets deployed, so disabled statements are left out
+    let content = if policy.disabled_statements.is_empty() {
+        policy.content
+    } else {
+        statements::enabled_content(&policy)
+    };
+    Ok(([(header::CONTENT_TYPE, policy.kind.media_type())], content).into_response())
 }
 
 pub async fn create_policy(
     State(state): State<AppState>,
-    Json(mut policy): Json<Policy>,
-) -> Json<Policy> {
-    policy.id = Uuid::new_v4();
-    policy.created_at = chrono::Utc::now();
-    policy.updated_at = chrono::Utc::now();
+    Json(req): Json<CreatePolicyRequest>,
+) -> Result<Json<Policy>, (StatusCode, Json<serde_json::Value>)> {
+    ensure_namespace(&state, &req.namespace).await?;
+
+    let mut policy = Policy::new(
+        &req.name,
+        &req.description,
+        req.kind,
+        req.access_model,
+        &req.content,
+        &req.namespace,
+        "zedid-api-user",
+    );
+    policy.explanation = req.explanation;
+    policy.subjects = req.subjects;
+    policy.resources = req.resources;
+    policy.actions = req.actions;
+    policy.excluded_subjects = req.excluded_subjects;
+    policy.excluded_resources = req.excluded_resources;
+    policy.tags = req.tags;
+    policy.variables = req.variables;
+    policy.active_from = req.active_from;
+    policy.active_until = req.active_until;
+    policy.schedule = req.schedule;
+    policy.rate_limit = req.rate_limit;
+    policy.tests = req.tests;
+    check_policy_limits(&policy).map_err(policy_error)?;
 
     let validation = state.policy_engine.validate_policy(&policy);
     policy.validation_passed = validation.passed;
 
-    state.policy_engine.add_policy(policy.clone()).await;
-    Json(policy)
+    let policy = state.policy_engine.add_policy(policy).await.map_err(policy_error)?;
+    Ok(Json(policy))
 }
 
 pub async fn generate_policy(
@@ -64,69 +169,856 @@ pub async fn generate_policy(
     Json(req): Json<GeneratePolicyRequest>,
 ) -> Result<Json<GeneratePolicyResponse>, (StatusCode, Json<serde_json::Value>)> {
     info!("Policy generation request: {}", req.intent);
+    ensure_namespace(&state, &req.namespace).await?;
 
     let response = state
         .policy_generator
         .generate(&req, "zedid-api-user")
         .await
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": e.to_string()})),
-            )
-        })?;
+        .map_err(policy_error)?;
 
     Ok(Json(response))
 }
 
+pub async fn set_policy_tests(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
+    Json(req): Json<SetPolicyTestsRequest>,
+) -> Result<Json<Policy>, (StatusCode, Json<serde_json::Value>)> {
+    let before = state.policy_engine.get_policy(id).await;
+    let policy = state
+        .policy_engine
+        .set_policy_tests(id, req.tests)
+        .await
+        .map_err(policy_error)?;
+    audit_policy_change(&state, &caller, &meta, before.as_ref(), &policy).await;
+    Ok(Json(policy))
+}
+
+pub async fn run_policy_tests(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PolicyTestReport>, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .run_policy_tests(id)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+pub async fn explain_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
+) -> Result<Json<ExplainPolicyResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let before = state.policy_engine.get_policy(id).await;
+    let explained = state
+        .policy_generator
+        .explain(id)
+        .await
+        .map_err(policy_error)?;
+    audit_policy_change(&state, &caller, &meta, before.as_ref(), &explained.policy).await;
+    Ok(Json(explained))
+}
+
+/// Evaluate a request against an inline, unsaved policy (no storage, no audit)
+pub async fn sandbox_policy(
+    State(state): State<AppState>,
+    Json(req): Json<SandboxRequest>,
+) -> Result<Json<SandboxResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let mut policy = Policy::new(
+        "sandbox",
+        "Inline sandbox policy",
+        req.kind,
+        AccessModel::ZeroTrust,
+        &req.content,
+        &req.request.namespace,
+        "zedid-sandbox",
+    );
+    policy.subjects = req.subjects;
+    policy.resources = req.resources;
+    policy.actions = req.actions;
+    policy.excluded_subjects = req.excluded_subjects;
+    policy.excluded_resources = req.excluded_resources;
+    policy.variables = req.variables;
+    check_policy_limits(&policy).map_err(policy_error)?;
+
+    let (validation, decision) = state
+        .policy_engine
+        .evaluate_sandbox(policy, &req.request)
+        .await;
+    Ok(Json(SandboxResponse { decision, validation }))
+}
+
+#[derive(Deserialize)]
+pub struct EvaluateQuery {
+    /// Evaluate against the policy set as it existed at this instant (RFC 3339)
+    pub as_of: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 pub async fn evaluate_policy(
     State(state): State<AppState>,
+    caller: Caller,
+    meta: RequestMeta,
+    Query(query): Query<EvaluateQuery>,
     Json(req): Json<PolicyDecisionRequest>,
-) -> Result<Json<PolicyDecisionResponse>, (StatusCode, Json<serde_json::Value>)> {
-    let response = state
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    if let Some(as_of) = query.as_of {
+        let (mut decision, policy_versions) = state.policy_engine.evaluate_as_of(&req, as_of).await;
+        finalize_decision(&state, &req, &mut decision, &caller, &meta).await;
+        return Ok(Json(HistoricalDecisionResponse {
+            decision,
+            as_of,
+            policy_versions,
+        })
+        .into_response());
+    }
+
+    let mut response = state
         .policy_engine
         .evaluate(&req)
         .await
-        .map_err(|e| {
+        .map_err(policy_error)?;
+    finalize_decision(&state, &req, &mut response, &caller, &meta).await;
+
+    Ok(Json(response).into_response())
+}
+
+/// Baseline of current decisions across representative (or derived)
+/// requests in a namespace, for comparison before and after a policy change
+pub async fn evaluation_report(
+    State(state): State<AppState>,
+    Json(req): Json<EvaluationReportRequest>,
+) -> Result<Json<EvaluationReport>, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .evaluation_report(&req.namespace, req.requests, req.limit)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
+
+/// What-if decision against the active set with unsaved policies applied,
+/// next to the current decision. Nothing is stored or audited.
+pub async fn evaluate_proposed(
+    State(state): State<AppState>,
+    Json(req): Json<ProposedEvaluationRequest>,
+) -> Result<Json<ProposedDecisionResponse>, (StatusCode, Json<serde_json::Value>)> {
+    let (decision, current_decision) = state
+        .policy_engine
+        .evaluate_proposed(&req.base_namespace, req.overlay_policies, &req.request)
+        .await
+        .map_err(policy_error)?;
+    let changed = decision.allowed != current_decision.allowed;
+    Ok(Json(ProposedDecisionResponse {
+        decision,
+        current_decision,
+        changed,
+    }))
+}
+
+/// Post-evaluation steps shared by REST and gRPC: write the decision to the
+/// audit log (when `ZEDID_AUDIT_DECISIONS` is on, or the reason is about to be
+/// redacted), then hide deny details from untrusted callers where the namespace
+/// asks for it. An audit write failure is reported in `warnings`, and with
+/// `ZEDID_AUDIT_REQUIRED` an unaudited decision fails closed. With
+/// `ZEDID_EXPLAIN_DENIALS`, unredacted denials get a `human_explanation`.
+pub(crate) async fn finalize_decision(
+    state: &AppState,
+    req: &PolicyDecisionRequest,
+    response: &mut PolicyDecisionResponse,
+    caller: &Caller,
+    meta: &RequestMeta,
+) {
+    // Audit the token's verified subject, not the one the caller asserted
+    let verified = match state.policy_engine.verify_subject_token(req).await {
+        Ok((verified, _)) => verified,
+        Err(_) => req.clone(),
+    };
+    let req = &verified;
+    let redact = !response.allowed
+        && !caller.is_trusted()
+        && state.policy_engine.reason_verbosity(&req.namespace).await == ReasonVerbosity::Redacted;
+    let audit_required = state.config.audit_required;
+    // Denials are always audited; allows only when decision auditing is on
+    let denied = !response.allowed || response.would_deny;
+    if state.config.audit_decisions || audit_required || redact || denied {
+        audit_decision(state, req, response, meta, redact).await;
+    }
+
+    if redact {
+        state.metrics.incr(metrics::DENY_REASONS_REDACTED_TOTAL);
+        response.reason = "access denied".to_string();
+        response.policy_id = None;
+        response.policy_name = None;
+    } else if state.config.explain_denials && !response.allowed {
+        match state.policy_generator.explain_denial(response).await {
+            Ok(explanation) => response.human_explanation = Some(explanation),
+            Err(e) => response.warnings.push(format!("denial could not be explained: {}", e)),
+        }
+    }
+}
+
+/// Write a `policy.evaluate` event for the decision, failing it closed when
+/// the write fails under `ZEDID_AUDIT_REQUIRED`
+async fn audit_decision(
+    state: &AppState,
+    req: &PolicyDecisionRequest,
+    response: &mut PolicyDecisionResponse,
+    meta: &RequestMeta,
+    redact: bool,
+) {
+    let identity_id = state
+        .identities
+        .read()
+        .await
+        .iter()
+        .find(|i| i.spiffe_id.as_deref() == Some(req.subject.as_str()) || i.id.to_string() == req.subject)
+        .map(|i| i.id)
+        .unwrap_or_default();
+    let mut audit = IdentityAuditEvent::new(
+        identity_id,
+        "policy.evaluate",
+        &req.subject,
+        &req.resource,
+        if response.allowed { AuditDecision::Allow } else { AuditDecision::Deny },
+        Some(response.reason.clone()),
+    );
+    audit.metadata = AuditMetadata {
+        policy_id: response.policy_id,
+        decision_id: Some(response.decision_id),
+        ..meta.audit_metadata()
+    }
+    .with("namespace", req.namespace.as_str())
+    .with("action", req.action.as_str())
+    .with("policy_name", response.policy_name.clone())
+    .with("enforcement_mode", serde_json::to_value(response.enforcement_mode).unwrap_or_default())
+    .with("would_deny", response.would_deny)
+    .with("redacted", redact);
+
+    if let Err(e) = state.append_audit(audit).await {
+        response.warnings.push(format!("decision was not audited: {}", e));
+        if state.config.audit_required && response.allowed {
+            response.allowed = false;
+            response.would_deny = false;
+            response.reason = "decision could not be audited — fail closed".to_string();
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DependencyRef {
+    /// Subject or resource as written in the (rendered) policy
+    pub value: String,
+    /// Identity it resolves to, by SPIFFE ID, UUID or (for resources) service name
+    pub identity: Option<IdentityRef>,
+}
+
+#[derive(Serialize)]
+pub struct IdentityRef {
+    pub id: Uuid,
+    pub name: String,
+    pub namespace: String,
+    pub kind: IdentityKind,
+    pub trust_level: TrustLevel,
+    pub is_active: bool,
+}
+
+#[derive(Serialize)]
+pub struct OverlappingPolicy {
+    pub policy_id: Uuid,
+    pub name: String,
+    pub namespace: String,
+    pub status: PolicyStatus,
+    pub shared_subjects: Vec<String>,
+    pub shared_resources: Vec<String>,
+}
+
+/// Blast radius of a policy: the identities it names, the resources it
+/// governs and the other policies that share either
+pub async fn policy_dependencies(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let all = state
+        .policy_engine
+        .render_policies(state.policy_engine.list_policies(None).await)
+        .await;
+    let policy = all.iter().find(|p| p.id == id).ok_or(StatusCode::NOT_FOUND)?;
+    let identities = state.identities.read().await;
+
+    let resolve = |value: &str, by_name: bool| {
+        identities
+            .iter()
+            .find(|i| {
+                i.spiffe_id.as_deref() == Some(value)
+                    || i.id.to_string() == value
+                    || (by_name && i.name == value)
+            })
+            .map(|i| IdentityRef {
+                id: i.id,
+                name: i.name.clone(),
+                namespace: i.namespace.clone(),
+                kind: i.kind.clone(),
+                trust_level: i.trust_level.clone(),
+                is_active: i.is_active,
+            })
+    };
+    let subjects: Vec<DependencyRef> = policy
+        .subjects
+        .iter()
+        .map(|s| DependencyRef { value: s.clone(), identity: resolve(s, false) })
+        .collect();
+    let resources: Vec<DependencyRef> = policy
+        .resources
+        .iter()
+        .map(|r| DependencyRef { value: r.clone(), identity: resolve(r, true) })
+        .collect();
+
+    let shared = |ours: &[String], theirs: &[String]| -> Vec<String> {
+        ours.iter().filter(|v| theirs.contains(v)).cloned().collect()
+    };
+    let overlapping: Vec<OverlappingPolicy> = all
+        .iter()
+        .filter(|p| p.id != policy.id)
+        .map(|p| OverlappingPolicy {
+            policy_id: p.id,
+            name: p.name.clone(),
+            namespace: p.namespace.clone(),
+            status: p.status.clone(),
+            shared_subjects: shared(&policy.subjects, &p.subjects),
+            shared_resources: shared(&policy.resources, &p.resources),
+        })
+        .filter(|o| !o.shared_subjects.is_empty() || !o.shared_resources.is_empty())
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "policy_id": policy.id,
+        "name": policy.name,
+        "subjects": subjects,
+        "resources": resources,
+        "actions": policy.actions,
+        "overlapping_policies": overlapping,
+    })))
+}
+
+/// Recorded revisions of a policy, oldest first
+pub async fn policy_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let revisions = state.policy_engine.policy_history(id);
+    if revisions.is_empty() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(serde_json::json!({
+        "revisions": revisions,
+        "total": revisions.len(),
+    })))
+}
+
+pub async fn evaluation_stats(State(state): State<AppState>) -> Json<EvaluationStats> {
+    Json(state.policy_engine.evaluation_stats())
+}
+
+/// Namespaces × resources matrix of active policy coverage, for the dashboard
+pub async fn coverage_heatmap(State(state): State<AppState>) -> Json<CoverageHeatmap> {
+    Json(state.policy_engine.coverage_heatmap().await)
+}
+
+/// Repository, synced commit and outcome of the latest Git sync
+pub async fn git_sync_status(
+    State(state): State<AppState>,
+) -> Result<Json<GitSyncStatus>, (StatusCode, Json<serde_json::Value>)> {
+    let source = state.policy_git.as_ref().ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Git policy sync is not configured (set ZEDID_POLICY_GIT_URL)"})),
+        )
+    })?;
+    Ok(Json(source.status()))
+}
+
+pub async fn policy_stats(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PolicyStats>, (StatusCode, Json<serde_json::Value>)> {
+    state.policy_engine.policy_stats(id).await.map(Json).map_err(policy_error)
+}
+
+pub async fn reset_policy_stats(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .reset_policy_stats(id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(policy_error)
+}
+
+pub async fn bulk_activate(
+    State(state): State<AppState>,
+    caller: Caller,
+    meta: RequestMeta,
+    Json(req): Json<BulkActivateRequest>,
+) -> Result<Json<BulkActivateResponse>, (StatusCode, Json<serde_json::Value>)> {
+    if req.ids.is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(serde_json::json!({"error": "ids cannot be empty"})),
+        ));
+    }
+    let mut before = vec![];
+    for id in &req.ids {
+        before.extend(state.policy_engine.get_policy(*id).await);
+    }
+    let response = state.policy_engine.activate_policies(&req.ids, req.atomic).await;
+    audit_policy_changes(&state, &caller, &meta, &before).await;
+    Ok(Json(response))
+}
+
+/// What one identity could do under a policy, as an allowed/denied action
+/// matrix over the policy's resources
+pub async fn identity_impact(
+    State(state): State<AppState>,
+    Path((id, identity_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<IdentityImpact>, (StatusCode, Json<serde_json::Value>)> {
+    let identity = state
+        .identities
+        .read()
+        .await
+        .iter()
+        .find(|i| i.id == identity_id)
+        .cloned()
+        .ok_or_else(|| {
             (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(serde_json::json!({"error": e.to_string()})),
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": "Identity not found"})),
             )
         })?;
+    state
+        .policy_engine
+        .impact_on_identity(id, &identity)
+        .await
+        .map(Json)
+        .map_err(policy_error)
+}
 
-    Ok(Json(response))
+/// Flattened view of everything a subject (SPIFFE ID or identity id,
+/// URL-encoded) is allowed to do right now, with the allowing policy
+pub async fn subject_permissions(
+    State(state): State<AppState>,
+    Path(subject): Path<String>,
+) -> Result<Json<SubjectPermissions>, (StatusCode, Json<serde_json::Value>)> {
+    state
+        .policy_engine
+        .effective_permissions(&subject)
+        .await
+        .map(Json)
+        .map_err(policy_error)
 }
 
-pub async fn activate_policy(
+pub async fn policy_statements(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<Policy>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<Json<Vec<PolicyStatement>>, (StatusCode, Json<serde_json::Value>)> {
     state
         .policy_engine
-        .update_policy_status(id, PolicyStatus::Active)
+        .policy_statements(id)
         .await
         .map(Json)
-        .map_err(|e| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": e.to_string()})),
-            )
-        })
+        .map_err(policy_error)
+}
+
+/// Enable or disable one Rego rule / Cedar statement of a policy
+pub async fn toggle_statement(
+    State(state): State<AppState>,
+    Path((id, name)): Path<(Uuid, String)>,
+    caller: Caller,
+    meta: RequestMeta,
+    Json(req): Json<ToggleStatementRequest>,
+) -> Result<Json<Vec<PolicyStatement>>, (StatusCode, Json<serde_json::Value>)> {
+    let before = state.policy_engine.get_policy(id).await;
+    let statements = state
+        .policy_engine
+        .set_statement_enabled(id, &name, req.enabled)
+        .await
+        .map_err(policy_error)?;
+    audit_policy_changes(&state, &caller, &meta, before.as_slice()).await;
+    Ok(Json(statements))
+}
+
+#[derive(Deserialize)]
+pub struct ActivateQuery {
+    /// Activate even a policy generated in simulation mode
+    #[serde(default)]
+    pub force: bool,
+}
+
+pub async fn activate_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(query): Query<ActivateQuery>,
+    caller: Caller,
+    meta: RequestMeta,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let before = state.policy_engine.get_policy(id).await;
+    let policy = state
+        .policy_engine
+        .activate_policy(id, query.force)
+        .await
+        .map_err(policy_error)?;
+    audit_policy_change(&state, &caller, &meta, before.as_ref(), &policy).await;
+    if policy.is_simulated {
+        let warning = "299 zedid \"Simulated policy activated; its content is not real model output\"";
+        return Ok(([(header::WARNING, warning)], Json(policy)).into_response());
+    }
+    Ok(Json(policy).into_response())
 }
 
 pub async fn disable_policy(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
 ) -> Result<Json<Policy>, (StatusCode, Json<serde_json::Value>)> {
-    state
+    let before = state.policy_engine.get_policy(id).await;
+    let policy = state
         .policy_engine
         .update_policy_status(id, PolicyStatus::Disabled)
         .await
-        .map(Json)
-        .map_err(|e| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({"error": e.to_string()})),
+        .map_err(policy_error)?;
+    audit_policy_change(&state, &caller, &meta, before.as_ref(), &policy).await;
+    Ok(Json(policy))
+}
+
+#[derive(Deserialize)]
+pub struct SubmitReviewRequest {
+    pub reviewers: Vec<String>,
+}
+
+/// The reviewer is the bearer token's subject, never the body
+#[derive(Deserialize)]
+pub struct ApproveRequest {
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RejectRequest {
+    pub comment: String,
+}
+
+/// Subject of the caller's bearer token; reviews need one
+fn reviewer(caller: &Caller) -> Result<String, (StatusCode, Json<serde_json::Value>)> {
+    caller.claims.as_ref().map(|c| c.sub.clone()).ok_or_else(|| {
+        (
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({"error": "Reviewing a policy requires a bearer token"})),
+        )
+    })
+}
+
+pub async fn submit_review(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
+    Json(req): Json<SubmitReviewRequest>,
+) -> Result<Json<Policy>, (StatusCode, Json<serde_json::Value>)> {
+    let before = state.policy_engine.get_policy(id).await;
+    let policy = state
+        .policy_engine
+        .submit_for_review(id, req.reviewers)
+        .await
+        .map_err(policy_error)?;
+    audit_policy_change(&state, &caller, &meta, before.as_ref(), &policy).await;
+    Ok(Json(policy))
+}
+
+pub async fn approve_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
+    Json(req): Json<ApproveRequest>,
+) -> Result<Json<Policy>, (StatusCode, Json<serde_json::Value>)> {
+    let reviewer = reviewer(&caller)?;
+    let before = state.policy_engine.get_policy(id).await;
+    let policy = state
+        .policy_engine
+        .approve_policy(id, &reviewer, req.comment)
+        .await
+        .map_err(policy_error)?;
+    info!("Policy {} approved by {}", id, reviewer);
+    audit_policy_change(&state, &caller, &meta, before.as_ref(), &policy).await;
+    Ok(Json(policy))
+}
+
+pub async fn reject_policy(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    caller: Caller,
+    meta: RequestMeta,
+    Json(req): Json<RejectRequest>,
+) -> Result<Json<Policy>, (StatusCode, Json<serde_json::Value>)> {
+    let reviewer = reviewer(&caller)?;
+    let before = state.policy_engine.get_policy(id).await;
+    let policy = state
+        .policy_engine
+        .reject_policy(id, &reviewer, &req.comment)
+        .await
+        .map_err(policy_error)?;
+    audit_policy_change(&state, &caller, &meta, before.as_ref(), &policy).await;
+    Ok(Json(policy))
+}
+
+/// Audit each of `before` that has since changed, against its current state
+pub async fn audit_policy_changes(state: &AppState, caller: &Caller, meta: &RequestMeta, before: &[Policy]) {
+    for old in before {
+        if let Some(new) = state.policy_engine.get_policy(old.id).await {
+            audit_policy_change(state, caller, meta, Some(old), &new).await;
+        }
+    }
+}
+
+/// Record a policy mutation as `policy.activate`, `policy.disable` or
+/// `policy.update`, with a before/after diff of the changed fields under
+/// `changes`. Content is diffed as a SHA-256 digest and length unless
+/// `audit_policy_content` is set, and the natural-language intent is masked
+/// while `tars_redact_intent` is. Nothing is written when nothing changed.
+async fn audit_policy_change(
+    state: &AppState,
+    caller: &Caller,
+    meta: &RequestMeta,
+    before: Option<&Policy>,
+    after: &Policy,
+) {
+    let Some(before) = before else {
+        return;
+    };
+    let mut changes = after.diff(before);
+    if changes.is_empty() {
+        return;
+    }
+    if let Some(content) = changes.get_mut("content").filter(|_| !state.config.audit_policy_content) {
+        *content = serde_json::json!({
+            "before": content_digest(&before.content),
+            "after": content_digest(&after.content),
+        });
+    }
+    if let Some(intent) = changes.get_mut("natural_language_intent").filter(|_| state.config.tars_redact_intent) {
+        for side in ["before", "after"] {
+            if !intent[side].is_null() {
+                intent[side] = "[REDACTED]".into();
+            }
+        }
+    }
+    let action = match &after.status {
+        status if *status == before.status => "policy.update",
+        PolicyStatus::Active => "policy.activate",
+        PolicyStatus::Disabled => "policy.disable",
+        _ => "policy.update",
+    };
+    let fields: Vec<&str> = changes.keys().map(String::as_str).collect();
+    let mut event = IdentityAuditEvent::new(
+        Uuid::nil(),
+        action,
+        caller.actor(),
+        &format!("policy/{}", after.id),
+        AuditDecision::Allow,
+        Some(format!("Policy '{}' changed: {}", after.name, fields.join(", "))),
+    );
+    event.metadata = AuditMetadata {
+        policy_id: Some(after.id),
+        ..meta.audit_metadata()
+    }
+    .with("namespace", after.namespace.as_str())
+    .with("changes", serde_json::Value::Object(changes));
+    if let Err(e) = state.append_audit(event).await {
+        warn!(policy_id = %after.id, action, "Policy change was not audited: {}", e);
+    }
+}
+
+fn content_digest(content: &str) -> serde_json::Value {
+    use sha2::{Digest, Sha256};
+    let hash: String = Sha256::digest(content.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect();
+    serde_json::json!({"sha256": hash, "bytes": content.len()})
+}
+
+pub async fn export_policies(
+    State(state): State<AppState>,
+    Query(query): Query<PolicyListQuery>,
+) -> Json<PolicyBundle> {
+    Json(
+        state
+            .policy_engine
+            .export_bundle(query.namespace.as_deref())
+            .await,
+    )
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub preserve_ids: bool,
+    /// Keep each policy's exported status instead of importing as Draft
+    #[serde(default)]
+    pub keep_status: bool,
+}
+
+pub async fn import_policies(
+    State(state): State<AppState>,
+    Query(query): Query<ImportQuery>,
+    Json(bundle): Json<PolicyBundle>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let results = state
+        .policy_engine
+        .import_bundle(bundle, query.preserve_ids, query.keep_status)
+        .await
+        .map_err(policy_error)?;
+    let imported = results.iter().filter(|r| r.imported).count();
+    info!("Imported {}/{} policies from bundle", imported, results.len());
+
+    Ok(Json(serde_json::json!({
+        "imported": imported,
+        "skipped": results.len() - imported,
+        "results": results,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use zedid_policy::models::PolicyKind;
+
+    #[tokio::test]
+    async fn test_unaudited_decision_fails_closed_when_audit_required() {
+        let mut config = AppConfig::load().unwrap();
+        config.audit_required = true;
+        let state = AppState::new(config).await.unwrap();
+        let req: PolicyDecisionRequest = serde_json::from_value(serde_json::json!({
+            "subject": "spiffe://tetrate.io/ns/production/sa/checkout",
+            "resource": "inventory-service",
+            "action": "GET",
+            "namespace": "production",
+            "context": {}
+        }))
+        .unwrap();
+        let mut allowed = state.policy_engine.evaluate(&req).await.unwrap();
+        assert!(allowed.allowed);
+
+        // Holding the audit lock makes the write time out
+        let guard = state.audit_log.write().await;
+        finalize_decision(&state, &req, &mut allowed, &Caller::default(), &RequestMeta::default()).await;
+        drop(guard);
+
+        assert!(!allowed.allowed);
+        assert_eq!(allowed.warnings.len(), 1);
+        assert_eq!(state.metrics.get(metrics::AUDIT_WRITE_FAILURES_TOTAL), 1);
+        assert!(state.audit_log.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_reviewer_is_taken_from_the_bearer_token() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let draft = Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        let draft = state.policy_engine.add_policy(draft).await.unwrap();
+        state.policy_engine.submit_for_review(draft.id, vec!["alice".to_string()]).await.unwrap();
+        let approve = |caller: Caller| {
+            approve_policy(
+                State(state.clone()),
+                Path(draft.id),
+                caller,
+                RequestMeta::default(),
+                Json(ApproveRequest { comment: None }),
             )
-        })
+        };
+
+        let Err((status, _)) = approve(Caller::default()).await else { panic!("anonymous approval") };
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let token = state
+            .jwt_service
+            .issue_token("alice", "alice", "production", "human", 3, None, 5, None)
+            .unwrap();
+        let alice = Caller::from_authorization(&state.jwt_service, Some(&format!("Bearer {}", token)));
+        let Json(approved) = approve(alice).await.unwrap();
+        assert_eq!(approved.status, PolicyStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_denials_get_a_human_explanation_unless_redacted() {
+        let mut config = AppConfig::load().unwrap();
+        config.explain_denials = true;
+        let state = AppState::new(config).await.unwrap();
+        let request = |action: &str| -> PolicyDecisionRequest {
+            serde_json::from_value(serde_json::json!({
+                "subject": "spiffe://tetrate.io/ns/production/sa/checkout",
+                "resource": "inventory-service",
+                "action": action,
+                "namespace": "production",
+                "context": {}
+            }))
+            .unwrap()
+        };
+        let finalize = |req: PolicyDecisionRequest| {
+            let state = state.clone();
+            async move {
+                let mut response = state.policy_engine.evaluate(&req).await.unwrap();
+                finalize_decision(&state, &req, &mut response, &Caller::default(), &RequestMeta::default()).await;
+                response
+            }
+        };
+
+        let allowed = finalize(request("GET")).await;
+        assert!(allowed.allowed && allowed.human_explanation.is_none());
+        let denied = finalize(request("DELETE")).await;
+        assert!(!denied.allowed);
+        let explanation = denied.human_explanation.unwrap();
+        assert!(explanation.starts_with("Simulated explanation"), "{}", explanation);
+        assert!(explanation.contains(&denied.reason));
+
+        let update: zedid_policy::models::UpdateNamespaceRequest =
+            serde_json::from_value(serde_json::json!({ "reason_verbosity": "redacted" })).unwrap();
+        state.policy_engine.update_namespace("production", update).await.unwrap();
+        let redacted = finalize(request("DELETE")).await;
+        assert_eq!(redacted.reason, "access denied");
+        assert!(redacted.human_explanation.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_policy_changes_are_audited_with_a_redacted_diff() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let active = state.policy_engine.list_policies(Some("production")).await;
+        let active = active.iter().find(|p| p.status == PolicyStatus::Active).unwrap();
+
+        let Json(disabled) = disable_policy(State(state.clone()), Path(active.id), Caller::default(), RequestMeta::default())
+            .await
+            .unwrap();
+        let mut edited = disabled.clone();
+        edited.content.push_str("\n# secret-tenant-id");
+        audit_policy_change(&state, &Caller::default(), &RequestMeta::default(), Some(&disabled), &edited).await;
+        // Unchanged policies are not audited
+        audit_policy_change(&state, &Caller::default(), &RequestMeta::default(), Some(&edited), &edited).await;
+
+        let log = state.audit_log.read().await;
+        assert_eq!(log.len(), 2);
+        assert_eq!((log[0].action.as_str(), log[0].actor.as_str()), ("policy.disable", "anonymous"));
+        assert_eq!(log[0].metadata.policy_id, Some(active.id));
+        let changes = &log[0].metadata.extra["changes"];
+        assert_eq!(changes["status"], serde_json::json!({"before": "active", "after": "disabled"}));
+        assert!(changes.get("updated_at").is_none());
+
+        assert_eq!(log[1].action, "policy.update");
+        let content = &log[1].metadata.extra["changes"]["content"];
+        assert_eq!(content["after"]["bytes"], edited.content.len());
+        assert_ne!(content["before"]["sha256"], content["after"]["sha256"]);
+        assert!(!content.to_string().contains("secret-tenant-id"));
+    }
 }
@@ -0,0 +1,83 @@
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use zedid_identity::spiffe::{validate_path, SpiffeId};
+
+#[derive(Deserialize)]
+pub struct ValidateQuery {
+    pub id: String,
+}
+
+#[derive(Serialize)]
+pub struct SpiffeIdReport {
+    pub valid: bool,
+    /// Canonical form: lowercase scheme and trust domain, collapsed slashes
+    pub spiffe_id: String,
+    pub trust_domain: String,
+    pub path: String,
+    pub path_segments: Vec<String>,
+    /// The trust domain is `ZEDID_TRUST_DOMAIN`
+    pub in_configured_domain: bool,
+    /// The trust domain is one of `ZEDID_TRUST_DOMAINS`
+    pub federated: bool,
+}
+
+/// Parse a SPIFFE ID with the same rules identity creation applies and
+/// report its parts. A malformed ID is 422 with `valid: false` and the reason.
+pub async fn validate_spiffe_id(
+    State(state): State<AppState>,
+    Query(query): Query<ValidateQuery>,
+) -> Result<Json<SpiffeIdReport>, (StatusCode, Json<serde_json::Value>)> {
+    let parsed = SpiffeId::parse(query.id.trim())
+        .and_then(|id| validate_path(&id.path).map(|_| id))
+        .map_err(|e| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(serde_json::json!({"valid": false, "id": query.id, "error": e.to_string()})),
+            )
+        })?;
+
+    let config = &state.config;
+    Ok(Json(SpiffeIdReport {
+        valid: true,
+        spiffe_id: parsed.to_normalized_uri(),
+        in_configured_domain: parsed.trust_domain.eq_ignore_ascii_case(&config.trust_domain),
+        federated: config.trust_domains.iter().any(|d| parsed.trust_domain.eq_ignore_ascii_case(d)),
+        path_segments: parsed.path.split('/').skip(1).map(str::to_string).collect(),
+        trust_domain: parsed.trust_domain,
+        path: parsed.path,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    #[tokio::test]
+    async fn test_validate_reports_components_and_domains() {
+        let mut config = AppConfig::load().unwrap();
+        config.trust_domain = "tetrate.io".to_string();
+        config.trust_domains = vec!["eu.tetrate.io".to_string()];
+        let state = AppState::new(config).await.unwrap();
+        let validate = |id: &str| validate_spiffe_id(State(state.clone()), Query(ValidateQuery { id: id.to_string() }));
+
+        let Json(local) = validate("spiffe://Tetrate.IO/ns/production//sa/checkout/").await.unwrap();
+        assert!(local.valid && local.in_configured_domain && !local.federated);
+        assert_eq!(local.spiffe_id, "spiffe://tetrate.io/ns/production/sa/checkout");
+        assert_eq!(local.path_segments, ["ns", "production", "sa", "checkout"]);
+
+        let Json(federated) = validate("spiffe://eu.tetrate.io/ns/payments/sa/refunds").await.unwrap();
+        assert!(federated.federated && !federated.in_configured_domain);
+
+        for bad in ["https://tetrate.io/ns/x", "spiffe:///ns/x", "spiffe://tetrate.io/ns/../admin", "spiffe://tetrate.io/"] {
+            let (status, Json(body)) = validate(bad).await.err().unwrap();
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{}", bad);
+            assert_eq!(body["valid"], false);
+        }
+    }
+}
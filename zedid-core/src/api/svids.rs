@@ -0,0 +1,34 @@
+use crate::state::AppState;
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use zedid_identity::IssuedSvid;
+
+#[derive(Deserialize)]
+pub struct SvidListQuery {
+    /// Only expired (`true`) or only still-valid (`false`) SVIDs
+    pub expired: Option<bool>,
+    /// Look up a single SPIFFE ID
+    pub spiffe_id: Option<String>,
+}
+
+pub async fn list_svids(
+    State(state): State<AppState>,
+    Query(query): Query<SvidListQuery>,
+) -> Json<serde_json::Value> {
+    let registry = state.issued_svids.read().await;
+    let svids: Vec<&IssuedSvid> = registry
+        .iter()
+        .filter(|s| query.expired.is_none_or(|expired| s.is_expired() == expired))
+        .filter(|s| query.spiffe_id.as_deref().is_none_or(|id| s.spiffe_id == id))
+        .collect();
+    let expired_count = registry.iter().filter(|s| s.is_expired()).count();
+
+    Json(serde_json::json!({
+        "svids": svids,
+        "total": svids.len(),
+        "expired_count": expired_count,
+    }))
+}
@@ -0,0 +1,125 @@
+use crate::state::AppState;
+use axum::{extract::rejection::JsonRejection, extract::State, Json};
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct IntrospectRequest {
+    pub token: String,
+    /// Resource server's namespace; the token must carry its `zedid:ns:` audience
+    #[serde(default)]
+    pub namespace: Option<String>,
+}
+
+/// RFC 7662-style introspection result. Inactive tokens carry only `active: false`.
+#[derive(Serialize, Default)]
+pub struct IntrospectResponse {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trust_level: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spiffe_id: Option<String>,
+}
+
+/// Validate a ZedID token on behalf of a resource server. Never errors:
+/// malformed requests and expired, invalid or revoked tokens are all
+/// `active: false`. A token is revoked once its identity is deactivated, removed
+/// or past its lease.
+pub async fn introspect(
+    State(state): State<AppState>,
+    req: Result<Json<IntrospectRequest>, JsonRejection>,
+) -> Json<IntrospectResponse> {
+    let Ok(Json(req)) = req else {
+        return Json(IntrospectResponse::default());
+    };
+    let token = req.token.trim();
+    let claims = match &req.namespace {
+        Some(namespace) => state.jwt_service.validate_token_for_namespace(token, namespace),
+        None => state.jwt_service.validate_token(token),
+    };
+    let Ok(claims) = claims else {
+        return Json(IntrospectResponse::default());
+    };
+    if !state.is_subject_live(&claims.sub).await {
+        return Json(IntrospectResponse::default());
+    }
+
+    Json(IntrospectResponse {
+        active: true,
+        sub: Some(claims.sub),
+        iss: Some(claims.iss),
+        aud: Some(claims.aud),
+        jti: Some(claims.jti),
+        name: Some(claims.name),
+        namespace: Some(claims.namespace),
+        kind: Some(claims.kind),
+        trust_level: Some(claims.trust_level),
+        exp: Some(claims.exp),
+        iat: Some(claims.iat),
+        spiffe_id: claims.spiffe_id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::auth::Caller;
+    use crate::config::AppConfig;
+
+    #[tokio::test]
+    async fn test_introspect_reports_active_and_revoked_tokens() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let identity = state.identities.read().await[0].clone();
+        let token = state
+            .jwt_service
+            .issue_token(&identity.id.to_string(), &identity.name, &identity.namespace, "workload", 3, None, 5, None)
+            .unwrap();
+        let introspect_token = |token: &str| {
+            introspect(State(state.clone()), Ok(Json(IntrospectRequest { token: token.to_string(), namespace: None })))
+        };
+
+        let Json(active) = introspect_token(&token).await;
+        assert!(active.active);
+        assert_eq!(active.sub.as_deref(), Some(identity.id.to_string().as_str()));
+        assert!(!introspect_token("not-a-jwt").await.active);
+
+        state.identities.write().await[0].is_active = false;
+        let Json(revoked) = introspect_token(&token).await;
+        assert!(!revoked.active && revoked.sub.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_revoked_identities_authenticate_as_anonymous() {
+        let state = AppState::new(AppConfig::load().unwrap()).await.unwrap();
+        let identity = state.identities.read().await[0].clone();
+        let token = state
+            .jwt_service
+            .issue_token(&identity.id.to_string(), &identity.name, &identity.namespace, "workload", 3, None, 5, None)
+            .unwrap();
+        let authorization = format!("Bearer {}", token);
+
+        let caller = Caller::authenticate(&state, Some(&authorization)).await;
+        assert_eq!(caller.actor(), identity.id.to_string());
+
+        state.identities.write().await[0].is_active = false;
+        let caller = Caller::authenticate(&state, Some(&authorization)).await;
+        assert!(caller.claims.is_none());
+    }
+}
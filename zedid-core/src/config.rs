@@ -1,23 +1,171 @@
 use anyhow::Result;
 use serde::Deserialize;
+use zedid_identity::spiffe::{DEFAULT_AGENT_PATH, DEFAULT_WORKLOAD_PATH};
+use zedid_identity::IdentityKind;
+use zedid_policy::generator::{GenerationFallback, GenerationLimits};
+use zedid_policy::CombiningAlgorithm;
+use crate::headers::DEFAULT_CONTENT_SECURITY_POLICY;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
     /// SPIFFE trust domain
     pub trust_domain: String,
+    /// Further trust domains `create_identity` may be asked for (`trust_domain` is always allowed)
+    pub trust_domains: Vec<String>,
+    /// Namespace for identities created without one
+    pub default_namespace: String,
+    /// SPIFFE path template for workloads/service accounts (`{namespace}`, `{name}`)
+    pub spiffe_workload_path: String,
+    /// SPIFFE path template for AI agents (`{namespace}`, `{name}`)
+    pub spiffe_agent_path: String,
     /// TARS endpoint URL
     pub tars_endpoint: String,
     /// TARS API key (optional)
     pub tars_api_key: Option<String>,
+    /// `kind=model` routes for live TARS generation; empty keeps the built-in table
+    pub tars_model_routes: Vec<String>,
+    /// Prompts of at least this many characters go to `tars_long_prompt_model` (0 disables)
+    pub tars_long_prompt_chars: usize,
+    pub tars_long_prompt_model: Option<String>,
+    /// Debug-log full TARS prompts and responses
+    pub tars_log_payloads: bool,
+    /// Mask the security intent in logged TARS prompts
+    pub tars_redact_intent: bool,
+    /// Consecutive TARS failures that open the circuit breaker (0 disables it)
+    pub tars_breaker_threshold: u32,
+    /// Seconds an open breaker waits before letting a probe request through
+    pub tars_breaker_cooldown_secs: u64,
+    /// Policy generation when TARS fails: error (default) or a flagged simulated policy
+    pub gen_fallback: GenerationFallback,
+    /// Identical generate requests within this many seconds reuse the earlier result (0 disables)
+    pub gen_dedup_window_secs: u64,
+    /// Input caps and prompt token budget for policy generation
+    pub gen_limits: GenerationLimits,
+    /// Most policies the store may hold (0 = unlimited)
+    pub max_policies: usize,
+    /// Most Draft policies per namespace (0 = unlimited)
+    pub max_drafts_per_namespace: usize,
     /// JWT signing secret
     pub jwt_secret: String,
     /// JWT issuer
     pub jwt_issuer: String,
+    /// Issuers accepted when validating tokens (defaults to `jwt_issuer`)
+    pub jwt_accepted_issuers: Vec<String>,
+    /// Audiences accepted when validating tokens (defaults to `zedid-api`)
+    pub jwt_accepted_audiences: Vec<String>,
+    /// Clock-skew tolerance for token validation, in seconds
+    pub jwt_leeway_secs: u64,
+    /// Add a `zedid:ns:<namespace>` audience to issued tokens
+    pub jwt_namespace_audiences: bool,
+    /// Longest token TTL, and furthest `not_before_minutes`, accepted at issuance (0 = unlimited)
+    pub jwt_max_ttl_minutes: i64,
+    /// How long a rotated-out signing key keeps verifying tokens
+    pub jwt_key_grace_minutes: i64,
+    /// Ask the policy engine (`token.issue`) before issuing a token
+    pub gate_token_issuance: bool,
     /// Database URL (SQLite for prototype, PostgreSQL for production)
     #[allow(dead_code)]
     pub database_url: String,
     /// Server port
     pub port: u16,
+    /// gRPC server port
+    pub grpc_port: u16,
+    /// Maximum accepted request body size in bytes (larger bodies get 413)
+    pub max_body_bytes: usize,
+    /// Responses smaller than this many bytes are sent uncompressed
+    pub compression_min_bytes: u16,
+    /// Accept HTTP/2 (h2c) alongside HTTP/1.1 on the REST listener
+    pub http2_enabled: bool,
+    /// Concurrent streams allowed per HTTP/2 connection
+    pub http2_max_concurrent_streams: u32,
+    /// HTTP/2 keep-alive ping interval in seconds (0 disables pings)
+    pub http2_keep_alive_interval_secs: u64,
+    /// Seconds to wait for a keep-alive ping ack before closing the connection
+    pub http2_keep_alive_timeout_secs: u64,
+    /// Reuse HTTP/1.1 connections across requests
+    pub http1_keep_alive: bool,
+    /// How long browsers may cache CORS preflight results, in seconds
+    pub cors_max_age_secs: u64,
+    /// `X-Frame-Options` value (None: header not sent)
+    pub frame_options: Option<String>,
+    /// `Content-Security-Policy` value (None: header not sent)
+    pub content_security_policy: Option<String>,
+    /// `Strict-Transport-Security` max-age for requests forwarded over HTTPS (0 disables)
+    pub hsts_max_age_secs: u64,
+    /// Decision cache TTL in milliseconds (0 disables the cache)
+    pub decision_cache_ttl_ms: u64,
+    /// Whether the decision cache key includes the request context
+    pub decision_cache_include_context: bool,
+    /// Allow Draft → Active without review (demo convenience)
+    pub allow_direct_activation: bool,
+    /// Refuse activation while a policy's attached tests fail
+    pub require_passing_tests: bool,
+    /// Accept `identity_snapshot` on decision requests
+    pub allow_identity_snapshots: bool,
+    /// Return a generic reason for denies to untrusted callers (namespaces may override)
+    pub redact_deny_reasons: bool,
+    /// How matching policies combine into one decision (namespaces may override)
+    pub combining_algorithm: CombiningAlgorithm,
+    /// Keep an identity's stored trust level after its SVID expires (default: drop to Low)
+    pub keep_trust_on_svid_expiry: bool,
+    /// Fraction of evaluations logged in full at debug level (0.0–1.0)
+    pub eval_log_sample: f64,
+    /// Log every denial in full regardless of the sample
+    pub eval_log_denials: bool,
+    /// Context fields masked in sampled evaluation logs
+    pub eval_log_redact: Vec<String>,
+    /// Context fields callers may set (unset: all but `mfa_verified`/`trust_level`)
+    pub context_allowlist: Option<Vec<String>>,
+    /// Reject evaluations with disallowed context fields instead of stripping them
+    pub reject_disallowed_context: bool,
+    /// Per-evaluation timeout in milliseconds (0 disables it)
+    pub eval_timeout_ms: u64,
+    /// Evaluations slower than this are logged with their request (0 disables it)
+    pub slow_eval_ms: u64,
+    /// Append every decision to this file as JSON lines in OPA's decision log format
+    pub decision_log_file: Option<String>,
+    /// External OPA server URL; when set, decisions are delegated to it
+    pub opa_url: Option<String>,
+    /// Rego package queried on the external OPA server
+    pub opa_package: Option<String>,
+    /// Repository to sync Rego policies from (Git sync disabled when unset)
+    pub policy_git_url: Option<String>,
+    pub policy_git_branch: String,
+    /// Directory within the repository holding the policies (default: the root)
+    pub policy_git_path: String,
+    /// Local checkout (default: `zedid-policy-git` in the temp directory)
+    pub policy_git_dir: Option<String>,
+    pub policy_git_interval_secs: u64,
+    /// Drop audit events older than this many days (unset keeps everything)
+    pub audit_retention_days: Option<i64>,
+    /// Cap on retained audit events; the oldest are evicted first (0 disables the cap)
+    pub audit_max_events: usize,
+    /// Write an audit event for every policy decision (denials are always audited)
+    pub audit_decisions: bool,
+    /// Deny any decision whose audit event cannot be written (implies `audit_decisions`)
+    pub audit_required: bool,
+    /// Ask TARS for a plain-language `human_explanation` of each denial
+    pub explain_denials: bool,
+    /// Record policy content verbatim in change audit events (default: a SHA-256 digest)
+    pub audit_policy_content: bool,
+    /// Allow `POST /admin/reset-demo` to wipe and re-seed the demo data
+    pub enable_demo_reset: bool,
+    /// Alert on SVIDs expiring within this many minutes (0 disables the checker)
+    pub svid_expiry_alert_minutes: i64,
+    /// SVID lifetime for workloads and service accounts
+    pub svid_ttl_workload_hours: i64,
+    /// SVID lifetime for AI agents
+    pub svid_ttl_agent_hours: i64,
+    /// SVID key pair type: ec-p256, ec-p384, rsa-2048 or rsa-4096 (checked at startup)
+    pub svid_key_alg: String,
+    /// Lease given to new AI agents, renewed via `renew-lease` (0: agents never expire)
+    pub agent_lease_minutes: i64,
+    /// Emit JSON logs instead of the human-readable format
+    pub log_json: bool,
+    /// Reject identities/policies whose namespace is not registered
+    pub strict_namespaces: bool,
+    /// Refuse to start when a seeded demo policy fails validation
+    pub strict_startup: bool,
 }
 
 impl AppConfig {
@@ -25,22 +173,298 @@ impl AppConfig {
         // Load from environment variables with defaults for prototype
         dotenvy::dotenv().ok();
 
+        let jwt_issuer = std::env::var("ZEDID_JWT_ISSUER")
+            .unwrap_or_else(|_| "zedid.tetrate.io".to_string());
+
         Ok(Self {
             trust_domain: std::env::var("ZEDID_TRUST_DOMAIN")
                 .unwrap_or_else(|_| "tetrate.io".to_string()),
+            trust_domains: csv_env("ZEDID_TRUST_DOMAINS").unwrap_or_default(),
+            default_namespace: std::env::var("ZEDID_DEFAULT_NAMESPACE")
+                .unwrap_or_else(|_| "default".to_string()),
+            spiffe_workload_path: std::env::var("ZEDID_SPIFFE_WORKLOAD_PATH")
+                .unwrap_or_else(|_| DEFAULT_WORKLOAD_PATH.to_string()),
+            spiffe_agent_path: std::env::var("ZEDID_SPIFFE_AGENT_PATH")
+                .unwrap_or_else(|_| DEFAULT_AGENT_PATH.to_string()),
             tars_endpoint: std::env::var("TARS_ENDPOINT")
                 .unwrap_or_else(|_| "simulation://tars.tetrate.io".to_string()),
             tars_api_key: std::env::var("TARS_API_KEY").ok(),
+            tars_model_routes: csv_env("ZEDID_TARS_MODEL_ROUTES").unwrap_or_default(),
+            tars_long_prompt_chars: std::env::var("ZEDID_TARS_LONG_PROMPT_CHARS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            tars_long_prompt_model: std::env::var("ZEDID_TARS_LONG_PROMPT_MODEL").ok(),
+            tars_log_payloads: std::env::var("ZEDID_TARS_LOG_PAYLOADS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            tars_redact_intent: std::env::var("ZEDID_TARS_REDACT_INTENT")
+                .map(|v| v != "false")
+                .unwrap_or(true),
+            tars_breaker_threshold: std::env::var("ZEDID_TARS_BREAKER_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+            tars_breaker_cooldown_secs: std::env::var("ZEDID_TARS_BREAKER_COOLDOWN_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            gen_fallback: match std::env::var("ZEDID_GEN_FALLBACK").as_deref() {
+                Ok("simulate") => GenerationFallback::Simulate,
+                _ => GenerationFallback::Error,
+            },
+            gen_dedup_window_secs: std::env::var("ZEDID_GEN_DEDUP_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            gen_limits: {
+                let defaults = GenerationLimits::default();
+                let limit = |name: &str, default: usize| {
+                    std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+                };
+                GenerationLimits {
+                    max_intent_chars: limit("ZEDID_GEN_MAX_INTENT_CHARS", defaults.max_intent_chars),
+                    max_hint_items: limit("ZEDID_GEN_MAX_HINT_ITEMS", defaults.max_hint_items),
+                    max_hint_chars: limit("ZEDID_GEN_MAX_HINT_CHARS", defaults.max_hint_chars),
+                    max_prompt_tokens: limit("ZEDID_GEN_MAX_PROMPT_TOKENS", defaults.max_prompt_tokens),
+                }
+            },
+            max_policies: std::env::var("ZEDID_MAX_POLICIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5000),
+            max_drafts_per_namespace: std::env::var("ZEDID_MAX_DRAFTS_PER_NAMESPACE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
             jwt_secret: std::env::var("ZEDID_JWT_SECRET")
                 .unwrap_or_else(|_| "zedid-dev-secret-change-in-production-please".to_string()),
-            jwt_issuer: std::env::var("ZEDID_JWT_ISSUER")
-                .unwrap_or_else(|_| "zedid.tetrate.io".to_string()),
+            jwt_accepted_issuers: csv_env("ZEDID_JWT_ACCEPTED_ISSUERS")
+                .unwrap_or_else(|| vec![jwt_issuer.clone()]),
+            jwt_accepted_audiences: csv_env("ZEDID_JWT_ACCEPTED_AUDIENCES")
+                .unwrap_or_else(|| vec![zedid_identity::jwt::DEFAULT_AUDIENCE.to_string()]),
+            jwt_issuer,
+            jwt_leeway_secs: std::env::var("ZEDID_JWT_LEEWAY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(zedid_identity::jwt::DEFAULT_LEEWAY_SECS),
+            jwt_namespace_audiences: std::env::var("ZEDID_JWT_NAMESPACE_AUDIENCES")
+                .map(|v| v != "false")
+                .unwrap_or(true),
+            jwt_max_ttl_minutes: std::env::var("ZEDID_JWT_MAX_TTL_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(zedid_identity::jwt::DEFAULT_MAX_TTL_MINUTES),
+            jwt_key_grace_minutes: std::env::var("ZEDID_JWT_KEY_GRACE_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(zedid_identity::jwt::DEFAULT_KEY_GRACE_MINUTES),
+            gate_token_issuance: std::env::var("ZEDID_GATE_TOKEN_ISSUANCE")
+                .map(|v| v == "true")
+                .unwrap_or(false),
             database_url: std::env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "sqlite::memory:".to_string()),
             port: std::env::var("PORT")
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .unwrap_or(8080),
+            grpc_port: std::env::var("ZEDID_GRPC_PORT")
+                .unwrap_or_else(|_| "50051".to_string())
+                .parse()
+                .unwrap_or(50051),
+            max_body_bytes: std::env::var("ZEDID_MAX_BODY_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1024 * 1024),
+            compression_min_bytes: std::env::var("ZEDID_COMPRESSION_MIN_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1024),
+            http2_enabled: std::env::var("ZEDID_HTTP2")
+                .map(|v| v != "false")
+                .unwrap_or(true),
+            http2_max_concurrent_streams: std::env::var("ZEDID_HTTP2_MAX_CONCURRENT_STREAMS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(256),
+            http2_keep_alive_interval_secs: std::env::var("ZEDID_HTTP2_KEEPALIVE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20),
+            http2_keep_alive_timeout_secs: std::env::var("ZEDID_HTTP2_KEEPALIVE_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20),
+            http1_keep_alive: std::env::var("ZEDID_HTTP1_KEEPALIVE")
+                .map(|v| v != "false")
+                .unwrap_or(true),
+            cors_max_age_secs: std::env::var("ZEDID_CORS_MAX_AGE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(600),
+            frame_options: header_env("ZEDID_FRAME_OPTIONS", "DENY"),
+            content_security_policy: header_env("ZEDID_CSP", DEFAULT_CONTENT_SECURITY_POLICY),
+            hsts_max_age_secs: std::env::var("ZEDID_HSTS_MAX_AGE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(31_536_000),
+            decision_cache_ttl_ms: std::env::var("ZEDID_DECISION_CACHE_TTL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            decision_cache_include_context: std::env::var("ZEDID_DECISION_CACHE_INCLUDE_CONTEXT")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            allow_direct_activation: std::env::var("ZEDID_ALLOW_DIRECT_ACTIVATION")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            require_passing_tests: std::env::var("ZEDID_REQUIRE_PASSING_TESTS")
+                .map(|v| v != "false")
+                .unwrap_or(true),
+            allow_identity_snapshots: std::env::var("ZEDID_ALLOW_IDENTITY_SNAPSHOTS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            redact_deny_reasons: std::env::var("ZEDID_REDACT_DENY_REASONS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            combining_algorithm: match std::env::var("ZEDID_COMBINING_ALGORITHM").as_deref() {
+                Ok("allow_overrides") => CombiningAlgorithm::AllowOverrides,
+                Ok("first_applicable") => CombiningAlgorithm::FirstApplicable,
+                Ok("permit_unless_deny") => CombiningAlgorithm::PermitUnlessDeny,
+                _ => CombiningAlgorithm::DenyOverrides,
+            },
+            keep_trust_on_svid_expiry: std::env::var("ZEDID_KEEP_TRUST_ON_SVID_EXPIRY")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            eval_log_sample: std::env::var("ZEDID_EVAL_LOG_SAMPLE")
+                .ok()
+                .and_then(|v| v.parse::<f64>().ok())
+                .map(|rate| rate.clamp(0.0, 1.0))
+                .unwrap_or(0.0),
+            eval_log_denials: std::env::var("ZEDID_EVAL_LOG_DENIALS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            eval_log_redact: csv_env("ZEDID_EVAL_LOG_REDACT").unwrap_or_default(),
+            context_allowlist: csv_env("ZEDID_CONTEXT_ALLOWLIST"),
+            reject_disallowed_context: std::env::var("ZEDID_CONTEXT_REJECT")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            eval_timeout_ms: std::env::var("ZEDID_EVAL_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1000),
+            slow_eval_ms: std::env::var("ZEDID_SLOW_EVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+            decision_log_file: std::env::var("ZEDID_DECISION_LOG_FILE").ok().filter(|path| !path.is_empty()),
+            opa_url: std::env::var("ZEDID_OPA_URL").ok().filter(|v| !v.is_empty()),
+            opa_package: std::env::var("ZEDID_OPA_PACKAGE").ok().filter(|v| !v.is_empty()),
+            policy_git_url: std::env::var("ZEDID_POLICY_GIT_URL").ok().filter(|v| !v.is_empty()),
+            policy_git_branch: std::env::var("ZEDID_POLICY_GIT_BRANCH")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .unwrap_or_else(|| "main".to_string()),
+            policy_git_path: std::env::var("ZEDID_POLICY_GIT_PATH").unwrap_or_default(),
+            policy_git_dir: std::env::var("ZEDID_POLICY_GIT_DIR").ok().filter(|v| !v.is_empty()),
+            policy_git_interval_secs: std::env::var("ZEDID_POLICY_GIT_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(300),
+            audit_retention_days: std::env::var("ZEDID_AUDIT_RETENTION_DAYS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|days| *days > 0),
+            audit_max_events: std::env::var("ZEDID_AUDIT_MAX_EVENTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10_000),
+            audit_decisions: std::env::var("ZEDID_AUDIT_DECISIONS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            audit_required: std::env::var("ZEDID_AUDIT_REQUIRED")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            explain_denials: std::env::var("ZEDID_EXPLAIN_DENIALS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            audit_policy_content: std::env::var("ZEDID_AUDIT_POLICY_CONTENT")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            enable_demo_reset: std::env::var("ZEDID_ENABLE_DEMO_RESET")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            svid_expiry_alert_minutes: std::env::var("ZEDID_SVID_EXPIRY_ALERT_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            svid_ttl_workload_hours: std::env::var("ZEDID_SVID_TTL_WORKLOAD_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|hours| *hours > 0)
+                .unwrap_or(1),
+            svid_ttl_agent_hours: std::env::var("ZEDID_SVID_TTL_AGENT_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|hours| *hours > 0)
+                .unwrap_or(4),
+            svid_key_alg: std::env::var("ZEDID_SVID_KEY_ALG")
+                .unwrap_or_else(|_| "ec-p256".to_string()),
+            agent_lease_minutes: std::env::var("ZEDID_AGENT_LEASE_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            log_json: std::env::var("ZEDID_LOG_FORMAT")
+                .map(|v| v.eq_ignore_ascii_case("json"))
+                .unwrap_or(false),
+            strict_namespaces: std::env::var("ZEDID_STRICT_NAMESPACES")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            strict_startup: std::env::var("ZEDID_STRICT_STARTUP")
+                .map(|v| v == "true")
+                .unwrap_or(false),
         })
     }
+
+    /// SVID lifetime for an identity kind (humans hold no SVID)
+    pub fn svid_ttl(&self, kind: &IdentityKind) -> chrono::Duration {
+        match kind {
+            IdentityKind::Workload | IdentityKind::ServiceAccount => {
+                chrono::Duration::hours(self.svid_ttl_workload_hours)
+            }
+            IdentityKind::AiAgent => chrono::Duration::hours(self.svid_ttl_agent_hours),
+            IdentityKind::Human => chrono::Duration::zero(),
+        }
+    }
+
+    /// Default lease for an identity kind; only AI agents have one
+    pub fn lease(&self, kind: &IdentityKind) -> Option<chrono::Duration> {
+        match kind {
+            IdentityKind::AiAgent if self.agent_lease_minutes > 0 => {
+                chrono::TimeDelta::try_minutes(self.agent_lease_minutes)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Comma-separated list from the environment; `None` when unset or empty
+fn csv_env(key: &str) -> Option<Vec<String>> {
+    let values: Vec<String> = std::env::var(key)
+        .ok()?
+        .split(',')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect();
+    (!values.is_empty()).then_some(values)
+}
+
+/// Response header value from the environment; set but empty disables the header
+fn header_env(key: &str, default: &str) -> Option<String> {
+    match std::env::var(key) {
+        Ok(v) if v.trim().is_empty() => None,
+        Ok(v) => Some(v),
+        Err(_) => Some(default.to_string()),
+    }
 }
@@ -0,0 +1,202 @@
+use crate::api::{auth::{Caller, RequestMeta}, identities::authorize_token_issue, policies::finalize_decision};
+use crate::state::AppState;
+use tonic::{Request, Response, Status};
+use tracing::info;
+use uuid::Uuid;
+use zedid_identity::IdentityError;
+use zedid_policy::models::PolicyDecisionRequest;
+
+pub mod proto {
+    tonic::include_proto!("zedid.v1");
+}
+
+use proto::zed_id_server::{ZedId, ZedIdServer};
+
+/// gRPC service — shares `AppState` with the REST handlers
+pub struct ZedIdGrpc {
+    state: AppState,
+}
+
+impl ZedIdGrpc {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    pub fn into_server(self) -> ZedIdServer<Self> {
+        ZedIdServer::new(self)
+    }
+}
+
+#[tonic::async_trait]
+impl ZedId for ZedIdGrpc {
+    async fn evaluate(
+        &self,
+        request: Request<proto::EvaluateRequest>,
+    ) -> Result<Response<proto::EvaluateResponse>, Status> {
+        let authorization = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let caller = Caller::authenticate(&self.state, authorization.as_deref()).await;
+        let meta = RequestMeta::from_headers(&request.metadata().clone().into_headers());
+        let req = request.into_inner();
+        let context = if req.context_json.is_empty() {
+            serde_json::Value::Object(serde_json::Map::new())
+        } else {
+            serde_json::from_str(&req.context_json)
+                .map_err(|e| Status::invalid_argument(format!("Invalid context_json: {}", e)))?
+        };
+
+        let decision_req = PolicyDecisionRequest {
+            subject: req.subject,
+            resource: req.resource,
+            action: req.action,
+            namespace: req.namespace,
+            context: context.into(),
+            skip_enrichment: req.skip_enrichment,
+            group: req
+                .group
+                .as_deref()
+                .map(Uuid::parse_str)
+                .transpose()
+                .map_err(|e| Status::invalid_argument(format!("Invalid group: {}", e)))?,
+            subject_token: req.subject_token,
+            identity_snapshot: None,
+            identity: None,
+        };
+
+        let mut decision = self
+            .state
+            .policy_engine
+            .evaluate(&decision_req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?;
+        finalize_decision(&self.state, &decision_req, &mut decision, &caller, &meta).await;
+
+        Ok(Response::new(proto::EvaluateResponse {
+            allowed: decision.allowed,
+            reason: decision.reason,
+            policy_id: decision.policy_id.map(|id| id.to_string()),
+            policy_name: decision.policy_name,
+            evaluation_time_ms: decision.evaluation_time_ms,
+            decision_id: decision.decision_id.to_string(),
+            cached: decision.cached,
+            enforcement_mode: enum_str(&decision.enforcement_mode),
+            would_deny: decision.would_deny,
+            warnings: decision.warnings,
+            human_explanation: decision.human_explanation,
+        }))
+    }
+
+    async fn issue_token(
+        &self,
+        request: Request<proto::IssueTokenRequest>,
+    ) -> Result<Response<proto::IssueTokenResponse>, Status> {
+        let meta = RequestMeta::from_headers(&request.metadata().clone().into_headers());
+        let req = request.into_inner();
+        let id = Uuid::parse_str(&req.identity_id)
+            .map_err(|_| Status::invalid_argument(format!("Invalid UUID: {}", req.identity_id)))?;
+
+        let identity = self
+            .state
+            .identities
+            .read()
+            .await
+            .iter()
+            .find(|i| i.id == id)
+            .cloned()
+            .ok_or_else(|| Status::not_found("Identity not found"))?;
+        if !identity.is_live(chrono::Utc::now()) {
+            return Err(Status::failed_precondition("Identity is inactive or its lease has expired"));
+        }
+        authorize_token_issue(&self.state, &identity, Default::default(), &meta)
+            .await
+            .map_err(Status::permission_denied)?;
+
+        let ttl = req.ttl_minutes.unwrap_or(60);
+        let not_before = req.not_before_minutes.unwrap_or(0);
+        let expires_in_seconds = not_before
+            .checked_add(ttl)
+            .and_then(|minutes| minutes.checked_mul(60))
+            .ok_or_else(|| Status::invalid_argument("Token lifetime is out of range"))?;
+        let trust_level = identity.trust_level.as_u8();
+        let kind = format!("{:?}", identity.kind).to_lowercase();
+
+        let token = self
+            .state
+            .jwt_service
+            .issue_token_with_claims(
+                &identity.id.to_string(),
+                &identity.name,
+                &identity.namespace,
+                &kind,
+                trust_level,
+                identity.spiffe_id.clone(),
+                ttl,
+                not_before,
+                req.audience.as_deref(),
+                identity.label_claims(),
+            )
+            .map_err(|e| match e {
+                IdentityError::ReservedClaim(_) | IdentityError::InvalidTokenLifetime(_) => {
+                    Status::invalid_argument(e.to_string())
+                }
+                _ => Status::internal(e.to_string()),
+            })?;
+
+        info!("gRPC token issued for identity {}", id);
+        self.state.mark_seen(id).await;
+
+        Ok(Response::new(proto::IssueTokenResponse {
+            token,
+            expires_in_seconds,
+            identity_id: id.to_string(),
+            kind,
+        }))
+    }
+
+    async fn get_identity(
+        &self,
+        request: Request<proto::GetIdentityRequest>,
+    ) -> Result<Response<proto::Identity>, Status> {
+        let raw = request.into_inner().id;
+        let id = Uuid::parse_str(&raw)
+            .map_err(|_| Status::invalid_argument(format!("Invalid UUID: {}", raw)))?;
+
+        let identities = self.state.identities.read().await;
+        let identity = identities
+            .iter()
+            .find(|i| i.id == id)
+            .ok_or_else(|| Status::not_found("Identity not found"))?;
+        let effective_trust_level = if self.state.config.keep_trust_on_svid_expiry {
+            identity.trust_level.clone()
+        } else {
+            identity.effective_trust_level()
+        };
+
+        Ok(Response::new(proto::Identity {
+            id: identity.id.to_string(),
+            name: identity.name.clone(),
+            kind: enum_str(&identity.kind),
+            trust_level: enum_str(&identity.trust_level),
+            spiffe_id: identity.spiffe_id.clone(),
+            email: identity.email.clone(),
+            namespace: identity.namespace.clone(),
+            labels: identity.labels.clone(),
+            created_at: identity.created_at.to_rfc3339(),
+            last_seen: identity.last_seen.to_rfc3339(),
+            is_active: identity.is_active,
+            svid_expiry: identity.svid_expiry.map(|e| e.to_rfc3339()),
+            effective_trust_level: enum_str(&effective_trust_level),
+        }))
+    }
+}
+
+/// Render a snake_case serde enum as its wire string (matches the REST JSON)
+fn enum_str<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
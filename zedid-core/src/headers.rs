@@ -0,0 +1,77 @@
+use crate::config::AppConfig;
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Default CSP for the dashboard. Inline handlers and styles in index.html
+/// need 'unsafe-inline'; fonts come from Google Fonts.
+pub const DEFAULT_CONTENT_SECURITY_POLICY: &str = "default-src 'self'; \
+    script-src 'self' 'unsafe-inline'; \
+    style-src 'self' 'unsafe-inline' https://fonts.googleapis.com; \
+    font-src 'self' https://fonts.gstatic.com; \
+    img-src 'self' data:; \
+    connect-src 'self'; \
+    frame-ancestors 'none'";
+
+/// Response headers added to every response (handlers may override them)
+#[derive(Debug, Clone)]
+pub struct SecurityHeaders {
+    frame_options: Option<HeaderValue>,
+    content_security_policy: Option<HeaderValue>,
+    /// `Strict-Transport-Security`, sent only on requests that reached the
+    /// proxy over HTTPS (`X-Forwarded-Proto: https`)
+    hsts: Option<HeaderValue>,
+}
+
+impl SecurityHeaders {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let value = |name: &str, v: &str| {
+            HeaderValue::from_str(v)
+                .map_err(|_| warn!("Ignoring invalid {} header value: {}", name, v))
+                .ok()
+        };
+        Self {
+            frame_options: config
+                .frame_options
+                .as_deref()
+                .and_then(|v| value("X-Frame-Options", v)),
+            content_security_policy: config
+                .content_security_policy
+                .as_deref()
+                .and_then(|v| value("Content-Security-Policy", v)),
+            hsts: (config.hsts_max_age_secs > 0)
+                .then(|| format!("max-age={}; includeSubDomains", config.hsts_max_age_secs))
+                .and_then(|v| value("Strict-Transport-Security", &v)),
+        }
+    }
+}
+
+pub async fn security_headers(
+    State(headers): State<Arc<SecurityHeaders>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let https = req
+        .headers()
+        .get("x-forwarded-proto")
+        .is_some_and(|v| v.as_bytes().eq_ignore_ascii_case(b"https"));
+    let mut res = next.run(req).await;
+    let out = res.headers_mut();
+    out.entry(header::X_CONTENT_TYPE_OPTIONS)
+        .or_insert(HeaderValue::from_static("nosniff"));
+    if let Some(v) = &headers.frame_options {
+        out.entry(header::X_FRAME_OPTIONS).or_insert(v.clone());
+    }
+    if let Some(v) = &headers.content_security_policy {
+        out.entry(header::CONTENT_SECURITY_POLICY).or_insert(v.clone());
+    }
+    if let Some(v) = headers.hsts.as_ref().filter(|_| https) {
+        out.entry(header::STRICT_TRANSPORT_SECURITY).or_insert(v.clone());
+    }
+    res
+}
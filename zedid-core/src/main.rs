@@ -1,49 +1,154 @@
 mod api;
 mod config;
+mod grpc;
+mod headers;
 mod state;
 
 use crate::config::AppConfig;
 use crate::state::AppState;
-use axum::{routing::get_service, Router};
+use axum::{extract::DefaultBodyLimit, routing::get_service, Router};
+use axum::{body::Body, http::Request, response::Response};
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
 use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 use tower_http::trace::TraceLayer;
-use tracing::{info};
+use tracing::{debug, error, info, warn, Span};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use zedid_policy::metrics::{self, Metrics};
+
+/// How often audit retention is enforced
+const AUDIT_PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+/// How often identities are scanned for SVIDs nearing expiry
+const SVID_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How often identities past their lease are deactivated
+const LEASE_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How often per-policy usage counters are flushed to the log
+const POLICY_STATS_FLUSH_INTERVAL: Duration = Duration::from_secs(300);
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize structured logging
-    tracing_subscriber::registry()
-        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-            "zedid=debug,tower_http=debug,axum=debug".into()
-        }))
-        .with(tracing_subscriber::fmt::layer().with_target(true))
-        .init();
+    // Load configuration (first, so the log format can be chosen)
+    let config = AppConfig::load()?;
+
+    // Initialize structured logging — pretty for local dev, JSON for aggregation
+    let registry = tracing_subscriber::registry().with(
+        EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "zedid=debug,tower_http=debug,axum=debug".into()),
+    );
+    if config.log_json {
+        registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(true)
+                    .with_span_list(false),
+            )
+            .init();
+    } else {
+        registry
+            .with(tracing_subscriber::fmt::layer().with_target(true))
+            .init();
+    }
 
     info!("🛡️  ZedID — Identity Dashboard & Policy Generator");
     info!("   Built with Rust × Tetrate TARS × Zero Trust");
     info!("   Tetrate Buildathon 2025");
 
-    // Load configuration
-    let config = AppConfig::load()?;
     info!("Trust domain: {}", config.trust_domain);
     info!("TARS endpoint: {}", config.tars_endpoint);
 
     // Initialize application state
     let state = AppState::new(config.clone()).await?;
 
-    // Static file directory (dashboard)
+    // gRPC server on its own port, sharing the same state
+    let grpc_addr: SocketAddr = format!("0.0.0.0:{}", config.grpc_port).parse()?;
+    let grpc_service = grpc::ZedIdGrpc::new(state.clone()).into_server();
+    tokio::spawn(async move {
+        info!("🔌 ZedID gRPC server listening on {}", grpc_addr);
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(grpc_service)
+            .serve(grpc_addr)
+            .await
+        {
+            error!("gRPC server failed: {}", e);
+        }
+    });
+
+    // Background audit retention
+    let pruner = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(AUDIT_PRUNE_INTERVAL);
+        loop {
+            interval.tick().await;
+            pruner.prune_audit_log().await;
+        }
+    });
+
+    // Background SVID expiry alerts
+    if config.svid_expiry_alert_minutes > 0 {
+        let checker = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SVID_EXPIRY_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                checker.check_svid_expiry().await;
+            }
+        });
+    }
+
+    // Background identity lease expiry
+    let leases = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(LEASE_EXPIRY_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            leases.expire_leases().await;
+        }
+    });
+
+    // Background policy sync from Git
+    if let Some(source) = state.policy_git.clone() {
+        let engine = Arc::clone(&state.policy_engine);
+        let every = Duration::from_secs(config.policy_git_interval_secs);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(every);
+            loop {
+                interval.tick().await;
+                // Failures are logged and reported by GET /policies/git-sync
+                let _ = source.sync(&engine).await;
+            }
+        });
+    }
+
+    // Background policy usage flush
+    let engine = Arc::clone(&state.policy_engine);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(POLICY_STATS_FLUSH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            engine.flush_policy_stats().await;
+        }
+    });
+
     // Static file directory (dashboard)
     // We check multiple locations to handle running from workspace root vs crate root
     let current_dir = std::env::current_dir().unwrap_or_default();
     let candidates = vec![
-        current_dir.join("static"),              // If running from zedid-core/
-        current_dir.join("zedid-core/static"),   // If running from workspace root
+        current_dir.join("static"),            // If running from zedid-core/
+        current_dir.join("zedid-core/static"), // If running from workspace root
     ];
-    
-    let static_dir = candidates.into_iter()
+
+    let static_dir = candidates
+        .into_iter()
         .find(|p| p.exists())
         .unwrap_or_else(|| current_dir.join("static"));
 
@@ -53,21 +158,69 @@ async fn main() -> anyhow::Result<()> {
         .not_found_service(ServeFile::new(static_dir.join("index.html")));
 
     // Build the router
+    let metrics = Arc::clone(&state.metrics);
     let app = Router::new()
         // API routes
-        .nest("/api/v1", api::router())
+        .nest(
+            "/api/v1",
+            api::router().layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                api::auth::audit_access_failures,
+            )),
+        )
+        // OIDC discovery at the root, where clients look for it
+        .merge(api::discovery::router())
         // Serve static dashboard files
         .nest_service("/static", ServeDir::new(&static_dir))
         // Serve index.html at root
         .fallback_service(get_service(serve_dir))
         .with_state(state)
+        .layer(DefaultBodyLimit::max(config.max_body_bytes))
+        // gzip/brotli per Accept-Encoding; event streams are never buffered
+        .layer(
+            CompressionLayer::new().compress_when(
+                SizeAbove::new(config.compression_min_bytes)
+                    .and(NotForContentType::GRPC)
+                    .and(NotForContentType::IMAGES)
+                    .and(NotForContentType::SSE),
+            ),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(headers::SecurityHeaders::from_config(&config)),
+            headers::security_headers,
+        ))
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
                 .allow_methods(Any)
-                .allow_headers(Any),
+                .allow_headers(Any)
+                .max_age(Duration::from_secs(config.cors_max_age_secs)),
         )
-        .layer(TraceLayer::new_for_http());
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(|req: &Request<Body>| {
+                    // Reuse the caller's request id when present so logs correlate across hops
+                    let request_id = req
+                        .headers()
+                        .get("x-request-id")
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string)
+                        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+                    tracing::info_span!(
+                        "request",
+                        request_id = %request_id,
+                        method = %req.method(),
+                        path = %req.uri().path(),
+                    )
+                })
+                .on_response(|res: &Response, latency: Duration, _span: &Span| {
+                    info!(
+                        status = res.status().as_u16(),
+                        latency_ms = latency.as_millis() as u64,
+                        "Request completed"
+                    );
+                }),
+        );
 
     let addr: SocketAddr = format!("0.0.0.0:{}", config.port).parse()?;
     info!("🚀 ZedID API server listening on http://{}", addr);
@@ -76,7 +229,69 @@ async fn main() -> anyhow::Result<()> {
     info!("🤖 System info at http://localhost:{}/api/v1/system/info", config.port);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    serve(listener, app, http_builder(&config), metrics).await
+}
+
+/// Connection settings for the REST listener. Sidecars that evaluate on every
+/// request benefit from HTTP/2 multiplexing and long-lived connections.
+fn http_builder(config: &AppConfig) -> auto::Builder<TokioExecutor> {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    builder.http1().keep_alive(config.http1_keep_alive);
+    let interval = match config.http2_keep_alive_interval_secs {
+        0 => None,
+        secs => Some(Duration::from_secs(secs)),
+    };
+    builder
+        .http2()
+        .timer(TokioTimer::new())
+        .max_concurrent_streams(config.http2_max_concurrent_streams)
+        .keep_alive_interval(interval)
+        .keep_alive_timeout(Duration::from_secs(config.http2_keep_alive_timeout_secs));
+    info!(
+        http2 = config.http2_enabled,
+        max_concurrent_streams = config.http2_max_concurrent_streams,
+        http1_keep_alive = config.http1_keep_alive,
+        "HTTP listener configured"
+    );
+    if config.http2_enabled {
+        builder
+    } else {
+        builder.http1_only()
+    }
+}
 
-    Ok(())
+/// Accept loop: one task per connection, tracked in the connection metrics
+async fn serve(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    builder: auto::Builder<TokioExecutor>,
+    metrics: Arc<Metrics>,
+) -> anyhow::Result<()> {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                // Transient (e.g. EMFILE); back off briefly rather than exit
+                warn!("Failed to accept connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let _ = stream.set_nodelay(true);
+        let service = TowerToHyperService::new(app.clone());
+        let builder = builder.clone();
+        let metrics = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            metrics.incr(metrics::HTTP_CONNECTIONS_TOTAL);
+            metrics.incr(metrics::HTTP_CONNECTIONS_ACTIVE);
+            if let Err(e) = builder
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                metrics.incr(metrics::HTTP_CONNECTION_ERRORS_TOTAL);
+                debug!(peer = %peer, "Connection closed with error: {}", e);
+            }
+            metrics.sub(metrics::HTTP_CONNECTIONS_ACTIVE, 1);
+        });
+    }
 }
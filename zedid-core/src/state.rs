@@ -1,14 +1,36 @@
 use crate::config::AppConfig;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
-use zedid_identity::{Identity, IdentityAuditEvent};
+use uuid::Uuid;
+use zedid_identity::{
+    AuditCheckpoint, AuditDecision, AuditMetadata, Identity, IdentityAuditEvent, IssuedSvid, Svid,
+    SvidKeyAlgorithm,
+};
 use zedid_identity::jwt::JwtService;
-use zedid_identity::spiffe::SpireClient;
-use zedid_policy::engine::PolicyEngine;
+use zedid_identity::spiffe::{SpiffePaths, SpireClient};
+use zedid_policy::engine::{EngineConfig, PolicyEngine};
 use zedid_policy::generator::PolicyGenerator;
-use zedid_policy::tars::TarsClient;
-use tracing::info;
+use zedid_policy::git::GitPolicySource;
+use zedid_policy::metrics::{self, Metrics};
+use zedid_policy::sinks::JsonlFileSink;
+use zedid_policy::tars::{ModelRouting, TarsClient, TarsLogging};
+use tracing::{info, warn};
+
+/// Longest an audit write waits for the log lock before it counts as failed
+const AUDIT_WRITE_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Objects seeded by `AppState::reset_demo`
+#[derive(Debug, Serialize)]
+pub struct DemoSeedCounts {
+    pub identities: usize,
+    pub namespaces: usize,
+    pub policies: usize,
+}
 
 /// Shared application state — injected into all axum handlers
 #[derive(Clone)]
@@ -16,38 +38,134 @@ pub struct AppState {
     pub config: AppConfig,
     pub identities: Arc<RwLock<Vec<Identity>>>,
     pub audit_log: Arc<RwLock<Vec<IdentityAuditEvent>>>,
+    /// Chain hash over events removed by retention pruning
+    pub audit_checkpoint: Arc<RwLock<Option<AuditCheckpoint>>>,
+    /// Latest SVID issued per SPIFFE ID
+    pub issued_svids: Arc<RwLock<Vec<IssuedSvid>>>,
+    /// SVID expiry already alerted on, per identity, so each SVID alerts once
+    pub svid_alerts: Arc<RwLock<HashMap<Uuid, DateTime<Utc>>>>,
     pub policy_engine: Arc<PolicyEngine>,
     pub policy_generator: Arc<PolicyGenerator>,
     pub jwt_service: Arc<JwtService>,
     pub spire_client: Arc<SpireClient>,
+    /// SPIFFE ID construction for new workloads and agents
+    pub spiffe_paths: Arc<SpiffePaths>,
+    pub metrics: Arc<Metrics>,
+    /// Set when `ZEDID_POLICY_GIT_URL` is configured
+    pub policy_git: Option<Arc<GitPolicySource>>,
 }
 
 impl AppState {
     pub async fn new(config: AppConfig) -> Result<Self> {
-        // Initialize SPIRE client
-        let spire_client = Arc::new(SpireClient::new(&config.trust_domain));
+        // Initialize SPIRE client; an unknown key algorithm fails startup
+        let key_algorithm = SvidKeyAlgorithm::parse(&config.svid_key_alg).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid ZEDID_SVID_KEY_ALG {:?}: expected ec-p256, ec-p384, rsa-2048 or rsa-4096",
+                config.svid_key_alg
+            )
+        })?;
+        let spire_client = Arc::new(SpireClient::new(&config.trust_domain).with_key_algorithm(key_algorithm));
+
+        // Configured SPIFFE path layout; a bad template fails startup
+        let spiffe_paths = Arc::new(
+            SpiffePaths::new(&config.trust_domain)
+                .with_workload_path(&config.spiffe_workload_path)?
+                .with_agent_path(&config.spiffe_agent_path)?,
+        );
 
         // Initialize JWT service
-        let jwt_service = Arc::new(JwtService::new(&config.jwt_secret, &config.jwt_issuer));
+        let jwt_service = Arc::new(
+            JwtService::new(&config.jwt_secret, &config.jwt_issuer)
+                .with_accepted_issuers(config.jwt_accepted_issuers.clone())
+                .with_accepted_audiences(config.jwt_accepted_audiences.clone())
+                .with_leeway(config.jwt_leeway_secs)
+                .with_max_ttl(config.jwt_max_ttl_minutes)
+                .with_key_grace(config.jwt_key_grace_minutes)
+                .with_namespace_audiences(config.jwt_namespace_audiences),
+        );
+
+        // Identity store, shared with the policy engine for input enrichment
+        let identities = Arc::new(RwLock::new(seed_demo_identities(&spiffe_paths)?));
+
+        // Counter registry shared by the engine and GET /metrics
+        let metrics = Arc::new(Metrics::new());
 
         // Initialize policy engine
-        let policy_engine = Arc::new(PolicyEngine::new());
+        let mut policy_engine = PolicyEngine::with_config(EngineConfig {
+            decision_cache_ttl_ms: config.decision_cache_ttl_ms,
+            cache_include_context: config.decision_cache_include_context,
+            allow_direct_activation: config.allow_direct_activation,
+            require_passing_tests: config.require_passing_tests,
+            allow_identity_snapshots: config.allow_identity_snapshots,
+            redact_deny_reasons: config.redact_deny_reasons,
+            combining_algorithm: config.combining_algorithm,
+            keep_trust_on_svid_expiry: config.keep_trust_on_svid_expiry,
+            trust_domain: config.trust_domain.clone(),
+            eval_timeout_ms: config.eval_timeout_ms,
+            opa_url: config.opa_url.clone(),
+            opa_package: config.opa_package.clone(),
+            eval_log_sample: config.eval_log_sample,
+            eval_log_denials: config.eval_log_denials,
+            eval_log_redact: config.eval_log_redact.clone(),
+            context_allowlist: config.context_allowlist.clone(),
+            reject_disallowed_context: config.reject_disallowed_context,
+            max_policies: config.max_policies,
+            max_drafts_per_namespace: config.max_drafts_per_namespace,
+            slow_eval_ms: config.slow_eval_ms,
+        })
+        .with_identities(Arc::clone(&identities))
+        .with_jwt_service(Arc::clone(&jwt_service))
+        .with_metrics(Arc::clone(&metrics));
+        if let Some(path) = &config.decision_log_file {
+            let sink = JsonlFileSink::open(path).await?.with_redacted(config.eval_log_redact.clone());
+            policy_engine = policy_engine.with_sink(Arc::new(sink));
+        }
+        let policy_engine = Arc::new(policy_engine);
 
         // Initialize TARS client
+        let mut routing = if config.tars_model_routes.is_empty() {
+            ModelRouting::default()
+        } else {
+            ModelRouting::from_routes(&config.tars_model_routes)?
+        };
+        if let Some(model) = &config.tars_long_prompt_model {
+            routing = routing.with_long_prompt_model(config.tars_long_prompt_chars, model);
+        }
         let tars_client = TarsClient::new(
             &config.tars_endpoint,
             config.tars_api_key.clone(),
-        );
+        )
+        .with_routing(routing)
+        .with_logging(TarsLogging {
+            log_payloads: config.tars_log_payloads,
+            redact_intent: config.tars_redact_intent,
+        })
+        .with_breaker(config.tars_breaker_threshold, Duration::from_secs(config.tars_breaker_cooldown_secs))
+        .with_metrics(Arc::clone(&metrics));
 
         // Initialize policy generator
-        let policy_generator = Arc::new(PolicyGenerator::new(
-            tars_client,
-            Arc::clone(&policy_engine),
-        ));
+        let policy_generator = Arc::new(
+            PolicyGenerator::new(tars_client, Arc::clone(&policy_engine))
+                .with_fallback(config.gen_fallback)
+                .with_dedup_window(Duration::from_secs(config.gen_dedup_window_secs))
+                .with_limits(config.gen_limits),
+        );
+
+        let policy_git = config.policy_git_url.as_deref().map(|url| {
+            let checkout = config
+                .policy_git_dir
+                .as_ref()
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(|| std::env::temp_dir().join("zedid-policy-git"));
+            Arc::new(GitPolicySource::new(url, &config.policy_git_branch, &config.policy_git_path, checkout))
+        });
 
         // Seed demo data
-        policy_engine.seed_demo_policies().await;
-        let identities = Arc::new(RwLock::new(seed_demo_identities(&config.trust_domain)));
+        policy_engine.seed_demo_namespaces().await;
+        let seed_failures = policy_engine.seed_demo_policies().await;
+        if config.strict_startup && !seed_failures.is_empty() {
+            anyhow::bail!("Startup self-check failed: {}", seed_failures.join(", "));
+        }
 
         info!("AppState initialized — ZedID ready");
 
@@ -55,24 +173,243 @@ impl AppState {
             config,
             identities,
             audit_log: Arc::new(RwLock::new(vec![])),
+            audit_checkpoint: Arc::new(RwLock::new(None)),
+            issued_svids: Arc::new(RwLock::new(vec![])),
+            svid_alerts: Arc::new(RwLock::new(HashMap::new())),
             policy_engine,
             policy_generator,
             jwt_service,
             spire_client,
+            spiffe_paths,
+            metrics,
+            policy_git,
         })
     }
+
+    /// Append an audit event, failing (and counting the failure) if the log
+    /// cannot be locked within `AUDIT_WRITE_TIMEOUT`. Callers that must not
+    /// act without an audit record check the result.
+    pub async fn append_audit(&self, event: IdentityAuditEvent) -> Result<(), String> {
+        event.metadata.validate()?;
+        match tokio::time::timeout(AUDIT_WRITE_TIMEOUT, self.audit_log.write()).await {
+            Ok(mut audit_log) => {
+                audit_log.push(event);
+                Ok(())
+            }
+            Err(_) => {
+                self.metrics.incr(metrics::AUDIT_WRITE_FAILURES_TOTAL);
+                warn!(
+                    action = %event.action,
+                    actor = %event.actor,
+                    "Audit write timed out; event dropped"
+                );
+                Err(format!(
+                    "audit log unavailable after {}ms",
+                    AUDIT_WRITE_TIMEOUT.as_millis()
+                ))
+            }
+        }
+    }
+
+    /// Whether `sub` (a token subject) names an identity that is active and
+    /// whose lease has not expired
+    pub async fn is_subject_live(&self, sub: &str) -> bool {
+        let now = chrono::Utc::now();
+        self.identities
+            .read()
+            .await
+            .iter()
+            .any(|i| i.id.to_string() == sub && i.is_live(now))
+    }
+
+    /// Note activity for an identity. The write lock is only taken when the
+    /// stored `last_seen` is older than `LAST_SEEN_RESOLUTION_SECS`.
+    pub async fn mark_seen(&self, id: Uuid) {
+        let now = chrono::Utc::now();
+        let stale = self
+            .identities
+            .read()
+            .await
+            .iter()
+            .any(|i| i.id == id && i.needs_last_seen_update(now));
+        if stale {
+            if let Some(identity) = self.identities.write().await.iter_mut().find(|i| i.id == id) {
+                identity.last_seen = now;
+            }
+        }
+    }
+
+    /// Apply audit retention: drop events past `audit_retention_days`, then the
+    /// oldest beyond `audit_max_events`. Pruned events are folded into the
+    /// checkpoint hash. Returns the number of events removed.
+    pub async fn prune_audit_log(&self) -> usize {
+        let mut audit_log = self.audit_log.write().await;
+
+        // Events are appended in time order, so expired ones form a prefix
+        let expired = self.config.audit_retention_days.map_or(0, |days| {
+            let cutoff = chrono::Utc::now() - chrono::Duration::days(days);
+            audit_log.iter().take_while(|e| e.timestamp < cutoff).count()
+        });
+        let over_cap = match self.config.audit_max_events {
+            0 => 0,
+            max => audit_log.len().saturating_sub(max),
+        };
+        let count = expired.max(over_cap);
+        if count == 0 {
+            return 0;
+        }
+
+        let pruned: Vec<IdentityAuditEvent> = audit_log.drain(..count).collect();
+        let mut checkpoint = self.audit_checkpoint.write().await;
+        *checkpoint = AuditCheckpoint::extend(checkpoint.as_ref(), &pruned);
+        info!(
+            pruned = count,
+            retained = audit_log.len(),
+            "Audit log pruned"
+        );
+        count
+    }
+
+    /// Deactivate every identity whose lease has run out, which also revokes
+    /// its tokens (introspection requires an active identity). Each expiry is
+    /// audited as `identity.lease_expired`. Returns the number deactivated.
+    pub async fn expire_leases(&self) -> usize {
+        let now = Utc::now();
+        let expired: Vec<Identity> = {
+            let mut identities = self.identities.write().await;
+            identities
+                .iter_mut()
+                .filter(|i| i.is_active && i.is_lease_expired(now))
+                .map(|identity| {
+                    identity.is_active = false;
+                    identity.clone()
+                })
+                .collect()
+        };
+        if expired.is_empty() {
+            return 0;
+        }
+        // Cached decisions were made with the identity still live
+        self.policy_engine.clear_decision_cache();
+
+        for identity in &expired {
+            let expires_at = identity.expires_at.unwrap_or(now);
+            warn!(
+                identity = %identity.name,
+                namespace = %identity.namespace,
+                expires_at = %expires_at,
+                "Identity lease expired; deactivated"
+            );
+            let mut event = IdentityAuditEvent::new(
+                identity.id,
+                "identity.lease_expired",
+                "system",
+                identity.spiffe_id.as_deref().unwrap_or(&identity.name),
+                AuditDecision::Deny,
+                Some(format!("Lease expired at {}; identity deactivated and its tokens revoked", expires_at.to_rfc3339())),
+            );
+            event.metadata = AuditMetadata::default().with("expires_at", expires_at.to_rfc3339());
+            if let Err(e) = self.append_audit(event).await {
+                warn!(identity = %identity.name, "Lease expiry was not audited: {}", e);
+            }
+            self.metrics.incr(metrics::IDENTITY_LEASES_EXPIRED_TOTAL);
+        }
+        expired.len()
+    }
+
+    /// Put the store back to its startup demo state: identities, namespaces
+    /// and policies are replaced with the seeded ones, and the audit log
+    /// (checkpoint included) and SVID registry are emptied
+    pub async fn reset_demo(&self) -> Result<DemoSeedCounts> {
+        let identities = seed_demo_identities(&self.spiffe_paths)?;
+        let seeded = identities.len();
+        *self.identities.write().await = identities;
+        self.issued_svids.write().await.clear();
+        self.svid_alerts.write().await.clear();
+        {
+            let mut audit_log = self.audit_log.write().await;
+            audit_log.clear();
+            *self.audit_checkpoint.write().await = None;
+        }
+        let (namespaces, policies) = self.policy_engine.reset_demo_data().await;
+        warn!(identities = seeded, namespaces, policies, "Demo data reset");
+        Ok(DemoSeedCounts { identities: seeded, namespaces, policies })
+    }
+
+    /// Emit an `svid.expiring` audit event for every SVID within the alert
+    /// threshold. Renewal moves the expiry, so a renewed SVID can alert again.
+    /// Returns the number of new alerts.
+    pub async fn check_svid_expiry(&self) -> usize {
+        let now = Utc::now();
+        let window = chrono::Duration::minutes(self.config.svid_expiry_alert_minutes);
+        let expiring: Vec<Identity> = self
+            .identities
+            .read()
+            .await
+            .iter()
+            .filter(|i| i.svid_expires_within(now, window))
+            .cloned()
+            .collect();
+
+        let mut alerts = self.svid_alerts.write().await;
+        let mut raised = 0;
+        for identity in expiring {
+            let expiry = identity.svid_expiry.unwrap_or(now);
+            if alerts.get(&identity.id) == Some(&expiry) {
+                continue;
+            }
+            alerts.insert(identity.id, expiry);
+            warn!(
+                identity = %identity.name,
+                namespace = %identity.namespace,
+                svid_expiry = %expiry,
+                "SVID expiring without renewal"
+            );
+            let mut event = IdentityAuditEvent::new(
+                identity.id,
+                "svid.expiring",
+                "system",
+                identity.spiffe_id.as_deref().unwrap_or(&identity.name),
+                AuditDecision::Error,
+                Some(format!("SVID expires at {}", expiry.to_rfc3339())),
+            );
+            event.metadata = AuditMetadata::default()
+                .with("svid_expiry", expiry.to_rfc3339())
+                .with("expired", expiry <= now);
+            if let Err(e) = self.append_audit(event).await {
+                warn!(identity = %identity.name, "SVID expiry alert was not audited: {}", e);
+            }
+            self.metrics.incr(metrics::SVID_EXPIRING_TOTAL);
+            raised += 1;
+        }
+        raised
+    }
+
+    /// Record an SVID issuance. Re-issuing for a known SPIFFE ID is a renewal
+    /// and replaces that entry rather than adding a new one.
+    pub async fn record_svid(&self, identity_id: Uuid, svid: &Svid) {
+        let mut registry = self.issued_svids.write().await;
+        let mut record = IssuedSvid::from_svid(identity_id, svid);
+        match registry.iter_mut().find(|r| r.spiffe_id == svid.spiffe_id) {
+            Some(existing) => {
+                record.renewals = existing.renewals + 1;
+                *existing = record;
+            }
+            None => registry.push(record),
+        }
+    }
 }
 
-fn seed_demo_identities(trust_domain: &str) -> Vec<Identity> {
+fn seed_demo_identities(paths: &SpiffePaths) -> Result<Vec<Identity>> {
     use zedid_identity::TrustLevel;
 
-    vec![
-        Identity::new_workload("checkout-service", "production", trust_domain),
-        Identity::new_workload("payment-service", "production", trust_domain),
-        Identity::new_workload("inventory-service", "production", trust_domain),
-        Identity::new_workload("auth-service", "platform", trust_domain),
-        Identity::new_ai_agent("tars-policy-agent", "ai-platform", trust_domain),
-        Identity::new_ai_agent("anomaly-detector", "ai-platform", trust_domain),
+    Ok(vec![
+        paths.workload("checkout-service", "production")?,
+        paths.workload("payment-service", "production")?,
+        paths.workload("inventory-service", "production")?,
+        paths.workload("auth-service", "platform")?,
+        paths.ai_agent("tars-policy-agent", "ai-platform")?,
+        paths.ai_agent("anomaly-detector", "ai-platform")?,
         Identity::new_human("alice.chen", "alice.chen@tetrate.io", "platform"),
         Identity::new_human("bob.kumar", "bob.kumar@tetrate.io", "production"),
         {
@@ -80,5 +417,5 @@ fn seed_demo_identities(trust_domain: &str) -> Vec<Identity> {
             admin.trust_level = TrustLevel::Critical;
             admin
         },
-    ]
+    ])
 }
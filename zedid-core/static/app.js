@@ -305,7 +305,9 @@ function renderPolicies(filter = '') {
           <div class="policy-desc">${p.description}</div>
         </div>
         <div class="policy-actions">
-          ${p.status === 'active'
+          ${p.source === 'git'
+            ? `<span class="ai-badge" title="Synced from ${p.source_path || 'Git'} — change it in the repository">🔒 Git</span>`
+            : p.status === 'active'
             ? `<button class="btn btn-sm btn-danger" onclick="togglePolicy('${p.id}', 'disable')">Disable</button>`
             : `<button class="btn btn-sm btn-primary" onclick="togglePolicy('${p.id}', 'activate')">Activate</button>`}
         </div>
@@ -314,6 +316,7 @@ function renderPolicies(filter = '') {
         <span class="policy-kind-badge">${kindLabel(p.kind)}</span>
         <span class="policy-status-badge status-${p.status}-badge">${p.status}</span>
         ${p.ai_generated ? `<span class="ai-badge">🤖 AI Generated${p.ai_model_used ? ' · ' + p.ai_model_used : ''}</span>` : ''}
+        ${p.is_simulated ? `<span class="ai-badge" title="Generated in TARS simulation mode — not real model output">⚠️ Simulated</span>` : ''}
         <span style="font-size:11px;color:var(--text-muted)">ns: ${p.namespace}</span>
         ${p.validation_passed ? '<span style="font-size:11px;color:var(--green)">✓ Validated</span>' : '<span style="font-size:11px;color:var(--amber)">⚠ Not validated</span>'}
       </div>
@@ -442,6 +445,7 @@ async function simulateGeneration(body) {
     validation_result: { passed: true, errors: [], warnings: ['Review before production deployment'], coverage_score: 0.85 },
     generation_time_ms: Math.floor(1200 + Math.random() * 800),
     model_used: 'gemini-2.0-flash [via TARS simulation]',
+    routing_reason: 'simulation mode: no TARS API key configured',
     tokens_used: Math.floor(400 + Math.random() * 600),
   };
 
@@ -628,7 +632,8 @@ function renderGeneratedPolicy(data) {
   output.innerHTML = `
     <div class="generated-policy-output">
       <div class="gen-meta">
-        <div class="gen-meta-item">🤖 <strong>Model:</strong> ${data.model_used}</div>
+        ${data.degraded ? `<div class="gen-meta-item" title="${data.routing_reason}">⚠️ <strong>Degraded:</strong> TARS unavailable, simulated policy</div>` : ''}
+        <div class="gen-meta-item"${data.routing_reason ? ` title="${data.routing_reason}"` : ''}>🤖 <strong>Model:</strong> ${data.model_used}</div>
         <div class="gen-meta-item">⚡ <strong>Time:</strong> ${data.generation_time_ms}ms</div>
         ${data.tokens_used ? `<div class="gen-meta-item">🔤 <strong>Tokens:</strong> ${data.tokens_used}</div>` : ''}
         <div class="gen-meta-item">📋 <strong>Format:</strong> ${kindLabel(p.kind)}</div>
@@ -5,6 +5,9 @@ pub enum IdentityError {
     #[error("Invalid SPIFFE ID: {0}")]
     InvalidSpiffeId(String),
 
+    #[error("Invalid namespace: {0}")]
+    InvalidNamespace(String),
+
     #[error("SVID expired for workload: {0}")]
     SvidExpired(String),
 
@@ -17,6 +20,12 @@ pub enum IdentityError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    #[error("Reserved claim cannot be overridden: {0}")]
+    ReservedClaim(String),
+
+    #[error("Invalid token lifetime: {0}")]
+    InvalidTokenLifetime(String),
+
     #[error("Cryptographic error: {0}")]
     CryptoError(String),
 
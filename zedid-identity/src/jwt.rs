@@ -1,7 +1,9 @@
 use crate::error::IdentityError;
-use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
 
 /// JWT Claims for ZedID identity tokens
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +18,9 @@ pub struct ZedIdClaims {
     pub exp: i64,
     /// Issued at (Unix timestamp)
     pub iat: i64,
+    /// Not valid before (Unix timestamp), set on post-dated tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nbf: Option<i64>,
     /// JWT ID
     pub jti: String,
     /// Identity name
@@ -28,23 +33,208 @@ pub struct ZedIdClaims {
     pub trust_level: u8,
     /// SPIFFE ID (if workload)
     pub spiffe_id: Option<String>,
+    /// Application-specific claims (e.g. `department`, `region`), flattened
+    /// into the top level of the token
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Claims set by ZedID itself (plus registered JWT claims), which `extra`
+/// may not override
+pub const RESERVED_CLAIMS: &[&str] = &[
+    "sub", "iss", "aud", "exp", "iat", "nbf", "jti", "name", "namespace", "kind", "trust_level", "spiffe_id",
+];
+
+/// Claims present in issued tokens (`spiffe_id` only for workloads and
+/// agents, `nbf` only for post-dated tokens)
+pub const ISSUED_CLAIMS: &[&str] = &[
+    "sub", "iss", "aud", "exp", "iat", "nbf", "jti", "name", "namespace", "kind", "trust_level", "spiffe_id",
+];
+
+/// Audience used when a token is issued without an explicit one
+pub const DEFAULT_AUDIENCE: &str = "zedid-api";
+/// Prefix of the audience naming a token's namespace (`zedid:ns:production`)
+pub const NAMESPACE_AUDIENCE_PREFIX: &str = "zedid:ns:";
+/// Default clock-skew tolerance for `exp`/`nbf`/`iat` checks, in seconds
+pub const DEFAULT_LEEWAY_SECS: u64 = 30;
+/// Default cap on a token's lifetime, in minutes
+pub const DEFAULT_MAX_TTL_MINUTES: i64 = 24 * 60;
+/// Default time a rotated-out key keeps verifying tokens, in minutes: long
+/// enough for any token signed just before the rotation to expire
+pub const DEFAULT_KEY_GRACE_MINUTES: i64 = DEFAULT_MAX_TTL_MINUTES;
+
+/// One HMAC key of the keyset
+struct SigningKey {
+    kid: String,
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    /// When the key was rotated out; it verifies for the grace period after
+    retired_at: Option<DateTime<Utc>>,
+}
+
+impl SigningKey {
+    fn new(secret: &str) -> Self {
+        Self {
+            kid: key_id(secret),
+            encoding: EncodingKey::from_secret(secret.as_bytes()),
+            decoding: DecodingKey::from_secret(secret.as_bytes()),
+            retired_at: None,
+        }
+    }
+}
+
+/// Key id derived from the secret, so replicas sharing a secret agree on it
+fn key_id(secret: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A verification key as published in the JWKS. Keys are HMAC secrets and
+/// are never published, so there is no `k`: the entry tells resource servers
+/// which `kid`s are live; tokens are still verified by introspection.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedKey {
+    pub kty: &'static str,
+    pub kid: String,
+    pub alg: &'static str,
+    #[serde(rename = "use")]
+    pub key_use: &'static str,
+    /// When a rotated-out key stops verifying tokens (unset for the signing key)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 pub struct JwtService {
-    encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    /// The signing key first, then rotated-out keys still verifying tokens
+    keys: RwLock<Vec<SigningKey>>,
+    /// How long a rotated-out key keeps verifying tokens
+    key_grace: Duration,
     issuer: String,
+    /// Issuers `validate_token` accepts (defaults to this service's own issuer)
+    accepted_issuers: Vec<String>,
+    /// Audiences `validate_token` accepts (defaults to `DEFAULT_AUDIENCE`)
+    accepted_audiences: Vec<String>,
+    /// Clock-skew tolerance applied to `exp`, `nbf` and `iat`
+    leeway_secs: u64,
+    /// Longest TTL, and furthest `nbf`, a token may be issued with (0 = unlimited)
+    max_ttl_minutes: i64,
+    /// Add the identity's namespace audience to every issued token
+    namespace_audiences: bool,
+}
+
+/// Audience scoping a token to `namespace`
+pub fn namespace_audience(namespace: &str) -> String {
+    format!("{}{}", NAMESPACE_AUDIENCE_PREFIX, namespace)
 }
 
 impl JwtService {
     pub fn new(secret: &str, issuer: &str) -> Self {
         Self {
-            encoding_key: EncodingKey::from_secret(secret.as_bytes()),
-            decoding_key: DecodingKey::from_secret(secret.as_bytes()),
+            keys: RwLock::new(vec![SigningKey::new(secret)]),
+            key_grace: Duration::minutes(DEFAULT_KEY_GRACE_MINUTES),
             issuer: issuer.to_string(),
+            accepted_issuers: vec![issuer.to_string()],
+            accepted_audiences: vec![DEFAULT_AUDIENCE.to_string()],
+            leeway_secs: DEFAULT_LEEWAY_SECS,
+            max_ttl_minutes: DEFAULT_MAX_TTL_MINUTES,
+            namespace_audiences: false,
+        }
+    }
+
+    /// Keep rotated-out keys verifying tokens for `minutes` after rotation
+    pub fn with_key_grace(mut self, minutes: i64) -> Self {
+        self.key_grace = Duration::minutes(minutes);
+        self
+    }
+
+    /// Sign new tokens with `secret` from now on. The previous signing key
+    /// keeps verifying tokens for the grace period, and keys whose grace
+    /// period is over are dropped. Returns the new key's `kid`.
+    pub fn rotate_key(&self, secret: &str) -> Result<String, IdentityError> {
+        let key = SigningKey::new(secret);
+        let kid = key.kid.clone();
+        let now = Utc::now();
+        let mut keys = self.keys.write().unwrap();
+        if keys.iter().any(|k| k.kid == kid) {
+            return Err(IdentityError::CryptoError(format!("Key {} is already in the keyset", kid)));
+        }
+        if let Some(current) = keys.first_mut() {
+            current.retired_at = Some(now);
+        }
+        keys.retain(|k| self.is_live(k, now));
+        keys.insert(0, key);
+        tracing::info!(kid = %kid, verifying = keys.len() - 1, "JWT signing key rotated");
+        Ok(kid)
+    }
+
+    /// `kid` of the key new tokens are signed with
+    pub fn signing_kid(&self) -> String {
+        self.keys.read().unwrap()[0].kid.clone()
+    }
+
+    /// Every key that currently verifies tokens, signing key first
+    pub fn published_keys(&self) -> Vec<PublishedKey> {
+        let now = Utc::now();
+        self.keys
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|k| self.is_live(k, now))
+            .map(|k| PublishedKey {
+                kty: "oct",
+                kid: k.kid.clone(),
+                alg: "HS256",
+                key_use: "sig",
+                expires_at: k.retired_at.map(|at| at + self.key_grace),
+            })
+            .collect()
+    }
+
+    fn is_live(&self, key: &SigningKey, now: DateTime<Utc>) -> bool {
+        key.retired_at.is_none_or(|at| now < at + self.key_grace)
+    }
+
+    /// Refuse to issue tokens living longer than `minutes`, or becoming valid
+    /// further than `minutes` ahead (0 lifts the cap)
+    pub fn with_max_ttl(mut self, minutes: i64) -> Self {
+        self.max_ttl_minutes = minutes;
+        self
+    }
+
+    /// Also mint tokens for `zedid:ns:<namespace>`, so a resource server can
+    /// accept only its own namespace with `validate_token_for_namespace`
+    pub fn with_namespace_audiences(mut self, enabled: bool) -> Self {
+        self.namespace_audiences = enabled;
+        self
+    }
+
+    /// Tolerate this much clock drift between issuer and validator
+    pub fn with_leeway(mut self, leeway_secs: u64) -> Self {
+        self.leeway_secs = leeway_secs;
+        self
+    }
+
+    /// Accept tokens from any of these issuers (e.g. federated ZedID instances)
+    pub fn with_accepted_issuers(mut self, issuers: Vec<String>) -> Self {
+        if !issuers.is_empty() {
+            self.accepted_issuers = issuers;
         }
+        self
     }
 
+    /// Accept tokens minted for any of these audiences
+    pub fn with_accepted_audiences(mut self, audiences: Vec<String>) -> Self {
+        if !audiences.is_empty() {
+            self.accepted_audiences = audiences;
+        }
+        self
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn issue_token(
         &self,
         subject: &str,
@@ -54,37 +244,148 @@ impl JwtService {
         trust_level: u8,
         spiffe_id: Option<String>,
         ttl_minutes: i64,
+        audience: Option<&str>,
     ) -> Result<String, IdentityError> {
+        self.issue_token_with_claims(
+            subject,
+            name,
+            namespace,
+            kind,
+            trust_level,
+            spiffe_id,
+            ttl_minutes,
+            0,
+            audience,
+            HashMap::new(),
+        )
+    }
+
+    /// `issue_token` with additional custom claims, valid from
+    /// `not_before_minutes` from now (0 for immediately) for `ttl_minutes`.
+    /// Fails with `ReservedClaim` if an extra claim would shadow one in
+    /// `RESERVED_CLAIMS`, and with `InvalidTokenLifetime` if either duration
+    /// is out of range.
+    #[allow(clippy::too_many_arguments)]
+    pub fn issue_token_with_claims(
+        &self,
+        subject: &str,
+        name: &str,
+        namespace: &str,
+        kind: &str,
+        trust_level: u8,
+        spiffe_id: Option<String>,
+        ttl_minutes: i64,
+        not_before_minutes: i64,
+        audience: Option<&str>,
+        extra: HashMap<String, serde_json::Value>,
+    ) -> Result<String, IdentityError> {
+        if let Some(reserved) = extra.keys().find(|k| RESERVED_CLAIMS.contains(&k.as_str())) {
+            return Err(IdentityError::ReservedClaim(reserved.clone()));
+        }
+        self.check_lifetime(ttl_minutes, not_before_minutes)?;
         let now = Utc::now();
-        let exp = now + Duration::minutes(ttl_minutes);
+        let after = |t: DateTime<Utc>, minutes: i64| Duration::try_minutes(minutes).and_then(|d| t.checked_add_signed(d));
+        let (nbf, exp) = after(now, not_before_minutes)
+            .and_then(|nbf| Some((nbf, after(nbf, ttl_minutes)?)))
+            .ok_or_else(|| IdentityError::InvalidTokenLifetime("token lifetime is out of range".to_string()))?;
+        let mut aud = vec![audience.unwrap_or(DEFAULT_AUDIENCE).to_string()];
+        if self.namespace_audiences && aud[0] != namespace_audience(namespace) {
+            aud.push(namespace_audience(namespace));
+        }
 
         let claims = ZedIdClaims {
             sub: subject.to_string(),
             iss: self.issuer.clone(),
-            aud: vec!["zedid-api".to_string()],
+            aud,
             exp: exp.timestamp(),
             iat: now.timestamp(),
+            nbf: (not_before_minutes > 0).then(|| nbf.timestamp()),
             jti: uuid::Uuid::new_v4().to_string(),
             name: name.to_string(),
             namespace: namespace.to_string(),
             kind: kind.to_string(),
             trust_level,
             spiffe_id,
+            extra,
         };
 
-        let header = Header::new(Algorithm::HS256);
-        encode(&header, &claims, &self.encoding_key)
-            .map_err(|e| IdentityError::JwtValidationFailed(e.to_string()))
+        self.sign(&claims)
+    }
+
+    /// Sign with the current key, stamping its `kid`
+    fn sign(&self, claims: &ZedIdClaims) -> Result<String, IdentityError> {
+        let keys = self.keys.read().unwrap();
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(keys[0].kid.clone());
+        encode(&header, claims, &keys[0].encoding).map_err(|e| IdentityError::JwtValidationFailed(e.to_string()))
+    }
+
+    fn check_lifetime(&self, ttl_minutes: i64, not_before_minutes: i64) -> Result<(), IdentityError> {
+        if ttl_minutes <= 0 {
+            return Err(IdentityError::InvalidTokenLifetime("ttl_minutes must be positive".to_string()));
+        }
+        if not_before_minutes < 0 {
+            return Err(IdentityError::InvalidTokenLifetime("not_before_minutes cannot be negative".to_string()));
+        }
+        let max = self.max_ttl_minutes;
+        if max > 0 && (ttl_minutes > max || not_before_minutes > max) {
+            return Err(IdentityError::InvalidTokenLifetime(format!(
+                "ttl_minutes and not_before_minutes may not exceed {}",
+                max
+            )));
+        }
+        Ok(())
     }
 
     pub fn validate_token(&self, token: &str) -> Result<ZedIdClaims, IdentityError> {
+        self.validate(token, &self.accepted_audiences)
+    }
+
+    /// `validate_token` for a resource server in `namespace`: the token must
+    /// carry that namespace's audience, whatever else it was minted for
+    pub fn validate_token_for_namespace(&self, token: &str, namespace: &str) -> Result<ZedIdClaims, IdentityError> {
+        self.validate(token, &[namespace_audience(namespace)])
+    }
+
+    fn validate(&self, token: &str, audiences: &[String]) -> Result<ZedIdClaims, IdentityError> {
         let mut validation = Validation::new(Algorithm::HS256);
-        validation.set_audience(&["zedid-api"]);
-        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(audiences);
+        validation.set_issuer(&self.accepted_issuers);
+        validation.validate_nbf = true;
+        validation.leeway = self.leeway_secs;
+
+        // The `kid` picks the verification key; tokens from before key ids
+        // were stamped are tried against every live key
+        let kid = decode_header(token)
+            .map_err(|e| IdentityError::JwtValidationFailed(e.to_string()))?
+            .kid;
+        let now = Utc::now();
+        let keys = self.keys.read().unwrap();
+        let mut candidates = keys
+            .iter()
+            .filter(|k| self.is_live(k, now) && kid.as_ref().is_none_or(|kid| *kid == k.kid))
+            .peekable();
+        if candidates.peek().is_none() {
+            return Err(IdentityError::JwtValidationFailed("Unknown or retired signing key".to_string()));
+        }
+        let mut result = Err(IdentityError::JwtValidationFailed("No verification key".to_string()));
+        for key in candidates {
+            result = decode::<ZedIdClaims>(token, &key.decoding, &validation)
+                .map(|data| data.claims)
+                .map_err(|e| IdentityError::JwtValidationFailed(e.to_string()));
+            if result.is_ok() {
+                break;
+            }
+        }
+        let claims = result?;
 
-        decode::<ZedIdClaims>(token, &self.decoding_key, &validation)
-            .map(|data| data.claims)
-            .map_err(|e| IdentityError::JwtValidationFailed(e.to_string()))
+        // A token "issued" in the future points at a skewed or forged issuer
+        if claims.iat > Utc::now().timestamp() + self.leeway_secs as i64 {
+            return Err(IdentityError::JwtValidationFailed(
+                "Token issued in the future (iat beyond leeway)".to_string(),
+            ));
+        }
+        Ok(claims)
     }
 }
 
@@ -104,6 +405,7 @@ mod tests {
                 3,
                 Some("spiffe://tetrate.io/ns/production/sa/checkout".to_string()),
                 60,
+                None,
             )
             .unwrap();
 
@@ -112,4 +414,150 @@ mod tests {
         assert_eq!(claims.name, "checkout-service");
         assert_eq!(claims.trust_level, 3);
     }
+
+    #[test]
+    fn test_extra_claims_round_trip_and_reserved_names_rejected() {
+        let svc = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io");
+        let extra = HashMap::from([("region".to_string(), serde_json::json!("eu-west-1"))]);
+        let token = svc
+            .issue_token_with_claims("identity-123", "checkout-service", "production", "workload", 3, None, 60, 0, None, extra)
+            .unwrap();
+        let claims = svc.validate_token(&token).unwrap();
+        assert_eq!(claims.extra["region"], "eu-west-1");
+        assert_eq!(claims.extra.len(), 1);
+
+        let forged = HashMap::from([("trust_level".to_string(), serde_json::json!(4))]);
+        let err = svc
+            .issue_token_with_claims("identity-123", "checkout-service", "production", "workload", 1, None, 60, 0, None, forged)
+            .unwrap_err();
+        assert!(matches!(err, IdentityError::ReservedClaim(name) if name == "trust_level"));
+    }
+
+    #[test]
+    fn test_audience_and_issuer_lists() {
+        let svc = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io");
+        let istio = svc
+            .issue_token("identity-123", "checkout-service", "production", "workload", 3, None, 60, Some("istio"))
+            .unwrap();
+        assert!(svc.validate_token(&istio).is_err());
+
+        let federated = JwtService::new("test-secret-key-zedid", "zedid.eu.tetrate.io")
+            .with_accepted_audiences(vec!["zedid-api".to_string(), "istio".to_string()])
+            .with_accepted_issuers(vec!["zedid.tetrate.io".to_string(), "zedid.eu.tetrate.io".to_string()]);
+        let claims = federated.validate_token(&istio).unwrap();
+        assert_eq!(claims.aud, vec!["istio".to_string()]);
+        assert_eq!(claims.iss, "zedid.tetrate.io");
+    }
+
+    #[test]
+    fn test_leeway_tolerates_recent_expiry() {
+        let svc = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io");
+        let now = Utc::now().timestamp();
+        let claims = ZedIdClaims {
+            sub: "identity-123".to_string(),
+            iss: "zedid.tetrate.io".to_string(),
+            aud: vec![DEFAULT_AUDIENCE.to_string()],
+            exp: now - 10,
+            iat: now - 3600,
+            nbf: None,
+            jti: uuid::Uuid::new_v4().to_string(),
+            name: "checkout-service".to_string(),
+            namespace: "production".to_string(),
+            kind: "workload".to_string(),
+            trust_level: 3,
+            spiffe_id: None,
+            extra: HashMap::new(),
+        };
+        let token = svc.sign(&claims).unwrap();
+
+        assert!(svc.with_leeway(30).validate_token(&token).is_ok());
+        let strict = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io").with_leeway(0);
+        assert!(strict.validate_token(&token).is_err());
+    }
+
+    #[test]
+    fn test_namespace_audience_rejects_other_namespaces() {
+        let svc = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io").with_namespace_audiences(true);
+        let token = svc
+            .issue_token("identity-123", "checkout-service", "production", "workload", 3, None, 60, None)
+            .unwrap();
+
+        let claims = svc.validate_token(&token).unwrap();
+        assert_eq!(claims.aud, ["zedid-api", "zedid:ns:production"]);
+        assert!(svc.validate_token_for_namespace(&token, "production").is_ok());
+        assert!(svc.validate_token_for_namespace(&token, "ai-platform").is_err());
+
+        let unscoped = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io")
+            .issue_token("identity-123", "checkout-service", "production", "workload", 3, None, 60, None)
+            .unwrap();
+        assert!(svc.validate_token_for_namespace(&unscoped, "production").is_err());
+    }
+
+    #[test]
+    fn test_post_dated_token_is_not_valid_before_nbf() {
+        let svc = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io");
+        let token = svc
+            .issue_token_with_claims("identity-123", "checkout-service", "production", "workload", 3, None, 60, 5, None, HashMap::new())
+            .unwrap();
+        let err = svc.validate_token(&token).unwrap_err();
+        assert!(err.to_string().contains("ImmatureSignature"), "{}", err);
+
+        // Five minutes from now, the token is valid: a validator whose leeway
+        // covers the wait sees it as the clock will then
+        let later = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io").with_leeway(5 * 60 + 5);
+        let claims = later.validate_token(&token).unwrap();
+        let nbf = claims.nbf.unwrap();
+        assert!((nbf - Utc::now().timestamp() - 300).abs() <= 5);
+        // The lifetime starts at nbf
+        assert_eq!(claims.exp - nbf, 3600);
+
+        let immediate = svc.issue_token("identity-123", "checkout-service", "production", "workload", 3, None, 60, None).unwrap();
+        assert!(svc.validate_token(&immediate).unwrap().nbf.is_none());
+
+        let capped = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io").with_max_ttl(30);
+        for (ttl, not_before) in [(60, 0), (10, 45), (0, 0), (10, -1)] {
+            let result = capped.issue_token_with_claims("identity-123", "checkout-service", "production", "workload", 3, None, ttl, not_before, None, HashMap::new());
+            assert!(matches!(result, Err(IdentityError::InvalidTokenLifetime(_))), "{} {}", ttl, not_before);
+        }
+
+        // Without a cap, lifetimes past what a timestamp can hold are refused, not a panic
+        let unlimited = JwtService::new("test-secret-key-zedid", "zedid.tetrate.io").with_max_ttl(0);
+        for (ttl, not_before) in [(i64::MAX, 0), (60, i64::MAX), (i64::MAX / 2, i64::MAX / 2)] {
+            let result = unlimited.issue_token_with_claims("identity-123", "checkout-service", "production", "workload", 3, None, ttl, not_before, None, HashMap::new());
+            assert!(matches!(result, Err(IdentityError::InvalidTokenLifetime(_))), "{} {}", ttl, not_before);
+        }
+    }
+
+    #[test]
+    fn test_rotated_out_key_verifies_until_grace_period_ends() {
+        let svc = JwtService::new("first-secret-key-zedid", "zedid.tetrate.io");
+        let issue = |svc: &JwtService| {
+            svc.issue_token("identity-123", "checkout-service", "production", "workload", 3, None, 60, None).unwrap()
+        };
+        let old_kid = svc.signing_kid();
+        let old_token = issue(&svc);
+        assert_eq!(decode_header(&old_token).unwrap().kid.as_deref(), Some(old_kid.as_str()));
+
+        let new_kid = svc.rotate_key("second-secret-key-zedid").unwrap();
+        let new_token = issue(&svc);
+        assert_eq!(decode_header(&new_token).unwrap().kid, Some(new_kid.clone()));
+        assert!(svc.validate_token(&old_token).is_ok());
+        assert!(svc.validate_token(&new_token).is_ok());
+        let published: Vec<_> = svc.published_keys().into_iter().map(|k| (k.kid, k.expires_at.is_some())).collect();
+        assert_eq!(published, [(new_kid.clone(), false), (old_kid.clone(), true)]);
+        assert!(svc.rotate_key("second-secret-key-zedid").is_err());
+
+        // A token naming a key it was not signed with does not verify
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(new_kid);
+        let claims = svc.validate_token(&old_token).unwrap();
+        let forged = encode(&header, &claims, &EncodingKey::from_secret(b"first-secret-key-zedid")).unwrap();
+        assert!(svc.validate_token(&forged).is_err());
+
+        // With no grace period the old key stops verifying at rotation
+        let strict = JwtService::new("first-secret-key-zedid", "zedid.tetrate.io").with_key_grace(0);
+        strict.rotate_key("second-secret-key-zedid").unwrap();
+        assert!(strict.validate_token(&old_token).is_err());
+        assert_eq!(strict.published_keys().len(), 1);
+    }
 }
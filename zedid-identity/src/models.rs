@@ -2,6 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Granularity of `Identity::last_seen` updates, in seconds
+pub const LAST_SEEN_RESOLUTION_SECS: i64 = 60;
+
 /// Represents the type of identity in ZedID
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -32,6 +35,35 @@ pub enum TrustLevel {
     Critical = 4,
 }
 
+impl TrustLevel {
+    /// Numeric form (0–4) used in JWT claims and policy input (`input.trust_level`)
+    pub fn as_u8(&self) -> u8 {
+        self.clone() as u8
+    }
+
+    /// Lowercase name, as serialized (`untrusted` … `critical`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Untrusted => "untrusted",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(Self::Untrusted),
+            1 => Some(Self::Low),
+            2 => Some(Self::Medium),
+            3 => Some(Self::High),
+            4 => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
 /// Core identity record in ZedID
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Identity {
@@ -47,64 +79,62 @@ pub struct Identity {
     pub last_seen: DateTime<Utc>,
     pub is_active: bool,
     pub svid_expiry: Option<DateTime<Utc>>,
+    /// End of the identity's lease: unless renewed by then, it is deactivated
+    /// and its tokens stop introspecting as active. `None` never expires.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl Identity {
+    pub fn builder(name: &str, namespace: &str) -> IdentityBuilder {
+        IdentityBuilder::new(name, namespace)
+    }
+
     pub fn new_workload(name: &str, namespace: &str, trust_domain: &str) -> Self {
-        let id = Uuid::new_v4();
-        let spiffe_id = format!("spiffe://{}/ns/{}/sa/{}", trust_domain, namespace, name);
-        Self {
-            id,
-            name: name.to_string(),
-            kind: IdentityKind::Workload,
-            trust_level: TrustLevel::High,
-            spiffe_id: Some(spiffe_id),
-            email: None,
-            namespace: namespace.to_string(),
-            labels: std::collections::HashMap::new(),
-            created_at: Utc::now(),
-            last_seen: Utc::now(),
-            is_active: true,
-            svid_expiry: Some(Utc::now() + chrono::Duration::hours(1)),
-        }
+        IdentityBuilder::new(name, namespace)
+            .kind(IdentityKind::Workload)
+            .trust_domain(trust_domain)
+            .build()
     }
 
     pub fn new_human(name: &str, email: &str, namespace: &str) -> Self {
-        Self {
-            id: Uuid::new_v4(),
-            name: name.to_string(),
-            kind: IdentityKind::Human,
-            trust_level: TrustLevel::Medium,
-            spiffe_id: None,
-            email: Some(email.to_string()),
-            namespace: namespace.to_string(),
-            labels: std::collections::HashMap::new(),
-            created_at: Utc::now(),
-            last_seen: Utc::now(),
-            is_active: true,
-            svid_expiry: None,
-        }
+        IdentityBuilder::new(name, namespace)
+            .kind(IdentityKind::Human)
+            .email(email)
+            .build()
     }
 
     pub fn new_ai_agent(name: &str, namespace: &str, trust_domain: &str) -> Self {
-        let id = Uuid::new_v4();
-        let spiffe_id = format!("spiffe://{}/ns/{}/agent/{}", trust_domain, namespace, name);
-        Self {
-            id,
-            name: name.to_string(),
-            kind: IdentityKind::AiAgent,
-            trust_level: TrustLevel::Medium,
-            spiffe_id: Some(spiffe_id),
-            email: None,
-            namespace: namespace.to_string(),
-            labels: std::collections::HashMap::new(),
-            created_at: Utc::now(),
-            last_seen: Utc::now(),
-            is_active: true,
-            svid_expiry: Some(Utc::now() + chrono::Duration::hours(4)),
+        IdentityBuilder::new(name, namespace)
+            .kind(IdentityKind::AiAgent)
+            .trust_domain(trust_domain)
+            .build()
+    }
+
+    /// Whether `last_seen` is old enough to be worth rewriting. Activity within
+    /// `LAST_SEEN_RESOLUTION_SECS` is coalesced so hot paths stay read-only.
+    pub fn needs_last_seen_update(&self, now: DateTime<Utc>) -> bool {
+        now - self.last_seen >= chrono::Duration::seconds(LAST_SEEN_RESOLUTION_SECS)
+    }
+
+    /// Whether `other` names the same principal: the same SPIFFE ID, or for
+    /// identities without one, the same kind, namespace and name
+    pub fn is_duplicate_of(&self, other: &Identity) -> bool {
+        match (&self.spiffe_id, &other.spiffe_id) {
+            (Some(ours), Some(theirs)) => ours == theirs,
+            _ => self.kind == other.kind && self.namespace == other.namespace && self.name == other.name,
         }
     }
 
+    pub fn is_lease_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Active and within its lease
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.is_active && !self.is_lease_expired(now)
+    }
+
     pub fn is_svid_valid(&self) -> bool {
         match &self.svid_expiry {
             Some(expiry) => *expiry > Utc::now(),
@@ -112,10 +142,153 @@ impl Identity {
         }
     }
 
+    /// Whether the SVID lapses within `window` of `now` (already-expired SVIDs included)
+    pub fn svid_expires_within(&self, now: DateTime<Utc>, window: chrono::Duration) -> bool {
+        self.svid_expiry.is_some_and(|expiry| expiry <= now + window)
+    }
+
+    /// Trust level to act on: High trust rests on attestation, so an identity
+    /// with a lapsed SVID is treated as at most `Low`
+    pub fn effective_trust_level(&self) -> TrustLevel {
+        if !self.is_svid_valid() && self.trust_level > TrustLevel::Low {
+            TrustLevel::Low
+        } else {
+            self.trust_level.clone()
+        }
+    }
+
     pub fn svid_ttl_seconds(&self) -> Option<i64> {
         self.svid_expiry
             .map(|exp| (exp - Utc::now()).num_seconds().max(0))
     }
+
+    /// Roles from the comma-separated `role` label
+    pub fn roles(&self) -> Vec<String> {
+        self.labels
+            .get("role")
+            .map(|r| r.split(',').map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Extra JWT claims from `claim.<name>` labels (`claim.region: eu-west-1`
+    /// becomes a `region` claim)
+    pub fn label_claims(&self) -> std::collections::HashMap<String, serde_json::Value> {
+        self.labels
+            .iter()
+            .filter_map(|(k, v)| Some((k.strip_prefix("claim.")?.to_string(), serde_json::Value::String(v.clone()))))
+            .collect()
+    }
+}
+
+/// Fluent constructor for `Identity`. Anything left unset falls back to the
+/// per-kind defaults: workloads and service accounts start at High trust with a
+/// 1h SVID, agents at Medium with a 4h SVID, humans at Medium with no SVID.
+#[derive(Debug, Clone)]
+pub struct IdentityBuilder {
+    name: String,
+    namespace: String,
+    kind: IdentityKind,
+    trust_level: Option<TrustLevel>,
+    trust_domain: String,
+    spiffe_id: Option<String>,
+    email: Option<String>,
+    labels: std::collections::HashMap<String, String>,
+    svid_ttl: Option<chrono::Duration>,
+    lease: Option<chrono::Duration>,
+}
+
+impl IdentityBuilder {
+    pub fn new(name: &str, namespace: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            namespace: namespace.to_string(),
+            kind: IdentityKind::Workload,
+            trust_level: None,
+            trust_domain: "tetrate.io".to_string(),
+            spiffe_id: None,
+            email: None,
+            labels: std::collections::HashMap::new(),
+            svid_ttl: None,
+            lease: None,
+        }
+    }
+
+    pub fn kind(mut self, kind: IdentityKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    pub fn trust_level(mut self, trust_level: TrustLevel) -> Self {
+        self.trust_level = Some(trust_level);
+        self
+    }
+
+    pub fn trust_domain(mut self, trust_domain: &str) -> Self {
+        self.trust_domain = trust_domain.to_ascii_lowercase();
+        self
+    }
+
+    /// Use this SPIFFE ID instead of deriving one from the kind's default path
+    pub fn spiffe_id(mut self, spiffe_id: String) -> Self {
+        self.spiffe_id = Some(spiffe_id);
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    pub fn labels(mut self, labels: std::collections::HashMap<String, String>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn svid_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.svid_ttl = Some(ttl);
+        self
+    }
+
+    /// Expire the identity this long after creation unless the lease is renewed
+    pub fn lease(mut self, lease: chrono::Duration) -> Self {
+        self.lease = Some(lease);
+        self
+    }
+
+    pub fn build(self) -> Identity {
+        let now = Utc::now();
+        let (trust_level, segment, ttl) = match self.kind {
+            IdentityKind::Workload | IdentityKind::ServiceAccount => {
+                (TrustLevel::High, Some("sa"), chrono::Duration::hours(1))
+            }
+            IdentityKind::AiAgent => (TrustLevel::Medium, Some("agent"), chrono::Duration::hours(4)),
+            IdentityKind::Human => (TrustLevel::Medium, None, chrono::Duration::zero()),
+        };
+        // Humans authenticate via OIDC and never hold an SVID
+        let spiffe_id = segment.map(|segment| {
+            self.spiffe_id.unwrap_or_else(|| {
+                format!("spiffe://{}/ns/{}/{}/{}", self.trust_domain, self.namespace, segment, self.name)
+            })
+        });
+        let spiffe_id = spiffe_id.map(|id| crate::spiffe::normalize_spiffe_id(&id));
+        let svid_expiry = spiffe_id.as_ref().map(|_| now + self.svid_ttl.unwrap_or(ttl));
+
+        Identity {
+            id: Uuid::new_v4(),
+            name: self.name,
+            kind: self.kind,
+            trust_level: self.trust_level.unwrap_or(trust_level),
+            spiffe_id,
+            email: self.email,
+            namespace: self.namespace,
+            labels: self.labels,
+            created_at: now,
+            last_seen: now,
+            is_active: true,
+            svid_expiry,
+            expires_at: self.lease.map(|lease| now + lease),
+        }
+    }
 }
 
 /// Represents a SPIFFE Verifiable Identity Document (SVID)
@@ -123,13 +296,51 @@ impl Identity {
 pub struct Svid {
     pub spiffe_id: String,
     pub cert_pem: String,
+    /// PKCS#8 `PRIVATE KEY` PEM of type `key_algorithm`
     pub key_pem: String,
+    #[serde(default)]
+    pub key_algorithm: SvidKeyAlgorithm,
     pub bundle_pem: String,
     pub issued_at: DateTime<Utc>,
     pub expires_at: DateTime<Utc>,
     pub serial_number: String,
 }
 
+/// Key pair type for issued SVIDs (`ZEDID_SVID_KEY_ALG`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SvidKeyAlgorithm {
+    #[default]
+    EcP256,
+    EcP384,
+    Rsa2048,
+    Rsa4096,
+}
+
+impl SvidKeyAlgorithm {
+    pub const ALL: [SvidKeyAlgorithm; 4] = [Self::EcP256, Self::EcP384, Self::Rsa2048, Self::Rsa4096];
+
+    /// Parse `ec-p256`, `ec-p384`, `rsa-2048` or `rsa-4096` (case-insensitive)
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|alg| alg.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EcP256 => "ec-p256",
+            Self::EcP384 => "ec-p384",
+            Self::Rsa2048 => "rsa-2048",
+            Self::Rsa4096 => "rsa-4096",
+        }
+    }
+}
+
+impl std::fmt::Display for SvidKeyAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl Svid {
     pub fn is_valid(&self) -> bool {
         Utc::now() < self.expires_at
@@ -140,6 +351,35 @@ impl Svid {
     }
 }
 
+/// Registry record of an issued SVID (metadata only — no key material)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssuedSvid {
+    pub identity_id: Uuid,
+    pub spiffe_id: String,
+    pub serial_number: String,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    /// Number of times this SPIFFE ID's SVID has been re-issued
+    pub renewals: u32,
+}
+
+impl IssuedSvid {
+    pub fn from_svid(identity_id: Uuid, svid: &Svid) -> Self {
+        Self {
+            identity_id,
+            spiffe_id: svid.spiffe_id.clone(),
+            serial_number: svid.serial_number.clone(),
+            issued_at: svid.issued_at,
+            expires_at: svid.expires_at,
+            renewals: 0,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Utc::now() >= self.expires_at
+    }
+}
+
 /// Audit event for identity operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IdentityAuditEvent {
@@ -151,7 +391,48 @@ pub struct IdentityAuditEvent {
     pub decision: AuditDecision,
     pub reason: Option<String>,
     pub timestamp: DateTime<Utc>,
-    pub metadata: serde_json::Value,
+    pub metadata: AuditMetadata,
+}
+
+/// Audit event metadata: common fields with a stable schema for SIEM
+/// consumers, plus event-specific details in `extra`. Serialized flat, so
+/// `extra` keys sit beside the typed ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AuditMetadata {
+    /// Caller-supplied `X-Request-Id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// First `X-Forwarded-For` hop, or `X-Real-IP`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_ip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision_id: Option<Uuid>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Typed `AuditMetadata` fields, which `extra` must not repeat
+pub const AUDIT_METADATA_FIELDS: &[&str] = &["request_id", "source_ip", "user_agent", "policy_id", "decision_id"];
+
+impl AuditMetadata {
+    /// Add an event-specific field
+    pub fn with(mut self, key: &str, value: impl Into<serde_json::Value>) -> Self {
+        self.extra.insert(key.to_string(), value.into());
+        self
+    }
+
+    /// `extra` may not shadow a typed field: the flattened JSON would carry
+    /// the key twice
+    pub fn validate(&self) -> Result<(), String> {
+        match self.extra.keys().find(|k| AUDIT_METADATA_FIELDS.contains(&k.as_str())) {
+            Some(key) => Err(format!("audit metadata extra field '{}' shadows a typed field", key)),
+            None => Ok(()),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -180,8 +461,53 @@ impl IdentityAuditEvent {
             decision,
             reason,
             timestamp: Utc::now(),
-            metadata: serde_json::Value::Object(serde_json::Map::new()),
+            metadata: AuditMetadata::default(),
+        }
+    }
+}
+
+/// Tamper-evidence for pruned audit history: a running SHA-256 over every
+/// evicted event (oldest first), so the retained log can still be tied to
+/// what was removed before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditCheckpoint {
+    /// Total events pruned since startup
+    pub pruned_events: u64,
+    pub last_event_id: Uuid,
+    pub last_event_timestamp: DateTime<Utc>,
+    /// Hex SHA-256 chained over all pruned events
+    pub hash: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditCheckpoint {
+    /// Fold newly pruned events into the chain. Returns `previous` unchanged
+    /// when nothing was pruned.
+    pub fn extend(previous: Option<&AuditCheckpoint>, pruned: &[IdentityAuditEvent]) -> Option<Self> {
+        use sha2::{Digest, Sha256};
+
+        let last = match pruned.last() {
+            Some(last) => last,
+            None => return previous.cloned(),
+        };
+        let mut hash = previous.map(|c| c.hash.clone()).unwrap_or_default();
+        for event in pruned {
+            let mut hasher = Sha256::new();
+            hasher.update(hash.as_bytes());
+            hasher.update(serde_json::to_vec(event).unwrap_or_default());
+            hash = hasher
+                .finalize()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
         }
+        Some(Self {
+            pruned_events: previous.map_or(0, |c| c.pruned_events) + pruned.len() as u64,
+            last_event_id: last.id,
+            last_event_timestamp: last.timestamp,
+            hash,
+            created_at: Utc::now(),
+        })
     }
 }
 
@@ -190,9 +516,26 @@ impl IdentityAuditEvent {
 pub struct CreateIdentityRequest {
     pub name: String,
     pub kind: IdentityKind,
+    /// Defaults to the server's `ZEDID_DEFAULT_NAMESPACE`
+    #[serde(default)]
     pub namespace: String,
+    /// Trust domain for multi-domain setups; must be in `ZEDID_TRUST_DOMAINS`.
+    /// Defaults to `ZEDID_TRUST_DOMAIN`.
+    #[serde(default)]
+    pub trust_domain: Option<String>,
     pub email: Option<String>,
     pub labels: Option<std::collections::HashMap<String, String>>,
+    /// Lease length; defaults to the server's lease for the kind, if any
+    #[serde(default)]
+    pub lease_minutes: Option<i64>,
+}
+
+/// Extend an identity's lease from now
+#[derive(Debug, Default, Deserialize)]
+pub struct RenewLeaseRequest {
+    /// Defaults to the server's lease for the identity's kind
+    #[serde(default)]
+    pub lease_minutes: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -200,4 +543,108 @@ pub struct CreateIdentityResponse {
     pub identity: Identity,
     pub svid: Option<Svid>,
     pub message: String,
+    /// True when the identity was only validated and previewed, not stored
+    pub dry_run: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trust_level_numeric_round_trip() {
+        for value in 0..=4u8 {
+            let level = TrustLevel::from_u8(value).unwrap();
+            assert_eq!(level.as_u8(), value);
+        }
+        assert_eq!(TrustLevel::High.as_u8(), 3);
+        assert!(TrustLevel::from_u8(2).unwrap() > TrustLevel::Low);
+        assert_eq!(TrustLevel::from_u8(5), None);
+    }
+
+    #[test]
+    fn test_audit_checkpoint_chains_across_prunes() {
+        let events: Vec<IdentityAuditEvent> = (0..3)
+            .map(|_| IdentityAuditEvent::new(Uuid::new_v4(), "svid.issue", "system", "r", AuditDecision::Allow, None))
+            .collect();
+
+        let all_at_once = AuditCheckpoint::extend(None, &events).unwrap();
+        let first = AuditCheckpoint::extend(None, &events[..1]);
+        let incremental = AuditCheckpoint::extend(first.as_ref(), &events[1..]).unwrap();
+
+        assert_eq!(incremental.hash, all_at_once.hash);
+        assert_eq!(incremental.pruned_events, 3);
+        assert_eq!(incremental.last_event_id, events[2].id);
+        assert!(AuditCheckpoint::extend(None, &[]).is_none());
+    }
+
+    #[test]
+    fn test_audit_metadata_flattens_and_rejects_shadowed_fields() {
+        let decision_id = Uuid::new_v4();
+        let metadata = AuditMetadata {
+            request_id: Some("req-1".to_string()),
+            decision_id: Some(decision_id),
+            ..Default::default()
+        }
+        .with("namespace", "payments");
+
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["request_id"], "req-1");
+        assert_eq!(json["namespace"], "payments");
+        assert!(json.get("source_ip").is_none());
+        let parsed: AuditMetadata = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, metadata);
+        assert!(metadata.validate().is_ok());
+
+        let shadowed = AuditMetadata::default().with("policy_id", "p-1");
+        assert!(shadowed.validate().unwrap_err().contains("policy_id"));
+    }
+
+    #[test]
+    fn test_svid_expires_within() {
+        let now = Utc::now();
+        let mut workload = Identity::new_workload("checkout", "production", "tetrate.io");
+        workload.svid_expiry = Some(now + chrono::Duration::minutes(5));
+
+        assert!(workload.svid_expires_within(now, chrono::Duration::minutes(10)));
+        assert!(!workload.svid_expires_within(now, chrono::Duration::minutes(1)));
+        let human = Identity::new_human("alice", "alice@tetrate.io", "platform");
+        assert!(!human.svid_expires_within(now, chrono::Duration::days(365)));
+    }
+
+    #[test]
+    fn test_builder_applies_kind_defaults_and_overrides() {
+        let agent = Identity::builder("tars", "ai-platform").kind(IdentityKind::AiAgent).build();
+        assert_eq!(agent.spiffe_id.as_deref(), Some("spiffe://tetrate.io/ns/ai-platform/agent/tars"));
+        assert_eq!(agent.trust_level, TrustLevel::Medium);
+
+        let labels = std::collections::HashMap::from([("team".to_string(), "payments".to_string())]);
+        let sa = Identity::builder("billing", "production")
+            .kind(IdentityKind::ServiceAccount)
+            .trust_domain("example.org")
+            .trust_level(TrustLevel::Low)
+            .labels(labels.clone())
+            .svid_ttl(chrono::Duration::minutes(5))
+            .build();
+        assert_eq!(sa.kind, IdentityKind::ServiceAccount);
+        assert_eq!(sa.spiffe_id.as_deref(), Some("spiffe://example.org/ns/production/sa/billing"));
+        assert_eq!(sa.trust_level, TrustLevel::Low);
+        assert_eq!(sa.labels, labels);
+        assert!(sa.svid_ttl_seconds().unwrap() <= 300);
+
+        let human = Identity::builder("alice", "platform").kind(IdentityKind::Human).build();
+        assert!(human.spiffe_id.is_none() && human.svid_expiry.is_none());
+    }
+
+    #[test]
+    fn test_expired_svid_downgrades_effective_trust() {
+        let mut workload = Identity::new_workload("checkout", "production", "tetrate.io");
+        workload.trust_level = TrustLevel::High;
+        assert_eq!(workload.effective_trust_level(), TrustLevel::High);
+
+        workload.svid_expiry = Some(Utc::now() - chrono::Duration::minutes(1));
+        assert_eq!(workload.effective_trust_level(), TrustLevel::Low);
+        workload.trust_level = TrustLevel::Untrusted;
+        assert_eq!(workload.effective_trust_level(), TrustLevel::Untrusted);
+    }
 }
@@ -0,0 +1,165 @@
+//! Offline policy validation for CI — no server required.
+//!
+//! Usage: zedid-policy validate <dir> [--min-coverage <0.0-1.0>] [--format text|json]
+//!
+//! Every `.rego`, `.cedar`, `.yaml`/`.yml` file in `<dir>` is checked with the
+//! engine's validation and limit rules. Exits 1 if any policy fails or the
+//! average coverage score is below `--min-coverage`, 2 on usage errors.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use zedid_policy::engine::{check_policy_limits, PolicyEngine};
+use zedid_policy::models::{AccessModel, Policy, PolicyKind};
+
+const USAGE: &str = "usage: zedid-policy validate <dir> [--min-coverage <0.0-1.0>] [--format text|json]";
+
+#[derive(Serialize)]
+struct FileReport {
+    file: String,
+    kind: PolicyKind,
+    passed: bool,
+    coverage_score: f32,
+    errors: Vec<String>,
+    warnings: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Report {
+    policies: Vec<FileReport>,
+    coverage: f32,
+    min_coverage: f32,
+    passed: bool,
+}
+
+struct Args {
+    dir: PathBuf,
+    min_coverage: f32,
+    json: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() != Some("validate") {
+        return Err(USAGE.to_string());
+    }
+    let mut dir = None;
+    let mut min_coverage = 0.0;
+    let mut json = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--min-coverage" => {
+                min_coverage = args
+                    .next()
+                    .and_then(|v| v.parse::<f32>().ok())
+                    .filter(|v| (0.0..=1.0).contains(v))
+                    .ok_or("--min-coverage takes a number between 0 and 1")?;
+            }
+            "--format" => match args.next().as_deref() {
+                Some("json") => json = true,
+                Some("text") => json = false,
+                _ => return Err("--format takes text or json".to_string()),
+            },
+            _ if dir.is_none() && !arg.starts_with("--") => dir = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument: {}\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Args {
+        dir: dir.ok_or(USAGE)?,
+        min_coverage,
+        json,
+    })
+}
+
+/// Policy kind from the file extension; YAML is Istio when it declares an AuthorizationPolicy
+fn policy_kind(path: &Path, content: &str) -> Option<PolicyKind> {
+    match path.extension()?.to_str()? {
+        "rego" => Some(PolicyKind::Rego),
+        "cedar" => Some(PolicyKind::Cedar),
+        "yaml" | "yml" if content.contains("kind: AuthorizationPolicy") => Some(PolicyKind::IstioAuthz),
+        "yaml" | "yml" => Some(PolicyKind::RbacYaml),
+        _ => None,
+    }
+}
+
+fn check_file(engine: &PolicyEngine, path: &Path) -> Option<FileReport> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let kind = policy_kind(path, &content)?;
+    let name = path.file_stem()?.to_string_lossy().to_string();
+    let policy = Policy::new(&name, "", kind.clone(), AccessModel::ZeroTrust, &content, "ci", "zedid-policy");
+
+    let mut validation = engine.validate_policy(&policy);
+    if let Err(e) = check_policy_limits(&policy) {
+        validation.errors.push(e.to_string());
+        validation.passed = false;
+        validation.coverage_score = 0.0;
+    }
+    Some(FileReport {
+        file: path.display().to_string(),
+        kind,
+        passed: validation.passed,
+        coverage_score: validation.coverage_score,
+        errors: validation.errors,
+        warnings: validation.warnings,
+    })
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(2);
+        }
+    };
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(&args.dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(e) => {
+            eprintln!("cannot read {}: {}", args.dir.display(), e);
+            return ExitCode::from(2);
+        }
+    };
+    paths.sort();
+
+    let engine = PolicyEngine::new();
+    let policies: Vec<FileReport> = paths.iter().filter_map(|p| check_file(&engine, p)).collect();
+    let coverage = if policies.is_empty() {
+        0.0
+    } else {
+        policies.iter().map(|p| p.coverage_score).sum::<f32>() / policies.len() as f32
+    };
+    let passed = !policies.is_empty() && policies.iter().all(|p| p.passed) && coverage >= args.min_coverage;
+    let report = Report {
+        policies,
+        coverage,
+        min_coverage: args.min_coverage,
+        passed,
+    };
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report).unwrap_or_default());
+    } else {
+        for p in &report.policies {
+            println!("{} {} ({:?}, coverage {:.2})", if p.passed { "PASS" } else { "FAIL" }, p.file, p.kind, p.coverage_score);
+            for e in &p.errors {
+                println!("    error: {}", e);
+            }
+            for w in &p.warnings {
+                println!("    warning: {}", w);
+            }
+        }
+        println!(
+            "\n{} policies, coverage {:.2} (min {:.2}): {}",
+            report.policies.len(),
+            report.coverage,
+            report.min_coverage,
+            if report.passed { "PASSED" } else { "FAILED" }
+        );
+    }
+
+    if report.passed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
@@ -1,28 +1,201 @@
 use crate::error::PolicyError;
+use crate::hooks::{EvaluationHook, IdentityEnrichmentHook};
+use crate::sinks::DecisionSink;
+use crate::istio;
+use crate::schedule::CronSchedule;
+use crate::statements;
+use crate::metrics::{self, Metrics};
+use crate::opa::OpaRestEngine;
+use crate::rbac;
+use crate::template;
 use crate::models::{
-    AccessModel, Policy, PolicyDecisionRequest, PolicyDecisionResponse,
-    PolicyKind, PolicyStatus, PolicyValidationResult,
+    AccessModel, BulkActivateResponse, CellCoverage, CombiningAlgorithm, CoverageHeatmap,
+    EffectivePermission, EnforcementMode, EvaluationReport, EvaluationReportRow, EvaluationStats,
+    GitSyncIssue, GitSyncReport, GoverningPolicy, HeatmapCell, IdentityImpact, Namespace, Policy,
+    PolicyBundle, PolicyDecisionRequest, PolicyDecisionResponse, PolicyEffect, PolicyGroup,
+    PolicyImportResult, PolicyKind, PolicyRevision, PolicyRevisionRef, PolicySearchHit,
+    PolicySource, PolicyStatement, PolicyStats, PolicyStatus, PolicyTestCase, PolicyTestReport,
+    PolicyTestResult, PolicyValidationResult, ReasonVerbosity, ReportCase, ResourceImpact,
+    ReviewComment, SearchMatch, SkippedPolicy, SubjectPermissions, MAX_POLICY_CONTENT_BYTES,
+    MAX_POLICY_LIST_ENTRIES, MAX_REPORT_REQUESTS, POLICY_BUNDLE_SCHEMA_VERSION,
 };
-use std::time::Instant;
-use tracing::{debug, info, warn};
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
+use zedid_identity::jwt::{JwtService, ZedIdClaims};
+use zedid_identity::spiffe::{normalize_spiffe_id, SpiffeId};
+use zedid_identity::{Identity, IdentityKind};
+
+/// Engine tunables, populated from `AppConfig` by the server
+#[derive(Debug, Clone, Default)]
+pub struct EngineConfig {
+    /// How long identical decisions are served from cache (0 disables caching)
+    pub decision_cache_ttl_ms: u64,
+    /// Include the request `context` in the cache key (excluded by default as it is volatile)
+    pub cache_include_context: bool,
+    /// Allow Draft → Active without going through review (demo convenience)
+    pub allow_direct_activation: bool,
+    /// Refuse to activate a policy while any of its attached tests fails
+    pub require_passing_tests: bool,
+    /// Accept a caller-supplied `identity_snapshot` in place of the live identity
+    pub allow_identity_snapshots: bool,
+    /// Redact deny reasons for untrusted callers unless a namespace overrides it
+    pub redact_deny_reasons: bool,
+    /// How matching policies combine, unless a namespace sets its own algorithm
+    pub combining_algorithm: CombiningAlgorithm,
+    /// Enrich with the stored trust level even when the subject's SVID has lapsed
+    pub keep_trust_on_svid_expiry: bool,
+    /// SPIFFE trust domain, exposed to policy templates as `{{ trust_domain }}`
+    pub trust_domain: String,
+    /// Per-evaluation deadline; a timed-out evaluation is denied (0 disables the timeout)
+    pub eval_timeout_ms: u64,
+    /// External OPA server to delegate decisions to (embedded evaluation when unset)
+    pub opa_url: Option<String>,
+    /// Rego package queried on the external OPA server (defaults to `zedid`)
+    pub opa_package: Option<String>,
+    /// Fraction of evaluations (0.0–1.0) whose full request and response are logged at debug
+    pub eval_log_sample: f64,
+    /// Also log every denial in full, regardless of the sample
+    pub eval_log_denials: bool,
+    /// Context fields masked in sampled evaluation logs
+    pub eval_log_redact: Vec<String>,
+    /// Context fields callers may set, unless a namespace has its own list.
+    /// Unset: anything except `PROTECTED_CONTEXT_FIELDS`.
+    pub context_allowlist: Option<Vec<String>>,
+    /// Reject requests carrying disallowed context fields instead of stripping them
+    pub reject_disallowed_context: bool,
+    /// Most policies the store may hold (0 = unlimited)
+    pub max_policies: usize,
+    /// Most Draft policies per namespace (0 = unlimited)
+    pub max_drafts_per_namespace: usize,
+    /// Evaluations slower than this are logged with their request (0 disables it)
+    pub slow_eval_ms: u64,
+}
+
+/// Context fields that vouch for the caller and must come from verified
+/// sources, never the request, unless explicitly allowlisted
+pub const PROTECTED_CONTEXT_FIELDS: &[&str] = &["mfa_verified", "trust_level", "roles"];
+
+/// Most recent evaluations kept for latency percentiles
+const LATENCY_WINDOW: usize = 10_000;
+
+/// A cached decision, tagged with its namespace for targeted invalidation
+struct CachedDecision {
+    namespace: String,
+    /// Namespace of the default policy consulted, if it lives elsewhere
+    default_namespace: Option<String>,
+    response: PolicyDecisionResponse,
+    expires_at: Instant,
+}
 
 /// In-memory policy store (in production: PostgreSQL via sqlx)
 pub struct PolicyEngine {
     policies: std::sync::Arc<tokio::sync::RwLock<Vec<Policy>>>,
+    namespaces: RwLock<Vec<Namespace>>,
+    config: EngineConfig,
+    decision_cache: std::sync::Mutex<HashMap<u64, CachedDecision>>,
+    /// Every recorded policy revision, oldest first (for `as_of` evaluation)
+    history: std::sync::Mutex<Vec<PolicyRevision>>,
+    groups: RwLock<Vec<PolicyGroup>>,
+    /// Per-policy usage counters, kept in memory and flushed to the log periodically
+    policy_stats: std::sync::Mutex<HashMap<Uuid, PolicyStats>>,
+    /// Start and count of the current one-second window of each rate-limited policy
+    rate_windows: std::sync::Mutex<HashMap<Uuid, (Instant, u32)>>,
+    /// Latencies of the last `LATENCY_WINDOW` evaluations, in microseconds
+    latencies: std::sync::Mutex<VecDeque<u64>>,
+    /// Identity store used to enrich evaluation input (shared with the API layer)
+    identities: Option<Arc<RwLock<Vec<Identity>>>>,
+    metrics: Arc<Metrics>,
+    /// Remote decision backend, set when `opa_url` is configured
+    opa: Option<OpaRestEngine>,
+    /// Run in order around every evaluation (see `EvaluationHook`)
+    hooks: Vec<Arc<dyn EvaluationHook>>,
+    /// Receive every live decision in the background (see `DecisionSink`)
+    sinks: Vec<Arc<dyn DecisionSink>>,
+    /// Verifies `subject_token` on decision requests
+    jwt: Option<Arc<JwtService>>,
 }
 
 impl PolicyEngine {
     pub fn new() -> Self {
-        let engine = Self {
+        Self::with_config(EngineConfig::default())
+    }
+
+    pub fn with_config(config: EngineConfig) -> Self {
+        let opa = config.opa_url.as_deref().map(|url| {
+            let package = config.opa_package.as_deref().unwrap_or("zedid");
+            info!("Delegating policy decisions to OPA at {} (data.{}.allow)", url, package);
+            OpaRestEngine::new(url, package)
+        });
+        Self {
             policies: std::sync::Arc::new(tokio::sync::RwLock::new(vec![])),
-        };
-        engine
+            namespaces: RwLock::new(vec![]),
+            config,
+            decision_cache: std::sync::Mutex::new(HashMap::new()),
+            history: std::sync::Mutex::new(vec![]),
+            groups: RwLock::new(vec![]),
+            policy_stats: std::sync::Mutex::new(HashMap::new()),
+            rate_windows: std::sync::Mutex::new(HashMap::new()),
+            latencies: std::sync::Mutex::new(VecDeque::new()),
+            identities: None,
+            metrics: Arc::new(Metrics::new()),
+            opa,
+            hooks: vec![],
+            sinks: vec![],
+            jwt: None,
+        }
     }
 
-    pub async fn seed_demo_policies(&self) {
-        let mut store = self.policies.write().await;
+    /// Attach the identity store so `evaluate` can enrich input with identity
+    /// attributes. Installs `IdentityEnrichmentHook` ahead of any other hook.
+    pub fn with_identities(mut self, identities: Arc<RwLock<Vec<Identity>>>) -> Self {
+        let enrichment = IdentityEnrichmentHook::new(
+            Arc::clone(&identities),
+            self.config.keep_trust_on_svid_expiry,
+        );
+        self.hooks.insert(0, Arc::new(enrichment));
+        self.identities = Some(identities);
+        self
+    }
+
+    /// Accept decision requests that carry a `subject_token` signed by this service
+    pub fn with_jwt_service(mut self, jwt: Arc<JwtService>) -> Self {
+        self.jwt = Some(jwt);
+        self
+    }
+
+    /// Append an evaluation hook; hooks run in the order they were added
+    pub fn with_hook(mut self, hook: Arc<dyn EvaluationHook>) -> Self {
+        info!("Registered evaluation hook {}", hook.name());
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Append a decision sink; sinks are called in the order they were added
+    pub fn with_sink(mut self, sink: Arc<dyn DecisionSink>) -> Self {
+        info!("Registered decision sink {}", sink.name());
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Report engine counters into a shared registry
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
 
+    /// Seed the demo policies, validating each as a created policy would be:
+    /// `validation_passed` records the result and failures are logged.
+    /// Returns one message per seed that failed validation.
+    pub async fn seed_demo_policies(&self) -> Vec<String> {
         // Demo policy 1: Checkout service can read inventory
         let mut p1 = Policy::new(
             "checkout-reads-inventory",
@@ -38,7 +211,6 @@ impl PolicyEngine {
         p1.resources = vec!["inventory-service".to_string()];
         p1.actions = vec!["GET".to_string(), "LIST".to_string()];
         p1.explanation = "The checkout service is permitted to read inventory data to display product availability. Write operations are explicitly denied.".to_string();
-        p1.validation_passed = true;
         p1.tags = vec!["production".to_string(), "e-commerce".to_string()];
 
         // Demo policy 2: TARS AI agent routing policy
@@ -58,7 +230,6 @@ impl PolicyEngine {
         p2.explanation = "AI agents with trust_level >= 2 may route requests through TARS. Budget limits are enforced per agent per day.".to_string();
         p2.ai_generated = true;
         p2.ai_model_used = Some("gemini-2.0-flash".to_string());
-        p2.validation_passed = true;
         p2.tags = vec!["ai-governance".to_string(), "tars".to_string()];
 
         // Demo policy 3: Admin access policy
@@ -76,13 +247,232 @@ impl PolicyEngine {
         p3.resources = vec!["zedid-api/*".to_string()];
         p3.actions = vec!["*".to_string()];
         p3.explanation = "Platform administrators can perform all operations on ZedID APIs. This policy requires trust_level=4 (Critical).".to_string();
-        p3.validation_passed = true;
         p3.tags = vec!["admin".to_string(), "privileged".to_string()];
 
-        store.push(p1);
-        store.push(p2);
-        store.push(p3);
+        // Startup self-check: the demo data goes through the same validation
+        // as any created policy
+        let mut failures = vec![];
+        let mut seeds = [p1, p2, p3];
+        for policy in &mut seeds {
+            let validation = self.validate_policy(policy);
+            policy.validation_passed = validation.passed;
+            if !validation.passed {
+                warn!(policy = %policy.name, errors = ?validation.errors, "Seeded policy failed validation");
+                failures.push(format!("{}: {}", policy.name, validation.errors.join("; ")));
+            }
+        }
+        info!(
+            validated = seeds.len() - failures.len(),
+            failed = failures.len(),
+            "Startup self-check of seeded policies complete"
+        );
+
+        let mut store = self.policies.write().await;
+        for policy in seeds {
+            self.record_revision(&policy);
+            store.push(policy);
+        }
         info!("Seeded {} demo policies", store.len());
+        failures
+    }
+
+    pub async fn seed_demo_namespaces(&self) {
+        let mut store = self.namespaces.write().await;
+        let mut system = Namespace::new("system", "ZedID control plane", Some("platform-team"));
+        system.default_trust_level = zedid_identity::TrustLevel::Critical;
+        let mut production = Namespace::new("production", "Customer-facing services", Some("sre-team"));
+        production.default_trust_level = zedid_identity::TrustLevel::High;
+        store.push(system);
+        store.push(production);
+        store.push(Namespace::new("platform", "Shared platform services", Some("platform-team")));
+        store.push(Namespace::new("ai-platform", "TARS-routed AI agents", Some("ml-team")));
+        info!("Seeded {} demo namespaces", store.len());
+    }
+
+    /// Drop every policy, namespace, group, revision, usage counter and cached
+    /// decision, then seed the demo namespaces and policies again. Returns the
+    /// number of namespaces and policies seeded.
+    pub async fn reset_demo_data(&self) -> (usize, usize) {
+        self.policies.write().await.clear();
+        self.namespaces.write().await.clear();
+        self.groups.write().await.clear();
+        self.history.lock().unwrap().clear();
+        self.policy_stats.lock().unwrap().clear();
+        self.decision_cache.lock().unwrap().clear();
+        self.seed_demo_namespaces().await;
+        self.seed_demo_policies().await;
+        (self.namespaces.read().await.len(), self.policies.read().await.len())
+    }
+
+    pub async fn list_namespaces(&self) -> Vec<Namespace> {
+        self.namespaces.read().await.clone()
+    }
+
+    pub async fn get_namespace(&self, name: &str) -> Option<Namespace> {
+        let store = self.namespaces.read().await;
+        store.iter().find(|n| n.name == name).cloned()
+    }
+
+    pub async fn create_namespace(&self, namespace: Namespace) -> Result<Namespace, PolicyError> {
+        if namespace.name.trim().is_empty() {
+            return Err(PolicyError::ValidationFailed(
+                "Namespace name cannot be empty".to_string(),
+            ));
+        }
+        let mut store = self.namespaces.write().await;
+        if store.iter().any(|n| n.name == namespace.name) {
+            return Err(PolicyError::Conflict(format!(
+                "Namespace already exists: {}",
+                namespace.name
+            )));
+        }
+        store.push(namespace.clone());
+        info!("Namespace created: {}", namespace.name);
+        Ok(namespace)
+    }
+
+    /// Combining algorithm in effect for a namespace
+    pub async fn combining_algorithm(&self, namespace: &str) -> CombiningAlgorithm {
+        self.get_namespace(namespace)
+            .await
+            .and_then(|n| n.combining_algorithm)
+            .unwrap_or(self.config.combining_algorithm)
+    }
+
+    /// Deny-reason verbosity for untrusted callers in a namespace
+    pub async fn reason_verbosity(&self, namespace: &str) -> ReasonVerbosity {
+        let configured = self
+            .get_namespace(namespace)
+            .await
+            .and_then(|n| n.reason_verbosity);
+        configured.unwrap_or(if self.config.redact_deny_reasons {
+            ReasonVerbosity::Redacted
+        } else {
+            ReasonVerbosity::Detailed
+        })
+    }
+
+    /// Drop caller-supplied context fields the namespace doesn't allow, or
+    /// reject the request if so configured and `may_reject` is set.
+    /// `None` when nothing was removed.
+    async fn filter_context(
+        &self,
+        req: &PolicyDecisionRequest,
+        may_reject: bool,
+    ) -> Result<Option<PolicyDecisionRequest>, PolicyError> {
+        let allowlist = self
+            .get_namespace(&req.namespace)
+            .await
+            .and_then(|n| n.context_allowlist)
+            .or_else(|| self.config.context_allowlist.clone());
+        let allowed = |field: &str| match &allowlist {
+            Some(list) => list.iter().any(|f| f == field),
+            None => !PROTECTED_CONTEXT_FIELDS.contains(&field),
+        };
+
+        let mut context = req.context.to_value();
+        let Some(fields) = context.as_object_mut() else {
+            return Ok(None);
+        };
+        let disallowed: Vec<String> = fields.keys().filter(|k| !allowed(k)).cloned().collect();
+        if disallowed.is_empty() {
+            return Ok(None);
+        }
+        if may_reject && self.config.reject_disallowed_context {
+            return Err(PolicyError::ValidationFailed(format!(
+                "Context fields not allowed in namespace {}: {}",
+                req.namespace,
+                disallowed.join(", ")
+            )));
+        }
+        warn!(
+            namespace = %req.namespace,
+            subject = %req.subject,
+            fields = %disallowed.join(","),
+            "Stripped caller-supplied context fields"
+        );
+        self.metrics.add(metrics::CONTEXT_FIELDS_STRIPPED_TOTAL, disallowed.len() as u64);
+        for field in &disallowed {
+            fields.remove(field);
+        }
+        let mut filtered = req.clone();
+        filtered.context = context.into();
+        Ok(Some(filtered))
+    }
+
+    pub async fn update_namespace(
+        &self,
+        name: &str,
+        update: crate::models::UpdateNamespaceRequest,
+    ) -> Result<Namespace, PolicyError> {
+        let mut store = self.namespaces.write().await;
+        let namespace = store
+            .iter_mut()
+            .find(|n| n.name == name)
+            .ok_or_else(|| PolicyError::NotFound(name.to_string()))?;
+        if let Some(description) = update.description {
+            namespace.description = description;
+        }
+        if let Some(owner) = update.owner {
+            namespace.owner = Some(owner);
+        }
+        if let Some(level) = update.default_trust_level {
+            namespace.default_trust_level = level;
+        }
+        if let Some(mode) = update.enforcement_mode {
+            info!("Namespace {} enforcement mode: {:?}", name, mode);
+            namespace.enforcement_mode = mode;
+        }
+        if let Some(verbosity) = update.reason_verbosity {
+            namespace.reason_verbosity = Some(verbosity);
+        }
+        if let Some(allowlist) = update.context_allowlist {
+            namespace.context_allowlist = Some(allowlist);
+            self.invalidate_decisions(name);
+        }
+        if let Some(algorithm) = update.combining_algorithm {
+            info!("Namespace {} combining algorithm: {:?}", name, algorithm);
+            namespace.combining_algorithm = Some(algorithm);
+            self.invalidate_decisions(name);
+        }
+        if let Some(variables) = update.variables {
+            namespace.variables = variables;
+            // Rendered policies in this namespace may now decide differently
+            self.invalidate_decisions(name);
+        }
+        Ok(namespace.clone())
+    }
+
+    /// Set or clear the policy every evaluation in `name` falls back on. It
+    /// may live in any namespace and is consulted while it is Active.
+    pub async fn set_default_policy(&self, name: &str, policy_id: Option<Uuid>) -> Result<Namespace, PolicyError> {
+        if let Some(id) = policy_id {
+            let policy = self.get_policy(id).await.ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+            if policy.status == PolicyStatus::Archived {
+                return Err(PolicyError::ValidationFailed(format!(
+                    "Archived policy '{}' cannot be a namespace default",
+                    policy.name
+                )));
+            }
+        }
+        let mut store = self.namespaces.write().await;
+        let namespace = store
+            .iter_mut()
+            .find(|n| n.name == name)
+            .ok_or_else(|| PolicyError::NotFound(name.to_string()))?;
+        namespace.default_policy_id = policy_id;
+        self.invalidate_decisions(name);
+        info!("Namespace {} default policy: {:?}", name, policy_id);
+        Ok(namespace.clone())
+    }
+
+    pub async fn delete_namespace(&self, name: &str) -> Result<Namespace, PolicyError> {
+        let mut store = self.namespaces.write().await;
+        let pos = store
+            .iter()
+            .position(|n| n.name == name)
+            .ok_or_else(|| PolicyError::NotFound(name.to_string()))?;
+        Ok(store.remove(pos))
     }
 
     pub async fn list_policies(&self, namespace: Option<&str>) -> Vec<Policy> {
@@ -93,259 +483,3860 @@ impl PolicyEngine {
         }
     }
 
+    /// Case-insensitive search over a policy's text fields and lists.
+    /// Filters are applied first; policies with no matching field are dropped.
+    pub async fn search_policies(
+        &self,
+        query: &str,
+        namespace: Option<&str>,
+        tag: Option<&str>,
+        status: Option<&PolicyStatus>,
+    ) -> Vec<PolicySearchHit> {
+        let store = self.policies.read().await;
+        store
+            .iter()
+            .filter(|p| namespace.is_none_or(|ns| p.namespace == ns))
+            .filter(|p| tag.is_none_or(|t| p.tags.iter().any(|pt| pt == t)))
+            .filter(|p| status.is_none_or(|s| p.status == *s))
+            .filter_map(|p| {
+                let lists = [("subjects", &p.subjects), ("resources", &p.resources), ("actions", &p.actions)];
+                let matches: Vec<SearchMatch> = [
+                    ("name", p.name.as_str()),
+                    ("description", p.description.as_str()),
+                    ("content", p.content.as_str()),
+                    ("explanation", p.explanation.as_str()),
+                ]
+                .into_iter()
+                .filter_map(|(field, text)| search_snippet(text, query).map(|s| (field, s)))
+                .chain(lists.into_iter().filter_map(|(field, values)| {
+                    values.iter().find_map(|v| search_snippet(v, query)).map(|s| (field, s))
+                }))
+                .map(|(field, snippet)| SearchMatch { field: field.to_string(), snippet })
+                .collect();
+                (!matches.is_empty()).then(|| PolicySearchHit { policy: p.clone(), matches })
+            })
+            .collect()
+    }
+
     pub async fn get_policy(&self, id: Uuid) -> Option<Policy> {
         let store = self.policies.read().await;
         store.iter().find(|p| p.id == id).cloned()
     }
 
-    pub async fn add_policy(&self, policy: Policy) -> Policy {
+    /// Store a new policy, subject to `max_policies` and, for Drafts,
+    /// `max_drafts_per_namespace`
+    pub async fn add_policy(&self, policy: Policy) -> Result<Policy, PolicyError> {
         let mut store = self.policies.write().await;
+        self.check_capacity(&store, &policy.namespace, policy.status == PolicyStatus::Draft)?;
         store.push(policy.clone());
+        self.record_revision(&policy);
+        self.invalidate_decisions(&policy.namespace);
         info!("Policy added: {} ({})", policy.name, policy.id);
-        policy
+        Ok(policy)
     }
 
-    pub async fn update_policy_status(
-        &self,
-        id: Uuid,
-        status: PolicyStatus,
-    ) -> Result<Policy, PolicyError> {
-        let mut store = self.policies.write().await;
-        let policy = store
-            .iter_mut()
-            .find(|p| p.id == id)
-            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
-        policy.status = status;
-        policy.updated_at = chrono::Utc::now();
-        Ok(policy.clone())
+    /// Whether one more policy (a Draft if `draft`) fits in `namespace`.
+    /// Generation checks this up front so a full store costs no tokens.
+    pub async fn ensure_capacity(&self, namespace: &str, draft: bool) -> Result<(), PolicyError> {
+        self.check_capacity(&self.policies.read().await, namespace, draft)
     }
 
-    /// Evaluate a policy decision — the core enforcement engine
-    /// In production: calls OPA REST API or uses embedded regorus
-    pub async fn evaluate(
+    fn check_capacity(&self, store: &[Policy], namespace: &str, draft: bool) -> Result<(), PolicyError> {
+        let max = self.config.max_policies;
+        if max > 0 && store.len() >= max {
+            return Err(PolicyError::QuotaExceeded(format!(
+                "the policy store is full ({} policies); delete unused policies first",
+                max
+            )));
+        }
+        let max = self.config.max_drafts_per_namespace;
+        if draft && max > 0 {
+            let drafts = store
+                .iter()
+                .filter(|p| p.namespace == namespace && p.status == PolicyStatus::Draft)
+                .count();
+            if drafts >= max {
+                return Err(PolicyError::Conflict(format!(
+                    "namespace {} already has {} Draft policies; review or delete some first",
+                    namespace, drafts
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Export policies (optionally one namespace) as a portable bundle
+    pub async fn export_bundle(&self, namespace: Option<&str>) -> PolicyBundle {
+        PolicyBundle {
+            schema_version: POLICY_BUNDLE_SCHEMA_VERSION,
+            exported_at: chrono::Utc::now(),
+            namespace: namespace.map(str::to_string),
+            policies: self.render_policies(self.list_policies(namespace).await).await,
+        }
+    }
+
+    /// Render templated policies against built-in, namespace and policy variables
+    /// (later sources win). Policies without placeholders are returned unchanged.
+    pub async fn render_policies(&self, mut policies: Vec<Policy>) -> Vec<Policy> {
+        let namespaces = self.namespaces.read().await;
+        for policy in policies.iter_mut() {
+            let templated = template::is_template(&policy.content)
+                || policy.subjects.iter().any(|s| template::is_template(s))
+                || policy.resources.iter().any(|r| template::is_template(r))
+                || policy.excluded_subjects.iter().any(|s| template::is_template(s))
+                || policy.excluded_resources.iter().any(|r| template::is_template(r));
+            if !templated {
+                continue;
+            }
+            let mut vars = HashMap::from([
+                ("trust_domain".to_string(), self.config.trust_domain.clone()),
+                ("namespace".to_string(), policy.namespace.clone()),
+            ]);
+            if let Some(ns) = namespaces.iter().find(|n| n.name == policy.namespace) {
+                vars.extend(ns.variables.clone());
+            }
+            vars.extend(policy.variables.clone());
+
+            policy.content = template::render(&policy.content, &vars);
+            for subject in policy.subjects.iter_mut() {
+                *subject = template::render(subject, &vars);
+            }
+            for resource in policy.resources.iter_mut() {
+                *resource = template::render(resource, &vars);
+            }
+            for excluded in policy
+                .excluded_subjects
+                .iter_mut()
+                .chain(policy.excluded_resources.iter_mut())
+            {
+                *excluded = template::render(excluded, &vars);
+            }
+        }
+        policies
+    }
+
+    /// Import a bundle. Ids are regenerated unless `preserve_ids`, and policies
+    /// land as Draft unless `keep_status`. Every policy is re-validated. An
+    /// exported Active policy is only imported Active if it validates and
+    /// passes the same gates as `activate_policy`; otherwise it stays Draft.
+    pub async fn import_bundle(
         &self,
-        req: &PolicyDecisionRequest,
-    ) -> Result<PolicyDecisionResponse, PolicyError> {
-        let start = Instant::now();
-        debug!(
-            "Evaluating: subject={} resource={} action={}",
-            req.subject, req.resource, req.action
-        );
+        bundle: PolicyBundle,
+        preserve_ids: bool,
+        keep_status: bool,
+    ) -> Result<Vec<PolicyImportResult>, PolicyError> {
+        if bundle.schema_version != POLICY_BUNDLE_SCHEMA_VERSION {
+            return Err(PolicyError::ValidationFailed(format!(
+                "Unsupported bundle schema_version {} (expected {})",
+                bundle.schema_version, POLICY_BUNDLE_SCHEMA_VERSION
+            )));
+        }
 
-        let store = self.policies.read().await;
+        let mut results = Vec::with_capacity(bundle.policies.len());
+        for mut policy in bundle.policies {
+            if preserve_ids {
+                if self.get_policy(policy.id).await.is_some() {
+                    results.push(PolicyImportResult {
+                        name: policy.name,
+                        id: Some(policy.id),
+                        imported: false,
+                        validation_passed: false,
+                        reason: Some("A policy with this id already exists".to_string()),
+                    });
+                    continue;
+                }
+            } else {
+                policy.id = Uuid::new_v4();
+            }
 
-        // Find applicable active policies
-        let applicable: Vec<&Policy> = store
-            .iter()
-            .filter(|p| {
-                p.status == PolicyStatus::Active
-                    && (p.namespace == req.namespace || p.namespace == "system")
-            })
-            .collect();
+            if let Err(e) = check_policy_limits(&policy) {
+                results.push(PolicyImportResult {
+                    name: policy.name,
+                    id: None,
+                    imported: false,
+                    validation_passed: false,
+                    reason: Some(e.to_string()),
+                });
+                continue;
+            }
 
-        if applicable.is_empty() {
-            warn!("No active policies found for namespace: {}", req.namespace);
-            return Ok(PolicyDecisionResponse {
-                allowed: false,
-                reason: "No applicable policies found — deny by default".to_string(),
-                policy_id: None,
-                policy_name: None,
-                evaluation_time_ms: start.elapsed().as_millis() as u64,
-                decision_id: Uuid::new_v4(),
+            let activate = keep_status && policy.status == PolicyStatus::Active;
+            if !keep_status || activate {
+                policy.status = PolicyStatus::Draft;
+                policy.approved_by = None;
+            }
+            // A copy of a Git-synced policy is maintained here from now on
+            policy.source = PolicySource::Local;
+            policy.source_path = None;
+            policy.source_commit = None;
+            policy.updated_at = chrono::Utc::now();
+
+            let validation = self.validate_policy(&policy);
+            policy.validation_passed = validation.passed;
+
+            let name = policy.name.clone();
+            let policy = match self.add_policy(policy).await {
+                Ok(policy) => policy,
+                Err(e) => {
+                    results.push(PolicyImportResult {
+                        name,
+                        id: None,
+                        imported: false,
+                        validation_passed: validation.passed,
+                        reason: Some(e.to_string()),
+                    });
+                    continue;
+                }
+            };
+            let mut reason = (!validation.passed).then(|| validation.errors.join("; "));
+            let policy = match activate {
+                true if validation.passed => match self.activate_policy(policy.id, false).await {
+                    Ok(active) => active,
+                    Err(e) => {
+                        reason = Some(format!("Imported as Draft: {}", e));
+                        policy
+                    }
+                },
+                true => {
+                    reason = reason.map(|r| format!("Imported as Draft: {}", r));
+                    policy
+                }
+                false => policy,
+            };
+            results.push(PolicyImportResult {
+                name: policy.name,
+                id: Some(policy.id),
+                imported: true,
+                validation_passed: validation.passed,
+                reason,
             });
         }
+        Ok(results)
+    }
 
-        // Simulate OPA evaluation logic
-        // In production: POST to OPA /v1/data/zedid/allow
-        for policy in &applicable {
-            if let Some(result) = simulate_rego_evaluation(policy, req) {
-                let elapsed = start.elapsed().as_millis() as u64;
-                info!(
-                    "Decision: {} | policy={} | {}ms",
-                    if result { "ALLOW" } else { "DENY" },
-                    policy.name,
-                    elapsed
-                );
-                return Ok(PolicyDecisionResponse {
-                    allowed: result,
-                    reason: if result {
-                        format!("Allowed by policy: {}", policy.name)
-                    } else {
-                        format!("Denied by policy: {}", policy.name)
-                    },
-                    policy_id: Some(policy.id),
-                    policy_name: Some(policy.name.clone()),
-                    evaluation_time_ms: elapsed,
-                    decision_id: Uuid::new_v4(),
+    /// Upsert policies parsed from the policy repository at `commit`, keyed
+    /// by file path. Invalid files, including ones that name no subjects or
+    /// resources (they would apply to every request), keep the last good
+    /// version. A file whose
+    /// name clashes with a local policy in its namespace is reported, not
+    /// applied, and synced policies whose file is gone are disabled.
+    pub async fn sync_git_policies(&self, commit: &str, policies: Vec<Policy>) -> GitSyncReport {
+        let mut report = GitSyncReport {
+            commit: commit.to_string(),
+            synced_at: chrono::Utc::now(),
+            created: vec![],
+            updated: vec![],
+            removed: vec![],
+            unchanged: 0,
+            conflicts: vec![],
+            invalid: vec![],
+        };
+        let mut store = self.policies.write().await;
+        let mut touched: Vec<String> = vec![];
+        let mut paths: Vec<String> = vec![];
+
+        for mut incoming in policies {
+            let path = incoming.source_path.clone().unwrap_or_default();
+            paths.push(path.clone());
+            let validation = self.validate_policy(&incoming);
+            let check = check_policy_limits(&incoming).err().map(|e| e.to_string());
+            let unscoped = (incoming.subjects.is_empty() && incoming.resources.is_empty())
+                .then(|| "no `# zedid:subjects` or `# zedid:resources` annotation — it would apply to every request".to_string());
+            if let Some(reason) = check
+                .or(unscoped)
+                .or_else(|| (!validation.passed).then(|| validation.errors.join("; ")))
+            {
+                report.invalid.push(GitSyncIssue { path, reason, policy_id: None });
+                continue;
+            }
+            incoming.validation_passed = true;
+
+            let synced = store
+                .iter_mut()
+                .find(|p| p.source == PolicySource::Git && p.source_path.as_deref() == Some(path.as_str()));
+            if let Some(existing) = synced {
+                existing.source_commit = incoming.source_commit.clone();
+                if same_git_policy(existing, &incoming) {
+                    report.unchanged += 1;
+                    continue;
+                }
+                // The file may have moved the policy to another namespace
+                touched.push(existing.namespace.clone());
+                incoming.id = existing.id;
+                incoming.created_at = existing.created_at;
+                incoming.version = existing.version + 1;
+                *existing = incoming;
+                self.record_revision(existing);
+                touched.push(existing.namespace.clone());
+                report.updated.push(path);
+                continue;
+            }
+
+            let local = store.iter().find(|p| {
+                p.source == PolicySource::Local && p.namespace == incoming.namespace && p.name == incoming.name
+            });
+            if let Some(local) = local {
+                report.conflicts.push(GitSyncIssue {
+                    reason: format!(
+                        "a locally-authored policy named {} already exists in namespace {}",
+                        local.name, local.namespace
+                    ),
+                    path,
+                    policy_id: Some(local.id),
                 });
+                continue;
             }
+            if let Err(e) = self.check_capacity(&store, &incoming.namespace, false) {
+                report.invalid.push(GitSyncIssue { path, reason: e.to_string(), policy_id: None });
+                continue;
+            }
+            self.record_revision(&incoming);
+            touched.push(incoming.namespace.clone());
+            store.push(incoming);
+            report.created.push(path);
+        }
+
+        let now = chrono::Utc::now();
+        for policy in store.iter_mut().filter(|p| {
+            p.source == PolicySource::Git
+                && p.status != PolicyStatus::Disabled
+                && !paths.iter().any(|path| p.source_path.as_deref() == Some(path.as_str()))
+        }) {
+            policy.status = PolicyStatus::Disabled;
+            policy.updated_at = now;
+            self.record_revision(policy);
+            touched.push(policy.namespace.clone());
+            report.removed.push(policy.source_path.clone().unwrap_or_default());
+        }
+
+        touched.sort();
+        touched.dedup();
+        for namespace in &touched {
+            self.invalidate_decisions(namespace);
         }
+        info!(
+            commit,
+            created = report.created.len(),
+            updated = report.updated.len(),
+            removed = report.removed.len(),
+            conflicts = report.conflicts.len(),
+            invalid = report.invalid.len(),
+            "Synced policies from Git"
+        );
+        report
+    }
 
-        Ok(PolicyDecisionResponse {
-            allowed: false,
-            reason: "No matching policy rule — implicit deny".to_string(),
-            policy_id: None,
-            policy_name: None,
-            evaluation_time_ms: start.elapsed().as_millis() as u64,
-            decision_id: Uuid::new_v4(),
+    pub async fn update_policy_status(
+        &self,
+        id: Uuid,
+        status: PolicyStatus,
+    ) -> Result<Policy, PolicyError> {
+        if status == PolicyStatus::Active {
+            self.ensure_tests_pass(id).await?;
+        }
+        self.modify_policy(id, |policy| {
+            self.check_status_change(policy, &status)?;
+            policy.status = status;
+            Ok(())
         })
+        .await
     }
 
-    /// Validate a policy document
-    pub fn validate_policy(&self, policy: &Policy) -> PolicyValidationResult {
-        let mut errors = vec![];
-        let mut warnings = vec![];
+    /// Activate a single policy. Simulated policies are refused unless `force`
+    /// is set, so demo output can't slip into enforcement unnoticed.
+    pub async fn activate_policy(&self, id: Uuid, force: bool) -> Result<Policy, PolicyError> {
+        self.ensure_tests_pass(id).await?;
+        self.modify_policy(id, |policy| {
+            if policy.is_simulated {
+                if !force {
+                    return Err(PolicyError::Conflict(format!(
+                        "Policy {} was generated in simulation mode; activate with force to enforce it anyway",
+                        policy.name
+                    )));
+                }
+                warn!("Force-activating simulated policy {} ({})", policy.name, policy.id);
+            }
+            self.check_status_change(policy, &PolicyStatus::Active)?;
+            policy.status = PolicyStatus::Active;
+            Ok(())
+        })
+        .await
+    }
 
-        if policy.content.is_empty() {
-            errors.push("Policy content cannot be empty".to_string());
-        }
+    /// Replace a policy's plain-language explanation
+    pub async fn set_explanation(&self, id: Uuid, explanation: String) -> Result<Policy, PolicyError> {
+        self.modify_policy(id, |policy| {
+            policy.explanation = explanation;
+            Ok(())
+        })
+        .await
+    }
 
-        if policy.subjects.is_empty() {
-            warnings.push("No subjects specified — policy may be overly broad".to_string());
-        }
+    /// Every policy (in any status) whose subjects select `identity`, matched
+    /// by SPIFFE ID, id, email or `role:` entries with the same wildcard rules
+    /// as evaluation. Exclusions are honoured; namespaces are not filtered,
+    /// since a workload can call into any namespace.
+    pub async fn policies_for_identity(&self, identity: &Identity) -> Vec<GoverningPolicy> {
+        let names: Vec<String> = [identity.spiffe_id.clone(), Some(identity.id.to_string()), identity.email.clone()]
+            .into_iter()
+            .flatten()
+            .collect();
+        let roles = identity.roles();
+        let policies = self.render_policies(self.list_policies(None).await).await;
+        policies
+            .into_iter()
+            .filter_map(|policy| {
+                let matched_by = identity_match(&policy, &names, &roles)?;
+                Some(GoverningPolicy {
+                    policy_id: policy.id,
+                    effect: policy_effect(&policy),
+                    name: policy.name,
+                    namespace: policy.namespace,
+                    kind: policy.kind,
+                    status: policy.status,
+                    matched_by,
+                })
+            })
+            .collect()
+    }
 
-        if policy.resources.is_empty() {
-            warnings.push("No resources specified — policy may be overly broad".to_string());
+    /// What `identity` could do under a single policy: each of the policy's
+    /// resources and actions is evaluated as the identity, enriched like a
+    /// live decision, against that policy alone (whatever its status)
+    pub async fn impact_on_identity(&self, id: Uuid, identity: &Identity) -> Result<IdentityImpact, PolicyError> {
+        let policy = self
+            .get_policy(id)
+            .await
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        let policies = self.render_policies(vec![policy]).await;
+        let policy = &policies[0];
+        if policy.resources.is_empty() || policy.actions.is_empty() {
+            return Err(PolicyError::ValidationFailed(
+                "Policy lists no resources or actions to evaluate".to_string(),
+            ));
         }
 
-        match policy.kind {
-            PolicyKind::Rego => {
-                if !policy.content.contains("package") {
-                    errors.push("Rego policy must have a package declaration".to_string());
+        let subject = identity.spiffe_id.clone().unwrap_or_else(|| identity.id.to_string());
+        let mut trust_level = identity.trust_level.clone();
+        let mut resources = vec![];
+        for resource in &policy.resources {
+            let mut impact = ResourceImpact { resource: resource.clone(), allowed: vec![], denied: vec![] };
+            for action in &policy.actions {
+                let req = PolicyDecisionRequest {
+                    subject: subject.clone(),
+                    resource: resource.clone(),
+                    action: action.clone(),
+                    namespace: policy.namespace.clone(),
+                    context: Default::default(),
+                    skip_enrichment: false,
+                    group: None,
+                    subject_token: None,
+                    identity_snapshot: None,
+                    identity: None,
+                };
+                let req = self.pre_evaluate(req, None).await;
+                if let Some(enriched) = &req.identity {
+                    trust_level = enriched.trust_level.clone();
                 }
-                if !policy.content.contains("allow") && !policy.content.contains("deny") {
-                    warnings.push(
-                        "Rego policy should define 'allow' or 'deny' rules".to_string(),
-                    );
+                let response = decide(&policies, &build_input(&req), Instant::now());
+                if response.allowed {
+                    impact.allowed.push(action.clone());
+                } else {
+                    impact.denied.push(action.clone());
                 }
             }
-            PolicyKind::Cedar => {
-                if !policy.content.contains("permit") && !policy.content.contains("forbid") {
-                    errors.push("Cedar policy must have permit or forbid rules".to_string());
+            resources.push(impact);
+        }
+        Ok(IdentityImpact {
+            policy_id: policy.id,
+            policy_name: policy.name.clone(),
+            identity_id: identity.id,
+            subject,
+            trust_level,
+            allowed: resources.iter().map(|r| r.allowed.len()).sum(),
+            denied: resources.iter().map(|r| r.denied.len()).sum(),
+            resources,
+        })
+    }
+
+    /// Every (resource, action) `subject` is allowed right now. Candidate
+    /// pairs are the resources × actions of the active policies selecting the
+    /// subject (`*` where a list is empty); each is decided like a live
+    /// request, so denies and combining algorithms apply. `system` policies
+    /// are checked in the subject's own namespace when it is known.
+    pub async fn effective_permissions(&self, subject: &str) -> Result<SubjectPermissions, PolicyError> {
+        let subject = normalize_spiffe_id(subject);
+        let identity = match &self.identities {
+            Some(ids) => ids
+                .read()
+                .await
+                .iter()
+                .find(|i| i.spiffe_id.as_deref() == Some(subject.as_str()) || i.id.to_string() == subject)
+                .cloned(),
+            None => None,
+        };
+        let (names, roles) = match &identity {
+            Some(i) => (
+                [i.spiffe_id.clone(), Some(i.id.to_string()), i.email.clone()].into_iter().flatten().collect(),
+                i.roles(),
+            ),
+            None => (vec![subject.clone()], vec![]),
+        };
+
+        let now = chrono::Utc::now();
+        let active = self
+            .list_policies(None)
+            .await
+            .into_iter()
+            .filter(|p| p.status == PolicyStatus::Active && p.is_in_window(now))
+            .collect();
+        let mut candidates: Vec<(String, String, String, &Policy)> = vec![];
+        let selecting = self.render_policies(active).await;
+        for policy in selecting.iter().filter(|p| identity_match(p, &names, &roles).is_some()) {
+            let namespace = match (&identity, policy.namespace.as_str()) {
+                (Some(i), "system") => i.namespace.clone(),
+                _ => policy.namespace.clone(),
+            };
+            let star = vec!["*".to_string()];
+            let resources = if policy.resources.is_empty() { &star } else { &policy.resources };
+            let actions = if policy.actions.is_empty() { &star } else { &policy.actions };
+            for resource in resources {
+                for action in actions {
+                    let pair = (namespace.clone(), resource.clone(), action.clone());
+                    if !candidates.iter().any(|(ns, r, a, _)| (ns, r, a) == (&pair.0, &pair.1, &pair.2)) {
+                        candidates.push((pair.0, pair.1, pair.2, policy));
+                    }
                 }
             }
-            _ => {}
         }
 
-        let coverage_score = if errors.is_empty() {
-            if warnings.is_empty() { 1.0 } else { 0.8 }
-        } else {
-            0.0
-        };
+        let (mut permissions, mut denied) = (vec![], vec![]);
+        for (namespace, resource, action, listed_by) in candidates {
+            let req = PolicyDecisionRequest {
+                subject: subject.clone(),
+                resource,
+                action,
+                namespace,
+                context: Default::default(),
+                skip_enrichment: false,
+                group: None,
+                subject_token: None,
+                identity_snapshot: None,
+                identity: None,
+            };
+            let req = self.pre_evaluate(req, None).await;
+            let (response, _, _) = self.decide_live(&req, false).await?;
+            if !response.allowed {
+                denied.push(req);
+                continue;
+            }
+            permissions.push(EffectivePermission {
+                namespace: req.namespace,
+                resource: req.resource,
+                action: req.action,
+                policy_id: response.policy_id.unwrap_or(listed_by.id),
+                policy_name: response.policy_name.unwrap_or_else(|| listed_by.name.clone()),
+                except: vec![],
+            });
+        }
+        for permission in permissions.iter_mut().filter(|p| p.resource.contains('*') || p.action == "*") {
+            permission.except = denied
+                .iter()
+                .filter(|d| {
+                    d.namespace == permission.namespace
+                        && pattern_matches(&permission.resource, &d.resource)
+                        && (permission.action == "*" || permission.action == d.action)
+                })
+                .map(|d| format!("{} {}", d.action, d.resource))
+                .collect();
+        }
+        Ok(SubjectPermissions {
+            subject,
+            identity_id: identity.map(|i| i.id),
+            permissions,
+            denied: denied.len(),
+        })
+    }
 
-        PolicyValidationResult {
-            passed: errors.is_empty(),
-            errors,
-            warnings,
-            coverage_score,
+    /// Decide each request as `evaluate` would — token verification, context
+    /// filtering, enrichment, hooks and the namespace's combining algorithm —
+    /// but without the decision cache, usage stats, latency or `last_seen`,
+    /// so reports and baselines don't skew live figures. Enforcement modes
+    /// are not applied: the raw decision is reported.
+    pub async fn evaluate_batch(
+        &self,
+        reqs: &[PolicyDecisionRequest],
+    ) -> Vec<Result<PolicyDecisionResponse, PolicyError>> {
+        let mut results = Vec::with_capacity(reqs.len());
+        for req in reqs {
+            results.push(self.evaluate_quietly(req).await);
         }
+        results
     }
-}
 
-impl Default for PolicyEngine {
-    fn default() -> Self {
-        Self::new()
+    async fn evaluate_quietly(&self, req: &PolicyDecisionRequest) -> Result<PolicyDecisionResponse, PolicyError> {
+        let start = Instant::now();
+        let (req, claims) = match self.verify_subject_token(req).await {
+            Ok(verified) => verified,
+            Err(reason) => {
+                return Ok(decision(false, format!("Invalid subject token — fail closed: {}", reason), None, start));
+            }
+        };
+        self.check_identity_snapshot(&req).map_err(PolicyError::ValidationFailed)?;
+        let filtered = self.filter_context(&req, true).await?;
+        let req = self.pre_evaluate(filtered.unwrap_or(req), claims.as_ref()).await;
+        let (mut response, _, _) = self.decide_live(&req, false).await?;
+        for hook in &self.hooks {
+            hook.post_evaluate(&req, &mut response).await;
+        }
+        Ok(response)
     }
-}
 
-/// Simulate Rego evaluation logic
-/// In production: use regorus crate or OPA REST API
-fn simulate_rego_evaluation(policy: &Policy, req: &PolicyDecisionRequest) -> Option<bool> {
-    // Check if any subject matches
-    let subject_matches = policy.subjects.is_empty()
-        || policy.subjects.iter().any(|s| {
-            s == &req.subject
-                || s.ends_with("/*")
-                    && req.subject.starts_with(s.trim_end_matches("/*"))
-                || s.starts_with("role:")
-        });
+    /// Decide `cases` (or, when empty, requests derived from the active
+    /// identities × the resources and actions listed by the namespace's active
+    /// policies) in `namespace`, for a pre-change baseline. Derived requests
+    /// beyond `limit` are sampled evenly, so the same store gives the same rows.
+    pub async fn evaluation_report(
+        &self,
+        namespace: &str,
+        cases: Vec<ReportCase>,
+        limit: Option<usize>,
+    ) -> Result<EvaluationReport, PolicyError> {
+        if cases.len() > MAX_REPORT_REQUESTS {
+            return Err(PolicyError::ValidationFailed(format!(
+                "A report takes at most {} requests ({} given)",
+                MAX_REPORT_REQUESTS,
+                cases.len()
+            )));
+        }
+        let derived = cases.is_empty();
+        let (cases, candidates) = if derived {
+            let all = self.report_candidates(namespace).await;
+            let limit = limit.unwrap_or(MAX_REPORT_REQUESTS).clamp(1, MAX_REPORT_REQUESTS);
+            let total = all.len();
+            let sampled = if total <= limit {
+                all
+            } else {
+                (0..limit).map(|i| all[i * total / limit].clone()).collect()
+            };
+            (sampled, total)
+        } else {
+            let total = cases.len();
+            (cases, total)
+        };
 
-    // Check if any resource matches
-    let resource_matches = policy.resources.is_empty()
-        || policy.resources.iter().any(|r| {
-            r == &req.resource
-                || r.ends_with("/*")
-                || r == "*"
-        });
+        let reqs: Vec<PolicyDecisionRequest> = cases
+            .into_iter()
+            .map(|case| PolicyDecisionRequest {
+                subject: case.subject,
+                resource: case.resource,
+                action: case.action,
+                namespace: namespace.to_string(),
+                context: case.context,
+                skip_enrichment: false,
+                group: None,
+                subject_token: None,
+                identity_snapshot: None,
+                identity: None,
+            })
+            .collect();
+        let results = self.evaluate_batch(&reqs).await;
+        let rows: Vec<EvaluationReportRow> = reqs
+            .into_iter()
+            .zip(results)
+            .map(|(req, result)| {
+                let (allowed, policy_id, policy_name, reason, error) = match result {
+                    Ok(r) => (r.allowed, r.policy_id, r.policy_name, r.reason, None),
+                    Err(e) => (false, None, None, "not decided".to_string(), Some(e.to_string())),
+                };
+                EvaluationReportRow {
+                    subject: req.subject,
+                    resource: req.resource,
+                    action: req.action,
+                    allowed,
+                    policy_id,
+                    policy_name,
+                    reason,
+                    error,
+                }
+            })
+            .collect();
+        let errors = rows.iter().filter(|r| r.error.is_some()).count();
+        let allowed = rows.iter().filter(|r| r.allowed).count();
+        Ok(EvaluationReport {
+            namespace: namespace.to_string(),
+            generated_at: chrono::Utc::now(),
+            derived,
+            candidates,
+            allowed,
+            denied: rows.len() - allowed - errors,
+            errors,
+            rows,
+        })
+    }
 
-    // Check if action matches
-    let action_matches = policy.actions.is_empty()
-        || policy.actions.iter().any(|a| a == &req.action || a == "*");
+    /// Namespaces × resources, with the number of active, in-window policies
+    /// governing each cell and whether any of them deny. `system` policies
+    /// count in every namespace, as in evaluation. Resources are the concrete
+    /// (wildcard-free) entries of every policy's `resources` plus the names
+    /// of known non-human identities; namespaces are the registered ones plus
+    /// any active policy's.
+    pub async fn coverage_heatmap(&self) -> CoverageHeatmap {
+        let now = chrono::Utc::now();
+        let all = self.list_policies(None).await;
+        let mut resources: Vec<String> = all
+            .iter()
+            .flat_map(|p| p.resources.iter())
+            .filter(|r| !r.contains('*') && !template::is_template(r))
+            .cloned()
+            .collect();
+        if let Some(ids) = &self.identities {
+            resources.extend(
+                ids.read()
+                    .await
+                    .iter()
+                    .filter(|i| !matches!(i.kind, IdentityKind::Human))
+                    .map(|i| i.name.clone()),
+            );
+        }
+        resources.sort();
+        resources.dedup();
 
-    if subject_matches && resource_matches && action_matches {
+        let active: Vec<Policy> = all
+            .into_iter()
+            .filter(|p| p.status == PolicyStatus::Active && p.is_in_window(now))
+            .collect();
+        let active = self.render_policies(active).await;
+        let mut namespaces: Vec<String> = self.list_namespaces().await.into_iter().map(|n| n.name).collect();
+        namespaces.extend(active.iter().map(|p| p.namespace.clone()));
+        namespaces.retain(|n| n != "system");
+        namespaces.sort();
+        namespaces.dedup();
+
+        // Index active policies (and their effect) by namespace once, so each
+        // row only scans its own and the system namespace's
+        let mut by_namespace: HashMap<&str, Vec<(&Policy, PolicyEffect)>> = HashMap::new();
+        for policy in &active {
+            by_namespace
+                .entry(policy.namespace.as_str())
+                .or_default()
+                .push((policy, policy_effect(policy)));
+        }
+        let system = by_namespace.get("system").cloned().unwrap_or_default();
+        let cells = namespaces
+            .iter()
+            .map(|namespace| {
+                let governing: Vec<_> = by_namespace
+                    .get(namespace.as_str())
+                    .into_iter()
+                    .flatten()
+                    .chain(system.iter())
+                    .collect();
+                resources
+                    .iter()
+                    .map(|resource| {
+                        let effects: Vec<PolicyEffect> = governing
+                            .iter()
+                            .filter(|(p, _)| governs_resource(p, resource))
+                            .map(|(_, effect)| *effect)
+                            .collect();
+                        let denies = effects.iter().filter(|e| **e == PolicyEffect::Deny).count();
+                        let coverage = match (effects.len(), denies) {
+                            (0, _) => CellCoverage::Uncovered,
+                            (_, 0) => CellCoverage::AllowOnly,
+                            _ => CellCoverage::HasDenies,
+                        };
+                        HeatmapCell { policies: effects.len(), denies, coverage }
+                    })
+                    .collect()
+            })
+            .collect();
+        CoverageHeatmap { generated_at: now, namespaces, resources, cells }
+    }
+
+    /// Active identities × the concrete resource/action pairs listed by the
+    /// namespace's active, in-window policies. Wildcard entries name no
+    /// single request and are skipped.
+    async fn report_candidates(&self, namespace: &str) -> Vec<ReportCase> {
+        let now = chrono::Utc::now();
+        let active = self
+            .list_policies(Some(namespace))
+            .await
+            .into_iter()
+            .filter(|p| p.status == PolicyStatus::Active && p.is_in_window(now))
+            .collect();
+        let mut pairs: Vec<(String, String)> = vec![];
+        for policy in self.render_policies(active).await {
+            for resource in policy.resources.iter().filter(|r| !r.contains('*')) {
+                for action in policy.actions.iter().filter(|a| *a != "*") {
+                    let pair = (resource.clone(), action.clone());
+                    if !pairs.contains(&pair) {
+                        pairs.push(pair);
+                    }
+                }
+            }
+        }
+        let subjects: Vec<String> = match &self.identities {
+            Some(ids) => ids
+                .read()
+                .await
+                .iter()
+                .filter(|i| i.is_live(now))
+                .map(|i| i.spiffe_id.clone().unwrap_or_else(|| i.id.to_string()))
+                .collect(),
+            None => vec![],
+        };
+        subjects
+            .iter()
+            .flat_map(|subject| {
+                pairs.iter().map(move |(resource, action)| ReportCase {
+                    subject: subject.clone(),
+                    resource: resource.clone(),
+                    action: action.clone(),
+                    context: Default::default(),
+                })
+            })
+            .collect()
+    }
+
+    /// Replace the regression tests attached to a policy
+    pub async fn set_policy_tests(&self, id: Uuid, tests: Vec<PolicyTestCase>) -> Result<Policy, PolicyError> {
+        check_policy_tests(&tests)?;
+        self.modify_policy(id, |policy| {
+            policy.tests = tests;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Run a policy's attached tests against that policy alone, whatever
+    /// its status
+    pub async fn run_policy_tests(&self, id: Uuid) -> Result<PolicyTestReport, PolicyError> {
+        let policy = self
+            .get_policy(id)
+            .await
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        Ok(self.run_tests(policy).await)
+    }
+
+    async fn run_tests(&self, policy: Policy) -> PolicyTestReport {
+        let policy_id = policy.id;
+        let tests = policy.tests.clone();
+        let policies = self.render_policies(vec![policy]).await;
+        let mut results = vec![];
+        for case in tests {
+            let mut req = case.request.clone();
+            req.identity = None;
+            let req = self.pre_evaluate(req, None).await;
+            let response = decide(&policies, &build_input(&req), Instant::now());
+            let actual = if response.allowed { PolicyEffect::Allow } else { PolicyEffect::Deny };
+            results.push(PolicyTestResult {
+                name: case.name,
+                expect: case.expect,
+                actual,
+                passed: actual == case.expect,
+                reason: response.reason,
+            });
+        }
+        let failed = results.iter().filter(|r| !r.passed).count();
+        PolicyTestReport { policy_id, total: results.len(), failed, passed: failed == 0, results }
+    }
+
+    /// With `require_passing_tests`, why each of `ids` may not be activated
+    /// because of a failing attached test
+    async fn failing_tests(&self, ids: &[Uuid]) -> HashMap<Uuid, String> {
+        let mut failing = HashMap::new();
+        if !self.config.require_passing_tests {
+            return failing;
+        }
+        for id in ids {
+            let Some(policy) = self.get_policy(*id).await.filter(|p| !p.tests.is_empty()) else {
+                continue;
+            };
+            let report = self.run_tests(policy).await;
+            if !report.passed {
+                let names: Vec<&str> =
+                    report.results.iter().filter(|r| !r.passed).map(|r| r.name.as_str()).collect();
+                failing.insert(*id, format!("{} of {} tests fail: {}", report.failed, report.total, names.join(", ")));
+            }
+        }
+        failing
+    }
+
+    async fn ensure_tests_pass(&self, id: Uuid) -> Result<(), PolicyError> {
+        match self.failing_tests(&[id]).await.remove(&id) {
+            Some(reason) => Err(PolicyError::Conflict(format!("Policy {} cannot be activated: {}", id, reason))),
+            None => Ok(()),
+        }
+    }
+
+    /// The Rego rules or Cedar statements of a policy with their enabled state
+    pub async fn policy_statements(&self, id: Uuid) -> Result<Vec<PolicyStatement>, PolicyError> {
+        let policy = self
+            .get_policy(id)
+            .await
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        statements::statements(&policy)
+    }
+
+    /// Enable or disable one statement; names that no longer exist in the
+    /// content are dropped from the disabled list
+    pub async fn set_statement_enabled(
+        &self,
+        id: Uuid,
+        name: &str,
+        enabled: bool,
+    ) -> Result<Vec<PolicyStatement>, PolicyError> {
+        let policy = self
+            .modify_policy(id, |policy| {
+                let names: Vec<String> = statements::statements(policy)?.into_iter().map(|s| s.name).collect();
+                if !names.iter().any(|n| n == name) {
+                    return Err(PolicyError::NotFound(format!("statement {} in policy {}", name, policy.name)));
+                }
+                policy.disabled_statements.retain(|n| n != name && names.contains(n));
+                if !enabled {
+                    policy.disabled_statements.push(name.to_string());
+                }
+                Ok(())
+            })
+            .await?;
+        info!(
+            "Statement {} of policy {} {}",
+            name,
+            policy.name,
+            if enabled { "enabled" } else { "disabled" }
+        );
+        statements::statements(&policy)
+    }
+
+    /// Statuses `policy` can be moved to right now, for the dashboard
+    pub fn next_statuses(&self, policy: &Policy) -> Vec<PolicyStatus> {
+        policy
+            .status
+            .transitions()
+            .iter()
+            .filter(|next| self.check_status_change(policy, next).is_ok())
+            .cloned()
+            .collect()
+    }
+
+    /// Enforce the status state machine (`PolicyStatus::transitions`), and
+    /// that Drafts and policies under review are activated only by approval
+    /// unless direct activation is allowed. Callers check and apply under the
+    /// policy store's write lock, so concurrent changes cannot interleave.
+    fn check_status_change(&self, policy: &Policy, status: &PolicyStatus) -> Result<(), PolicyError> {
+        ensure_editable(policy)?;
+        if !policy.status.can_transition_to(status) {
+            return Err(PolicyError::Conflict(format!(
+                "Policy {} cannot move from {:?} to {:?}",
+                policy.name, policy.status, status
+            )));
+        }
+        if matches!(policy.status, PolicyStatus::Draft | PolicyStatus::Review)
+            && *status == PolicyStatus::Active
+            && !self.config.allow_direct_activation
+        {
+            return Err(PolicyError::Conflict(format!(
+                "{:?} policy {} must be approved through review before activation",
+                policy.status, policy.name
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn list_groups(&self) -> Vec<PolicyGroup> {
+        self.groups.read().await.clone()
+    }
+
+    pub async fn get_group(&self, id: Uuid) -> Option<PolicyGroup> {
+        let store = self.groups.read().await;
+        store.iter().find(|g| g.id == id).cloned()
+    }
+
+    pub async fn create_group(&self, mut group: PolicyGroup) -> Result<PolicyGroup, PolicyError> {
+        if group.name.trim().is_empty() {
+            return Err(PolicyError::ValidationFailed(
+                "Policy group name cannot be empty".to_string(),
+            ));
+        }
+        group.policy_ids.dedup();
+        self.ensure_policies_exist(&group.policy_ids).await?;
+        let mut store = self.groups.write().await;
+        if store.iter().any(|g| g.name == group.name) {
+            return Err(PolicyError::Conflict(format!(
+                "Policy group already exists: {}",
+                group.name
+            )));
+        }
+        store.push(group.clone());
+        info!("Policy group created: {} ({} policies)", group.name, group.policy_ids.len());
+        Ok(group)
+    }
+
+    /// Add and remove group members; unknown policy IDs reject the whole change
+    pub async fn update_group_members(
+        &self,
+        id: Uuid,
+        add: Vec<Uuid>,
+        remove: Vec<Uuid>,
+    ) -> Result<PolicyGroup, PolicyError> {
+        self.ensure_policies_exist(&add).await?;
+        let mut store = self.groups.write().await;
+        let group = store
+            .iter_mut()
+            .find(|g| g.id == id)
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        group.policy_ids.retain(|p| !remove.contains(p));
+        for policy_id in add {
+            if !group.policy_ids.contains(&policy_id) {
+                group.policy_ids.push(policy_id);
+            }
+        }
+        group.updated_at = chrono::Utc::now();
+        // Cached group-scoped decisions may no longer hold
+        self.decision_cache.lock().unwrap().clear();
+        Ok(group.clone())
+    }
+
+    /// Move every member of a group to `status`. All transitions are checked
+    /// before any is applied, so a group is never left half-activated.
+    /// Members already in `status` are left as they are.
+    pub async fn update_group_status(
+        &self,
+        id: Uuid,
+        status: PolicyStatus,
+    ) -> Result<Vec<Policy>, PolicyError> {
+        if status == PolicyStatus::Active {
+            let members = self.get_group(id).await.map(|g| g.policy_ids).unwrap_or_default();
+            if let Some((policy_id, reason)) = self.failing_tests(&members).await.into_iter().next() {
+                return Err(PolicyError::Conflict(format!("Policy {} cannot be activated: {}", policy_id, reason)));
+            }
+        }
+        let groups = self.groups.read().await;
+        let group = groups
+            .iter()
+            .find(|g| g.id == id)
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        let mut store = self.policies.write().await;
+
+        for policy_id in &group.policy_ids {
+            let policy = store
+                .iter()
+                .find(|p| p.id == *policy_id)
+                .ok_or_else(|| PolicyError::NotFound(policy_id.to_string()))?;
+            if policy.status != status {
+                self.check_status_change(policy, &status)?;
+            }
+        }
+
+        let now = chrono::Utc::now();
+        let mut updated = vec![];
+        for policy in store
+            .iter_mut()
+            .filter(|p| group.policy_ids.contains(&p.id) && p.status != status)
+        {
+            policy.status = status.clone();
+            policy.updated_at = now;
+            self.record_revision(policy);
+            self.invalidate_decisions(&policy.namespace);
+            updated.push(policy.clone());
+        }
+        info!("Policy group {} set to {:?} ({} policies)", group.name, status, updated.len());
+        Ok(updated)
+    }
+
+    /// Activate several policies together. Each one is re-checked first:
+    /// it must exist, not already be active, pass validation and the review
+    /// gate, and not share a name with another active policy in its namespace.
+    /// An atomic batch activates nothing if any policy fails.
+    pub async fn activate_policies(&self, ids: &[Uuid], atomic: bool) -> BulkActivateResponse {
+        let mut failing = self.failing_tests(ids).await;
+        let mut store = self.policies.write().await;
+        let mut skipped = vec![];
+        let mut ready: Vec<Uuid> = vec![];
+        for id in ids {
+            if ready.contains(id) {
+                continue;
+            }
+            if let Some(reason) = failing.remove(id) {
+                skipped.push(SkippedPolicy { id: *id, reason });
+                continue;
+            }
+            match self.activation_blocker(&store, *id, &ready) {
+                Some(reason) => skipped.push(SkippedPolicy { id: *id, reason }),
+                None => ready.push(*id),
+            }
+        }
+
+        if atomic && !skipped.is_empty() {
+            warn!("Bulk activation aborted: {} of {} policies failed checks", skipped.len(), ids.len());
+            skipped.extend(ready.into_iter().map(|id| SkippedPolicy {
+                id,
+                reason: "batch aborted".to_string(),
+            }));
+            return BulkActivateResponse { activated: vec![], skipped, aborted: true };
+        }
+
+        let now = chrono::Utc::now();
+        for policy in store.iter_mut().filter(|p| ready.contains(&p.id)) {
+            policy.status = PolicyStatus::Active;
+            policy.updated_at = now;
+            self.record_revision(policy);
+            self.invalidate_decisions(&policy.namespace);
+        }
+        info!("Bulk activation: {} activated, {} skipped", ready.len(), skipped.len());
+        BulkActivateResponse { activated: ready, skipped, aborted: false }
+    }
+
+    fn activation_blocker(&self, store: &[Policy], id: Uuid, ready: &[Uuid]) -> Option<String> {
+        let Some(policy) = store.iter().find(|p| p.id == id) else {
+            return Some("policy not found".to_string());
+        };
+        if policy.status == PolicyStatus::Active {
+            return Some("already active".to_string());
+        }
+        if policy.is_simulated {
+            return Some("generated in simulation mode".to_string());
+        }
+        if let Err(e) = self.check_status_change(policy, &PolicyStatus::Active) {
+            return Some(e.to_string());
+        }
+        let validation = self.validate_policy(policy);
+        if !validation.passed {
+            return Some(format!("validation failed: {}", validation.errors.join("; ")));
+        }
+        let clash = store.iter().find(|p| {
+            p.id != id
+                && p.name == policy.name
+                && p.namespace == policy.namespace
+                && (p.status == PolicyStatus::Active || ready.contains(&p.id))
+        });
+        clash.map(|other| format!("conflicts with active policy {} ({})", other.name, other.id))
+    }
+
+    async fn ensure_policies_exist(&self, ids: &[Uuid]) -> Result<(), PolicyError> {
+        let store = self.policies.read().await;
+        match ids.iter().find(|id| !store.iter().any(|p| p.id == **id)) {
+            Some(missing) => Err(PolicyError::NotFound(missing.to_string())),
+            None => Ok(()),
+        }
+    }
+
+    /// Draft → Review, assigning the given reviewers
+    pub async fn submit_for_review(
+        &self,
+        id: Uuid,
+        reviewers: Vec<String>,
+    ) -> Result<Policy, PolicyError> {
+        if reviewers.is_empty() {
+            return Err(PolicyError::ValidationFailed(
+                "At least one reviewer must be assigned".to_string(),
+            ));
+        }
+        self.modify_policy(id, |policy| {
+            expect_status(policy, PolicyStatus::Draft)?;
+            policy.status = PolicyStatus::Review;
+            policy.reviewers = reviewers;
+            policy.approved_by = None;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Review → Active, recording the approving reviewer
+    pub async fn approve_policy(
+        &self,
+        id: Uuid,
+        reviewer: &str,
+        comment: Option<String>,
+    ) -> Result<Policy, PolicyError> {
+        self.ensure_tests_pass(id).await?;
+        self.modify_policy(id, |policy| {
+            expect_status(policy, PolicyStatus::Review)?;
+            expect_reviewer(policy, reviewer)?;
+            policy.status = PolicyStatus::Active;
+            policy.approved_by = Some(reviewer.to_string());
+            if let Some(comment) = comment {
+                policy.review_comments.push(ReviewComment {
+                    reviewer: reviewer.to_string(),
+                    comment,
+                    created_at: chrono::Utc::now(),
+                });
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    /// Review → Draft, recording the rejection comment
+    pub async fn reject_policy(
+        &self,
+        id: Uuid,
+        reviewer: &str,
+        comment: &str,
+    ) -> Result<Policy, PolicyError> {
+        self.modify_policy(id, |policy| {
+            expect_status(policy, PolicyStatus::Review)?;
+            expect_reviewer(policy, reviewer)?;
+            policy.status = PolicyStatus::Draft;
+            policy.review_comments.push(ReviewComment {
+                reviewer: reviewer.to_string(),
+                comment: comment.to_string(),
+                created_at: chrono::Utc::now(),
+            });
+            Ok(())
+        })
+        .await
+    }
+
+    /// Apply a mutation to a stored policy, bumping `updated_at` and
+    /// invalidating cached decisions for its namespace on success
+    async fn modify_policy<F>(&self, id: Uuid, mutate: F) -> Result<Policy, PolicyError>
+    where
+        F: FnOnce(&mut Policy) -> Result<(), PolicyError>,
+    {
+        let mut store = self.policies.write().await;
+        let policy = store
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        ensure_editable(policy)?;
+        mutate(policy)?;
+        policy.updated_at = chrono::Utc::now();
+        self.record_revision(policy);
+        self.invalidate_decisions(&policy.namespace);
+        Ok(policy.clone())
+    }
+
+    fn record_revision(&self, policy: &Policy) {
+        let mut history = self.history.lock().unwrap();
+        let revision = history.iter().filter(|r| r.policy.id == policy.id).count() as u32 + 1;
+        history.push(PolicyRevision {
+            revision,
+            recorded_at: chrono::Utc::now(),
+            policy: policy.clone(),
+        });
+    }
+
+    /// Recorded revisions of one policy, oldest first
+    pub fn policy_history(&self, id: Uuid) -> Vec<PolicyRevision> {
+        let history = self.history.lock().unwrap();
+        history.iter().filter(|r| r.policy.id == id).cloned().collect()
+    }
+
+    /// Re-run a decision against the policies that were Active at `as_of`,
+    /// selected like a live decision (namespace default last, group scoping,
+    /// combining algorithm) under today's namespace and group settings.
+    /// Bypasses the cache, the external OPA backend and enforcement modes.
+    pub async fn evaluate_as_of(
+        &self,
+        req: &PolicyDecisionRequest,
+        as_of: chrono::DateTime<chrono::Utc>,
+    ) -> (PolicyDecisionResponse, Vec<PolicyRevisionRef>) {
+        let start = Instant::now();
+        let (req, claims) = match self.verify_subject_token(req).await {
+            Ok(verified) => verified,
+            Err(reason) => return (self.token_rejected(req, &reason, start), vec![]),
+        };
+        let mut req = req;
+        if let Err(reason) = self.check_identity_snapshot(&req) {
+            warn!(subject = %req.subject, "Ignoring identity snapshot: {}", reason);
+            req.identity_snapshot = None;
+        }
+        let filtered = self.filter_context(&req, false).await.ok().flatten();
+        let req = &self.pre_evaluate(filtered.unwrap_or(req), claims.as_ref()).await;
+
+        // Latest revision per policy at or before `as_of`, in first-seen order
+        let revisions: Vec<PolicyRevision> = {
+            let history = self.history.lock().unwrap();
+            let mut latest: Vec<&PolicyRevision> = vec![];
+            for rev in history.iter().filter(|r| r.recorded_at <= as_of) {
+                match latest.iter_mut().find(|r| r.policy.id == rev.policy.id) {
+                    Some(slot) => *slot = rev,
+                    None => latest.push(rev),
+                }
+            }
+            latest.into_iter().cloned().collect()
+        };
+        let store: Vec<Policy> = revisions.iter().map(|r| r.policy.clone()).collect();
+        let (policies, _, algorithm) = self.applicable_policies(req, Some(&store), as_of).await;
+        let used = policies
+            .iter()
+            .filter_map(|p| revisions.iter().find(|r| r.policy.id == p.id))
+            .map(|r| PolicyRevisionRef {
+                policy_id: r.policy.id,
+                name: r.policy.name.clone(),
+                revision: r.revision,
+                version: r.policy.version,
+                recorded_at: r.recorded_at,
+            })
+            .collect();
+
+        let response = if policies.is_empty() {
+            decision(
+                false,
+                format!("No policies were active at {} — deny by default", as_of.to_rfc3339()),
+                None,
+                start,
+            )
+        } else {
+            combine(&policies, &build_input(req), algorithm, start)
+        };
+        (response, used)
+    }
+
+    /// Decide `req` in `base_namespace` both as the live policy set stands
+    /// and with `overlay` applied: each overlay policy replaces the stored one
+    /// with its id, or is added, and takes part unless Disabled or Archived.
+    /// Enriched and combined like a live decision, but nothing is stored,
+    /// cached, counted, rate limited or exported. Bypasses the external OPA
+    /// backend and enforcement modes. Returns (proposed, current).
+    pub async fn evaluate_proposed(
+        &self,
+        base_namespace: &str,
+        overlay: Vec<Policy>,
+        req: &PolicyDecisionRequest,
+    ) -> Result<(PolicyDecisionResponse, PolicyDecisionResponse), PolicyError> {
+        let start = Instant::now();
+        let mut req = req.clone();
+        if req.namespace.is_empty() {
+            req.namespace = base_namespace.to_string();
+        } else if req.namespace != base_namespace {
+            return Err(PolicyError::ValidationFailed(format!(
+                "Request namespace '{}' does not match base namespace '{}'",
+                req.namespace, base_namespace
+            )));
+        }
+        for policy in &overlay {
+            let validation = self.validate_policy(policy);
+            if !validation.passed {
+                return Err(PolicyError::ValidationFailed(format!(
+                    "Overlay policy '{}' failed validation: {}",
+                    policy.name,
+                    validation.errors.join("; ")
+                )));
+            }
+        }
+
+        let (req, claims) = match self.verify_subject_token(&req).await {
+            Ok(verified) => verified,
+            Err(reason) => {
+                let rejected = decision(false, format!("Invalid subject token — fail closed: {}", reason), None, start);
+                return Ok((rejected.clone(), rejected));
+            }
+        };
+        self.check_identity_snapshot(&req).map_err(PolicyError::ValidationFailed)?;
+        let filtered = self.filter_context(&req, true).await?;
+        let req = &self.pre_evaluate(filtered.unwrap_or(req), claims.as_ref()).await;
+
+        let mut store = self.policies.read().await.clone();
+        for mut policy in overlay {
+            if !matches!(policy.status, PolicyStatus::Disabled | PolicyStatus::Archived) {
+                policy.status = PolicyStatus::Active;
+            }
+            match store.iter_mut().find(|p| p.id == policy.id) {
+                Some(slot) => *slot = policy,
+                None => store.push(policy),
+            }
+        }
+
+        let input = build_input(req);
+        let now = chrono::Utc::now();
+        let proposed = self.applicable_policies(req, Some(&store), now).await;
+        let current = self.applicable_policies(req, None, now).await;
+        let [proposed, current] = [proposed, current].map(|(applicable, _, algorithm)| {
+            if applicable.is_empty() {
+                decision(
+                    false,
+                    "No applicable policies found — deny by default".to_string(),
+                    None,
+                    start,
+                )
+            } else {
+                combine(&applicable, &input, algorithm, start)
+            }
+        });
+        Ok((proposed, current))
+    }
+
+    /// Evaluate a policy decision — the core enforcement engine
+    /// In production: calls OPA REST API or uses embedded regorus
+    pub async fn evaluate(
+        &self,
+        req: &PolicyDecisionRequest,
+    ) -> Result<PolicyDecisionResponse, PolicyError> {
+        let start = Instant::now();
+        let (req, claims) = match self.verify_subject_token(req).await {
+            Ok(verified) => verified,
+            Err(reason) => return Ok(self.token_rejected(req, &reason, start)),
+        };
+        self.check_identity_snapshot(&req).map_err(PolicyError::ValidationFailed)?;
+        let filtered = self.filter_context(&req, true).await?;
+        let req = &self.pre_evaluate(filtered.unwrap_or(req), claims.as_ref()).await;
+        let cache_key = self.decision_cache_key(req);
+        self.metrics.incr(metrics::EVALUATIONS_TOTAL);
+        self.mark_subject_seen(&req.subject).await;
+
+        let cached = cache_key.and_then(|key| self.cached_decision(key));
+        let response = match cached {
+            Some(mut hit) => {
+                self.metrics.incr(metrics::DECISION_CACHE_HITS_TOTAL);
+                if let Some(id) = hit.policy_id {
+                    self.record_policy_stats(&[id], &hit);
+                }
+                hit.decision_id = Uuid::new_v4();
+                hit.cached = true;
+                hit.evaluation_time_ms = start.elapsed().as_millis() as u64;
+                hit
+            }
+            None => {
+                let evaluation = self.evaluate_uncached(req);
+                let outcome = match self.config.eval_timeout_ms {
+                    0 => Ok(evaluation.await),
+                    ms => tokio::time::timeout(Duration::from_millis(ms), evaluation).await,
+                };
+                let Ok(response) = outcome else {
+                    // Fail closed: a timeout is never subject to monitor/dry-run relaxation
+                    return Ok(self.timed_out(req, start));
+                };
+                let (response, default_namespace) = response?;
+                if let Some(key) = cache_key.filter(|_| !response.throttled) {
+                    self.store_decision(key, &req.namespace, default_namespace, &response);
+                }
+                response
+            }
+        };
+
+        // Enforcement mode is applied after the cache, which holds raw decisions
+        let mut response = response;
+        response.enforcement_mode = self
+            .get_namespace(&req.namespace)
+            .await
+            .map(|n| n.enforcement_mode)
+            .unwrap_or_default();
+        if response.enforcement_mode != EnforcementMode::Enforce && !response.allowed && !response.throttled {
+            response.allowed = true;
+            response.would_deny = true;
+            response.reason = format!("Not enforced ({:?}): {}", response.enforcement_mode, response.reason);
+        }
+        for hook in &self.hooks {
+            hook.post_evaluate(req, &mut response).await;
+        }
+
+        log_decision(req, &response);
+        self.log_sampled(req, &response);
+        self.export_decision(req, &response);
+        self.record_latency(req, &response, start.elapsed());
+        Ok(response)
+    }
+
+    /// Pass the decision to every sink on a background task; a sink's
+    /// failure is logged and counted, never returned
+    fn export_decision(&self, req: &PolicyDecisionRequest, response: &PolicyDecisionResponse) {
+        if self.sinks.is_empty() {
+            return;
+        }
+        let sinks = self.sinks.clone();
+        let counters = Arc::clone(&self.metrics);
+        let (req, response) = (req.clone(), response.clone());
+        tokio::spawn(async move {
+            for sink in sinks {
+                if let Err(e) = sink.record(&req, &response).await {
+                    counters.incr(metrics::DECISION_SINK_ERRORS_TOTAL);
+                    warn!(sink = sink.name(), decision_id = %response.decision_id, "Decision sink failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Add an evaluation to the latency window and warn if it was slow
+    fn record_latency(&self, req: &PolicyDecisionRequest, response: &PolicyDecisionResponse, elapsed: Duration) {
+        {
+            let mut latencies = self.latencies.lock().unwrap();
+            if latencies.len() == LATENCY_WINDOW {
+                latencies.pop_front();
+            }
+            latencies.push_back(elapsed.as_micros() as u64);
+        }
+        let threshold = self.config.slow_eval_ms;
+        if threshold == 0 || elapsed < Duration::from_millis(threshold) {
+            return;
+        }
+        self.metrics.incr(metrics::SLOW_EVALUATIONS_TOTAL);
+        warn!(
+            actor = %req.subject,
+            resource = %req.resource,
+            action = %req.action,
+            namespace = %req.namespace,
+            policy = response.policy_name.as_deref().unwrap_or("-"),
+            decision_id = %response.decision_id,
+            cached = response.cached,
+            latency_ms = elapsed.as_millis() as u64,
+            threshold_ms = threshold,
+            context = %redact_context(req.context.to_value(), &self.config.eval_log_redact),
+            "Slow policy evaluation"
+        );
+    }
+
+    /// Latency percentiles over the most recent evaluations (cache hits and
+    /// timeouts included)
+    pub fn evaluation_stats(&self) -> EvaluationStats {
+        let mut samples: Vec<u64> = self.latencies.lock().unwrap().iter().copied().collect();
+        samples.sort_unstable();
+        // Nearest-rank percentile, reported in milliseconds
+        let percentile = |p: usize| match samples.len() {
+            0 => 0.0,
+            n => samples[(n * p).div_ceil(100).max(1) - 1] as f64 / 1000.0,
+        };
+        EvaluationStats {
+            evaluations: self.metrics.get(metrics::EVALUATIONS_TOTAL),
+            cache_hits: self.metrics.get(metrics::DECISION_CACHE_HITS_TOTAL),
+            timeouts: self.metrics.get(metrics::EVAL_TIMEOUTS_TOTAL),
+            samples: samples.len(),
+            p50_ms: percentile(50),
+            p95_ms: percentile(95),
+            p99_ms: percentile(99),
+            max_ms: samples.last().map_or(0.0, |max| *max as f64 / 1000.0),
+            slow_threshold_ms: self.config.slow_eval_ms,
+            slow_evaluations: self.metrics.get(metrics::SLOW_EVALUATIONS_TOTAL),
+        }
+    }
+
+    /// Debug-log the full request and response for a sampled fraction of
+    /// evaluations (and every denial, if configured), with context redaction
+    fn log_sampled(&self, req: &PolicyDecisionRequest, response: &PolicyDecisionResponse) {
+        let denied = !response.allowed || response.would_deny;
+        let sampled = self.config.eval_log_sample > 0.0
+            && rand::random::<f64>() < self.config.eval_log_sample;
+        if !(sampled || (denied && self.config.eval_log_denials)) {
+            return;
+        }
+        let mut request = serde_json::to_value(req).unwrap_or_default();
+        request["context"] = redact_context(req.context.to_value(), &self.config.eval_log_redact);
+        debug!(
+            decision_id = %response.decision_id,
+            sampled,
+            request = %request,
+            response = %serde_json::to_value(response).unwrap_or_default(),
+            "Sampled policy evaluation"
+        );
+    }
+
+    fn timed_out(&self, req: &PolicyDecisionRequest, start: Instant) -> PolicyDecisionResponse {
+        self.metrics.incr(metrics::EVAL_TIMEOUTS_TOTAL);
+        let err = PolicyError::OpaError(format!(
+            "evaluation exceeded {}ms",
+            self.config.eval_timeout_ms
+        ));
+        error!(
+            actor = %req.subject,
+            resource = %req.resource,
+            namespace = %req.namespace,
+            error = %err,
+            "Policy evaluation timed out"
+        );
+        let response = decision(
+            false,
+            "evaluation timed out — fail closed".to_string(),
+            None,
+            start,
+        );
+        log_decision(req, &response);
+        self.log_sampled(req, &response);
+        self.export_decision(req, &response);
+        self.record_latency(req, &response, start.elapsed());
+        response
+    }
+
+    /// Also returns the namespace of the default policy consulted, so cached
+    /// decisions can be dropped when it changes
+    async fn evaluate_uncached(
+        &self,
+        req: &PolicyDecisionRequest,
+    ) -> Result<(PolicyDecisionResponse, Option<String>), PolicyError> {
+        debug!(
+            "Evaluating: subject={} resource={} action={}",
+            req.subject, req.resource, req.action
+        );
+        let (response, consulted, default_namespace) = self.decide_live(req, true).await?;
+        self.record_policy_stats(&consulted, &response);
+        Ok((response, default_namespace))
+    }
+
+    /// Decide an (already enriched) request against the live policy set,
+    /// without caching or stats. Also returns the policies consulted and the
+    /// namespace of the default policy, if it lives elsewhere. With
+    /// `rate_limited`, the evaluation counts against policy rate limits.
+    async fn decide_live(
+        &self,
+        req: &PolicyDecisionRequest,
+        rate_limited: bool,
+    ) -> Result<(PolicyDecisionResponse, Vec<Uuid>, Option<String>), PolicyError> {
+        let start = Instant::now();
+        let input = build_input(req);
+
+        if let Some(opa) = &self.opa {
+            let allowed = opa.evaluate(&input).await?;
+            let reason = if allowed {
+                format!("Allowed by OPA: {}", opa.rule())
+            } else {
+                format!("Denied by OPA: {}", opa.rule())
+            };
+            return Ok((decision(allowed, reason, None, start), vec![], None));
+        }
+
+        let (applicable, default_namespace, algorithm) =
+            self.applicable_policies(req, None, chrono::Utc::now()).await;
+
+        if applicable.is_empty() {
+            warn!("No active policies found for namespace: {}", req.namespace);
+            return Ok((
+                decision(
+                    false,
+                    "No applicable policies found — deny by default".to_string(),
+                    None,
+                    start,
+                ),
+                vec![],
+                None,
+            ));
+        }
+
+        if rate_limited {
+            if let Some(policy) = self.throttled_by(&applicable) {
+                warn!(policy = %policy.name, subject = %req.subject, "Policy rate limit exceeded");
+                let reason = format!(
+                    "Policy '{}' rate limit of {}/s exceeded — fail closed",
+                    policy.name,
+                    policy.rate_limit.unwrap_or_default()
+                );
+                let mut response = decision(false, reason, Some(policy), start);
+                response.throttled = true;
+                return Ok((response, vec![], None));
+            }
+        }
+
+        let response = combine(&applicable, &input, algorithm, start);
+        let consulted = applicable.iter().map(|p| p.id).collect();
+        Ok((response, consulted, default_namespace))
+    }
+
+    /// Policies Active and in their window at `at` that apply to `req`,
+    /// rendered, with the namespace default last: from the live store, or
+    /// from `store` when given. Also returns the default's namespace if it lives elsewhere, and
+    /// the combining algorithm to use.
+    async fn applicable_policies(
+        &self,
+        req: &PolicyDecisionRequest,
+        store: Option<&[Policy]>,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> (Vec<Policy>, Option<String>, CombiningAlgorithm) {
+        // Scoping to a group narrows the set; an unknown group matches nothing
+        let members = match req.group {
+            Some(id) => Some(self.get_group(id).await.map(|g| g.policy_ids).unwrap_or_default()),
+            None => None,
+        };
+
+        let namespace = self.get_namespace(&req.namespace).await;
+        let default_id = namespace.as_ref().and_then(|n| n.default_policy_id);
+        let algorithm = namespace
+            .and_then(|n| n.combining_algorithm)
+            .unwrap_or(self.config.combining_algorithm);
+
+        // Find applicable active policies within their active window. The
+        // namespace default goes last, so under deny-overrides it only
+        // decides an allow when no other policy does, but its deny still
+        // overrides.
+        let select = |store: &[Policy]| -> (Vec<Policy>, Option<Policy>) {
+            let applicable = store
+                .iter()
+                .filter(|p| {
+                    p.status == PolicyStatus::Active
+                        && p.is_in_window(at)
+                        && (p.namespace == req.namespace || p.namespace == "system")
+                        && members.as_ref().is_none_or(|m| m.contains(&p.id))
+                        && default_id != Some(p.id)
+                })
+                .cloned()
+                .collect();
+            let default_policy = default_id
+                .and_then(|id| store.iter().find(|p| p.id == id))
+                .filter(|p| p.status == PolicyStatus::Active && p.is_in_window(at))
+                .cloned();
+            (applicable, default_policy)
+        };
+        let (mut applicable, default_policy) = match store {
+            Some(store) => select(store),
+            None => select(&self.policies.read().await),
+        };
+        let default_namespace = default_policy
+            .as_ref()
+            .map(|p| p.namespace.clone())
+            .filter(|ns| *ns != req.namespace);
+        applicable.extend(default_policy);
+        let applicable = self.render_policies(applicable).await;
+        (applicable, default_namespace, algorithm)
+    }
+
+    /// The first of `policies` whose `rate_limit` is used up for the current
+    /// second. Otherwise the evaluation is counted against every limited policy.
+    fn throttled_by<'a>(&self, policies: &'a [Policy]) -> Option<&'a Policy> {
+        let now = Instant::now();
+        let mut windows = self.rate_windows.lock().unwrap();
+        let exceeded = policies.iter().find(|p| {
+            let Some(limit) = p.rate_limit else { return false };
+            let (started, count) = windows.entry(p.id).or_insert((now, 0));
+            if now.duration_since(*started) >= Duration::from_secs(1) {
+                *started = now;
+                *count = 0;
+            }
+            *count >= limit
+        });
+        match exceeded {
+            Some(policy) => {
+                self.metrics.incr(&format!("{}{{policy_id=\"{}\"}}", metrics::POLICY_THROTTLED_TOTAL, policy.id));
+            }
+            None => {
+                for policy in policies.iter().filter(|p| p.rate_limit.is_some()) {
+                    if let Some((_, count)) = windows.get_mut(&policy.id) {
+                        *count += 1;
+                    }
+                }
+            }
+        }
+        exceeded
+    }
+
+    fn record_policy_stats(&self, consulted: &[Uuid], response: &PolicyDecisionResponse) {
+        let now = chrono::Utc::now();
+        let mut stats = self.policy_stats.lock().unwrap();
+        for id in consulted {
+            let entry = stats.entry(*id).or_insert_with(|| PolicyStats::new(*id));
+            entry.evaluations += 1;
+            if response.policy_id != Some(*id) {
+                continue;
+            }
+            let rule = if response.allowed { "allow" } else { "deny" };
+            if response.allowed {
+                entry.allows += 1;
+            } else {
+                entry.denies += 1;
+            }
+            *entry.rule_hits.entry(rule.to_string()).or_default() += 1;
+            entry.last_hit = Some(now);
+        }
+    }
+
+    /// Usage counters for a policy; all zero if it has never been consulted
+    pub async fn policy_stats(&self, id: Uuid) -> Result<PolicyStats, PolicyError> {
+        self.get_policy(id)
+            .await
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        let stats = self.policy_stats.lock().unwrap();
+        Ok(stats.get(&id).cloned().unwrap_or_else(|| PolicyStats::new(id)))
+    }
+
+    pub async fn reset_policy_stats(&self, id: Uuid) -> Result<(), PolicyError> {
+        self.get_policy(id)
+            .await
+            .ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        self.policy_stats.lock().unwrap().remove(&id);
+        Ok(())
+    }
+
+    /// Log a usage line for every active policy so hit counts reach log-based
+    /// analytics; active policies with no hits are flagged as removal candidates
+    pub async fn flush_policy_stats(&self) {
+        let active: Vec<(Uuid, String)> = self
+            .policies
+            .read()
+            .await
+            .iter()
+            .filter(|p| p.status == PolicyStatus::Active)
+            .map(|p| (p.id, p.name.clone()))
+            .collect();
+        let stats = self.policy_stats.lock().unwrap();
+        for (id, name) in active {
+            match stats.get(&id).filter(|s| s.last_hit.is_some()) {
+                Some(s) => info!(
+                    policy = %name,
+                    policy_id = %id,
+                    evaluations = s.evaluations,
+                    allows = s.allows,
+                    denies = s.denies,
+                    "Policy usage"
+                ),
+                None => debug!(policy = %name, policy_id = %id, "Active policy has no hits — removal candidate"),
+            }
+        }
+    }
+
+    /// Evaluate a request against a single unsaved policy. Nothing is stored,
+    /// cached or audited; a policy that fails validation always denies.
+    pub async fn evaluate_sandbox(
+        &self,
+        policy: Policy,
+        req: &PolicyDecisionRequest,
+    ) -> (PolicyValidationResult, PolicyDecisionResponse) {
+        let start = Instant::now();
+        let validation = self.validate_policy(&policy);
+        if !validation.passed {
+            let reason = format!("Policy failed validation: {}", validation.errors.join("; "));
+            return (validation, decision(false, reason, None, start));
+        }
+
+        let (req, claims) = match self.verify_subject_token(req).await {
+            Ok(verified) => verified,
+            Err(reason) => return (validation, self.token_rejected(req, &reason, start)),
+        };
+        let req = &self.pre_evaluate(req, claims.as_ref()).await;
+        let input = build_input(req);
+        let policies = self.render_policies(vec![policy]).await;
+        (validation, decide(&policies, &input, start))
+    }
+
+    /// A caller-supplied `identity_snapshot` must be allowed and describe the
+    /// (verified) decision subject
+    fn check_identity_snapshot(&self, req: &PolicyDecisionRequest) -> Result<(), String> {
+        let Some(snapshot) = &req.identity_snapshot else {
+            return Ok(());
+        };
+        if !self.config.allow_identity_snapshots {
+            return Err("Identity snapshots are not accepted".to_string());
+        }
+        let snapshot_id = snapshot.spiffe_id.as_deref().map(normalize_spiffe_id);
+        if snapshot_id.as_deref() != Some(req.subject.as_str()) && snapshot.id.to_string() != req.subject {
+            return Err(format!("identity_snapshot does not describe subject {}", req.subject));
+        }
+        Ok(())
+    }
+
+    /// Bump `last_seen` for the identity behind a decision subject, throttled
+    /// by `Identity::needs_last_seen_update` so evaluations stay read-mostly
+    async fn mark_subject_seen(&self, subject: &str) {
+        let Some(store) = &self.identities else {
+            return;
+        };
+        let is_subject = |i: &Identity| {
+            i.spiffe_id.as_deref() == Some(subject) || i.id.to_string() == subject
+        };
+        let now = chrono::Utc::now();
+        let stale = store
+            .read()
+            .await
+            .iter()
+            .any(|i| is_subject(i) && i.needs_last_seen_update(now));
+        if stale {
+            if let Some(identity) = store.write().await.iter_mut().find(|i| is_subject(i)) {
+                identity.last_seen = now;
+            }
+        }
+    }
+
+    /// Verify `subject_token`, if any, and take the subject and namespace from
+    /// its claims. With an identity store, the token's subject must also be a
+    /// live identity. `Err` carries the reason the token was rejected.
+    pub async fn verify_subject_token(
+        &self,
+        req: &PolicyDecisionRequest,
+    ) -> Result<(PolicyDecisionRequest, Option<ZedIdClaims>), String> {
+        let mut req = req.clone();
+        req.subject = normalize_spiffe_id(&req.subject);
+        let Some(token) = req.subject_token.take() else {
+            return Ok((req, None));
+        };
+        let jwt = self
+            .jwt
+            .as_ref()
+            .ok_or("subject tokens are not accepted by this engine")?;
+        let claims = jwt.validate_token(&token).map_err(|e| e.to_string())?;
+        if let Some(store) = &self.identities {
+            let now = chrono::Utc::now();
+            let live = store
+                .read()
+                .await
+                .iter()
+                .any(|i| i.id.to_string() == claims.sub && i.is_live(now));
+            if !live {
+                return Err("token subject is inactive or its lease has expired".to_string());
+            }
+        }
+        req.subject = normalize_spiffe_id(claims.spiffe_id.as_deref().unwrap_or(&claims.sub));
+        req.namespace = claims.namespace.clone();
+        Ok((req, Some(claims)))
+    }
+
+    /// Fail-closed decision for a request whose `subject_token` did not verify
+    fn token_rejected(&self, req: &PolicyDecisionRequest, reason: &str, start: Instant) -> PolicyDecisionResponse {
+        warn!(resource = %req.resource, action = %req.action, "Rejected subject token: {}", reason);
+        let response = decision(false, format!("Invalid subject token — fail closed: {}", reason), None, start);
+        log_decision(req, &response);
+        self.export_decision(req, &response);
+        response
+    }
+
+    /// Set the token's verified `trust_level` and `roles` in the (already
+    /// filtered) context, then run every hook's `pre_evaluate`
+    async fn pre_evaluate(
+        &self,
+        mut req: PolicyDecisionRequest,
+        claims: Option<&ZedIdClaims>,
+    ) -> PolicyDecisionRequest {
+        if let Some(claims) = claims {
+            let mut context = req.context.to_value();
+            if let Some(fields) = context.as_object_mut() {
+                fields.insert("trust_level".to_string(), claims.trust_level.into());
+                if let Some(roles) = token_roles(claims) {
+                    fields.insert("roles".to_string(), roles.into());
+                }
+                req.context = context.into();
+            }
+        }
+        for hook in &self.hooks {
+            hook.pre_evaluate(&mut req).await;
+        }
+        req
+    }
+
+    /// Hash of the request tuple, or `None` when caching is disabled
+    fn decision_cache_key(&self, req: &PolicyDecisionRequest) -> Option<u64> {
+        // A snapshot decision depends on attributes outside the key
+        if self.config.decision_cache_ttl_ms == 0 || req.identity_snapshot.is_some() {
+            return None;
+        }
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        req.subject.hash(&mut hasher);
+        req.resource.hash(&mut hasher);
+        req.action.hash(&mut hasher);
+        req.namespace.hash(&mut hasher);
+        req.group.hash(&mut hasher);
+        // Enrichment adds identity attributes (labels, trust) policies read
+        req.skip_enrichment.hash(&mut hasher);
+        let context = req.context.to_value();
+        if self.config.cache_include_context {
+            context.to_string().hash(&mut hasher);
+        } else {
+            // A verified subject token's trust level and roles always count
+            for field in ["trust_level", "roles"] {
+                context.get(field).map(|v| v.to_string()).hash(&mut hasher);
+            }
+        }
+        Some(hasher.finish())
+    }
+
+    fn cached_decision(&self, key: u64) -> Option<PolicyDecisionResponse> {
+        let mut cache = self.decision_cache.lock().unwrap();
+        match cache.get(&key) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.response.clone()),
+            Some(_) => {
+                cache.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn store_decision(
+        &self,
+        key: u64,
+        namespace: &str,
+        default_namespace: Option<String>,
+        response: &PolicyDecisionResponse,
+    ) {
+        let ttl = Duration::from_millis(self.config.decision_cache_ttl_ms);
+        let mut cache = self.decision_cache.lock().unwrap();
+        let now = Instant::now();
+        cache.retain(|_, entry| entry.expires_at > now);
+        cache.insert(
+            key,
+            CachedDecision {
+                namespace: namespace.to_string(),
+                default_namespace,
+                response: response.clone(),
+                expires_at: now + ttl,
+            },
+        );
+    }
+
+    /// Drop every cached decision, e.g. after identities changed underneath them
+    pub fn clear_decision_cache(&self) {
+        self.invalidate_decisions("system");
+    }
+
+    /// Drop cached decisions affected by a policy change in `namespace`.
+    /// `system` policies apply everywhere, so they flush the whole cache.
+    fn invalidate_decisions(&self, namespace: &str) {
+        let mut cache = self.decision_cache.lock().unwrap();
+        if namespace == "system" {
+            cache.clear();
+        } else {
+            cache.retain(|_, entry| {
+                entry.namespace != namespace && entry.default_namespace.as_deref() != Some(namespace)
+            });
+        }
+    }
+
+    /// Validate a policy document
+    /// Warnings for `spiffe://` subjects (and exclusions) that can never match:
+    /// unparseable IDs, a foreign trust domain, or — for exact IDs — no such
+    /// identity in the store. Patterns and templates are only partly checked;
+    /// the store check is skipped while it is being written to.
+    fn lint_spiffe_subjects(&self, policy: &Policy) -> Vec<String> {
+        let identities = self.identities.as_ref().and_then(|ids| ids.try_read().ok());
+        let mut warnings = vec![];
+        for subject in policy.subjects.iter().chain(&policy.excluded_subjects) {
+            if !subject.starts_with("spiffe://") || template::is_template(subject) {
+                continue;
+            }
+            let id = match SpiffeId::parse(subject) {
+                Ok(id) => id,
+                Err(e) => {
+                    warnings.push(format!("Subject {} is not a valid SPIFFE ID: {}", subject, e));
+                    continue;
+                }
+            };
+            // An engine without a configured trust domain (e.g. the CI validator) skips this check
+            if !self.config.trust_domain.is_empty() && !id.trust_domain.eq_ignore_ascii_case(&self.config.trust_domain) {
+                warnings.push(format!(
+                    "Subject {} is in trust domain {}, not {}",
+                    subject, id.trust_domain, self.config.trust_domain
+                ));
+            } else if !subject.contains('*')
+                && identities
+                    .as_ref()
+                    .is_some_and(|ids| !ids.iter().any(|i| i.spiffe_id == Some(id.to_normalized_uri())))
+            {
+                warnings.push(format!("Subject {} does not match any known identity", subject));
+            }
+        }
+        warnings
+    }
+
+    pub fn validate_policy(&self, policy: &Policy) -> PolicyValidationResult {
+        let mut errors = vec![];
+        let mut warnings = vec![];
+
+        if policy.content.is_empty() {
+            errors.push("Policy content cannot be empty".to_string());
+        }
+
+        if policy.subjects.is_empty() {
+            warnings.push("No subjects specified — policy may be overly broad".to_string());
+        }
+
+        if policy.resources.is_empty() {
+            warnings.push("No resources specified — policy may be overly broad".to_string());
+        }
+
+        match policy.kind {
+            PolicyKind::Rego => {
+                if !policy.content.contains("package") {
+                    errors.push("Rego policy must have a package declaration".to_string());
+                }
+                if !policy.content.contains("allow") && !policy.content.contains("deny") {
+                    warnings.push(
+                        "Rego policy should define 'allow' or 'deny' rules".to_string(),
+                    );
+                }
+            }
+            PolicyKind::Cedar
+                if !policy.content.contains("permit") && !policy.content.contains("forbid") =>
+            {
+                errors.push("Cedar policy must have permit or forbid rules".to_string());
+            }
+            PolicyKind::IstioAuthz => match serde_yaml::from_str::<serde_yaml::Value>(&policy.content) {
+                Err(e) => errors.push(format!("Policy is not valid YAML: {}", e)),
+                Ok(doc) if doc["kind"].as_str() != Some("AuthorizationPolicy") => {
+                    errors.push("Istio policy must have kind: AuthorizationPolicy".to_string());
+                }
+                Ok(_) => {}
+            },
+            PolicyKind::RbacYaml if !policy.content.is_empty() => errors.extend(rbac::validate(&policy.content)),
+            _ => {}
+        }
+
+        warnings.extend(self.lint_spiffe_subjects(policy));
+
+        let coverage_score = if errors.is_empty() {
+            if warnings.is_empty() { 1.0 } else { 0.8 }
+        } else {
+            0.0
+        };
+
+        PolicyValidationResult {
+            passed: errors.is_empty(),
+            errors,
+            warnings,
+            coverage_score,
+        }
+    }
+}
+
+impl Default for PolicyEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Git-synced policies change only through the repository
+fn ensure_editable(policy: &Policy) -> Result<(), PolicyError> {
+    match policy.source {
+        PolicySource::Git => Err(PolicyError::Conflict(format!(
+            "Policy {} is managed in Git ({}); change it in the repository",
+            policy.name,
+            policy.source_path.as_deref().unwrap_or("-")
+        ))),
+        PolicySource::Local => Ok(()),
+    }
+}
+
+/// Whether a re-synced file leaves the stored policy as it is
+fn same_git_policy(stored: &Policy, incoming: &Policy) -> bool {
+    stored.status == incoming.status
+        && stored.name == incoming.name
+        && stored.namespace == incoming.namespace
+        && stored.description == incoming.description
+        && stored.content == incoming.content
+        && stored.subjects == incoming.subjects
+        && stored.resources == incoming.resources
+        && stored.actions == incoming.actions
+}
+
+/// Build an (enforced, uncached) decision response
+fn decision(
+    allowed: bool,
+    reason: String,
+    policy: Option<&Policy>,
+    start: Instant,
+) -> PolicyDecisionResponse {
+    PolicyDecisionResponse {
+        allowed,
+        reason,
+        policy_id: policy.map(|p| p.id),
+        policy_name: policy.map(|p| p.name.clone()),
+        evaluation_time_ms: start.elapsed().as_millis() as u64,
+        decision_id: Uuid::new_v4(),
+        cached: false,
+        enforcement_mode: EnforcementMode::Enforce,
+        would_deny: false,
+        throttled: false,
+        human_explanation: None,
+        warnings: vec![],
+    }
+}
+
+/// Evaluate `input` against policies considered on their own (sandbox,
+/// impact analysis, policy tests), where the default deny-overrides applies.
+fn decide(policies: &[Policy], input: &serde_json::Value, start: Instant) -> PolicyDecisionResponse {
+    combine(policies, input, CombiningAlgorithm::DenyOverrides, start)
+}
+
+/// Evaluate `input` against every policy and combine the results with
+/// `algorithm`. Under the default deny-overrides an explicit deny (e.g. an
+/// Istio DENY policy) overrides any allow; otherwise the first policy that
+/// allows decides, and if none does the request is implicitly denied.
+/// In production: POST to OPA /v1/data/zedid/allow
+fn combine(
+    policies: &[Policy],
+    input: &serde_json::Value,
+    algorithm: CombiningAlgorithm,
+    start: Instant,
+) -> PolicyDecisionResponse {
+    let results: Vec<(&Policy, Option<bool>)> = policies.iter().map(|p| (p, evaluate_policy(p, input))).collect();
+    let first = |effect: bool| results.iter().find(|(_, r)| *r == Some(effect)).map(|(p, _)| *p);
+    let allow = |policy: &Policy| decision(true, format!("Allowed by policy: {}", policy.name), Some(policy), start);
+    let deny = |policy: &Policy| decision(false, format!("Denied by policy: {}", policy.name), Some(policy), start);
+    let decided = match algorithm {
+        CombiningAlgorithm::DenyOverrides => first(false).map(deny).or_else(|| first(true).map(allow)),
+        CombiningAlgorithm::AllowOverrides => first(true).map(allow).or_else(|| first(false).map(deny)),
+        CombiningAlgorithm::FirstApplicable => results.iter().find_map(|(policy, r)| match r {
+            Some(true) => Some(allow(policy)),
+            Some(false) => Some(deny(policy)),
+            None => None,
+        }),
+        CombiningAlgorithm::PermitUnlessDeny => Some(first(false).map(deny).unwrap_or_else(|| match first(true) {
+            Some(policy) => allow(policy),
+            None => decision(true, "No policy denied — permit unless deny".to_string(), None, start),
+        })),
+    };
+    decided.unwrap_or_else(|| {
+        decision(
+            false,
+            "No matching policy rule — implicit deny".to_string(),
+            None,
+            start,
+        )
+    })
+}
+
+/// Dispatch on the policy format: Istio and RBAC YAML policies are evaluated
+/// natively, everything else through the simulated Rego matcher. A policy whose
+/// allow rules have all been disabled no longer allows anything.
+fn evaluate_policy(policy: &Policy, input: &serde_json::Value) -> Option<bool> {
+    if is_excluded(policy, input) {
+        return None;
+    }
+    match policy.kind {
+        PolicyKind::IstioAuthz => istio::evaluate(&policy.content, input),
+        PolicyKind::RbacYaml => rbac::evaluate(&policy.content, input),
+        PolicyKind::Rego | PolicyKind::Cedar
+            if !policy.disabled_statements.is_empty() && !statements::can_grant(policy) =>
+        {
+            None
+        }
+        _ => simulate_rego_evaluation(policy, input),
+    }
+}
+
+/// Reject policies that are unnamed or too large to store safely
+pub fn check_policy_limits(policy: &Policy) -> Result<(), PolicyError> {
+    if policy.name.trim().is_empty() {
+        return Err(PolicyError::ValidationFailed(
+            "Policy name cannot be empty".to_string(),
+        ));
+    }
+    if policy.content.len() > MAX_POLICY_CONTENT_BYTES {
+        return Err(PolicyError::ValidationFailed(format!(
+            "Policy content is {} bytes (max {})",
+            policy.content.len(),
+            MAX_POLICY_CONTENT_BYTES
+        )));
+    }
+    if let (Some(from), Some(until)) = (policy.active_from, policy.active_until) {
+        if from >= until {
+            return Err(PolicyError::ValidationFailed(
+                "active_from must be before active_until".to_string(),
+            ));
+        }
+    }
+    if let Some(expr) = &policy.schedule {
+        CronSchedule::parse(expr)?;
+    }
+    check_policy_tests(&policy.tests)?;
+    for (field, entries) in [
+        ("subjects", &policy.subjects),
+        ("resources", &policy.resources),
+        ("actions", &policy.actions),
+        ("excluded_subjects", &policy.excluded_subjects),
+        ("excluded_resources", &policy.excluded_resources),
+    ] {
+        if entries.len() > MAX_POLICY_LIST_ENTRIES {
+            return Err(PolicyError::ValidationFailed(format!(
+                "Too many {}: {} (max {})",
+                field,
+                entries.len(),
+                MAX_POLICY_LIST_ENTRIES
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Test cases need distinct, non-empty names, and are bounded like other lists
+fn check_policy_tests(tests: &[PolicyTestCase]) -> Result<(), PolicyError> {
+    if tests.len() > MAX_POLICY_LIST_ENTRIES {
+        return Err(PolicyError::ValidationFailed(format!(
+            "Too many tests: {} (max {})",
+            tests.len(),
+            MAX_POLICY_LIST_ENTRIES
+        )));
+    }
+    for (i, case) in tests.iter().enumerate() {
+        if case.name.trim().is_empty() {
+            return Err(PolicyError::ValidationFailed("Test case name cannot be empty".to_string()));
+        }
+        if tests[..i].iter().any(|other| other.name == case.name) {
+            return Err(PolicyError::ValidationFailed(format!("Duplicate test case name: {}", case.name)));
+        }
+    }
+    Ok(())
+}
+
+/// Mask the named top-level context fields for logging
+pub(crate) fn redact_context(mut context: serde_json::Value, fields: &[String]) -> serde_json::Value {
+    if let Some(map) = context.as_object_mut() {
+        for field in fields {
+            if let Some(value) = map.get_mut(field) {
+                *value = serde_json::Value::String("[REDACTED]".to_string());
+            }
+        }
+    }
+    context
+}
+
+/// Structured decision event — field names are stable for log indexing
+fn log_decision(req: &PolicyDecisionRequest, response: &PolicyDecisionResponse) {
+    if response.would_deny && response.enforcement_mode == EnforcementMode::Monitor {
+        warn!(
+            actor = %req.subject,
+            resource = %req.resource,
+            action = %req.action,
+            namespace = %req.namespace,
+            decision = "would_deny",
+            policy = response.policy_name.as_deref().unwrap_or("-"),
+            decision_id = %response.decision_id,
+            "Policy decision not enforced (monitor mode)"
+        );
+        return;
+    }
+    info!(
+        actor = %req.subject,
+        resource = %req.resource,
+        action = %req.action,
+        namespace = %req.namespace,
+        decision = if response.would_deny {
+            "would_deny"
+        } else if response.allowed {
+            "allow"
+        } else {
+            "deny"
+        },
+        policy = response.policy_name.as_deref().unwrap_or("-"),
+        decision_id = %response.decision_id,
+        cached = response.cached,
+        latency_ms = response.evaluation_time_ms,
+        "Policy decision"
+    );
+}
+
+/// Characters of context kept on each side of a search match
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// Case-insensitive find of `query` in `text`, returning the surrounding text
+/// on one line with the match wrapped in `**`
+fn search_snippet(text: &str, query: &str) -> Option<String> {
+    let needle: Vec<char> = query.trim().chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return None;
+    }
+    // Fold per character so indices line up with `chars`
+    let chars: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let start = folded.windows(needle.len()).position(|w| w == needle.as_slice())?;
+    let end = start + needle.len();
+
+    let from = start.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let to = (end + SNIPPET_CONTEXT_CHARS).min(chars.len());
+    let part = |range: std::ops::Range<usize>| -> String {
+        chars[range].iter().map(|c| if c.is_whitespace() { ' ' } else { *c }).collect()
+    };
+    Some(format!(
+        "{}{}**{}**{}{}",
+        if from > 0 { "…" } else { "" },
+        part(from..start),
+        part(start..end),
+        part(end..to),
+        if to < chars.len() { "…" } else { "" },
+    ))
+}
+
+fn expect_status(policy: &Policy, expected: PolicyStatus) -> Result<(), PolicyError> {
+    if policy.status != expected {
+        return Err(PolicyError::Conflict(format!(
+            "Policy is {:?}, expected {:?}",
+            policy.status, expected
+        )));
+    }
+    Ok(())
+}
+
+fn expect_reviewer(policy: &Policy, reviewer: &str) -> Result<(), PolicyError> {
+    if !policy.reviewers.iter().any(|r| r == reviewer) {
+        return Err(PolicyError::Unauthorized(format!(
+            "{} is not an assigned reviewer",
+            reviewer
+        )));
+    }
+    Ok(())
+}
+
+/// Build the OPA-style `input` document for a decision.
+///
+/// Schema:
+/// ```json
+/// {
+///   "subject": "...", "resource": "...", "action": "...", "namespace": "...",
+///   "context": { ... caller-supplied ... },
+///   "identity": {            // present only when the subject resolves
+///     "id": "uuid", "name": "...", "kind": "workload|human|ai_agent|service_account",
+///     "trust_level": 0-4, "namespace": "...", "labels": { ... }, "is_active": true
+///   }
+/// }
+/// ```
+pub(crate) fn build_input(req: &PolicyDecisionRequest) -> serde_json::Value {
+    let mut input = serde_json::json!({
+        "subject": req.subject,
+        "resource": req.resource,
+        "action": req.action,
+        "namespace": req.namespace,
+        "context": req.context.to_value(),
+    });
+    if let Some(identity) = &req.identity {
+        input["identity"] = serde_json::json!({
+            "id": identity.id,
+            "name": identity.name,
+            "kind": identity.kind,
+            "trust_level": identity.trust_level.as_u8(),
+            "namespace": identity.namespace,
+            "labels": identity.labels,
+            "is_active": identity.is_active,
+        });
+    }
+    input
+}
+
+/// Roles carried by a token: a `roles` array or comma-separated `role` claim
+fn token_roles(claims: &ZedIdClaims) -> Option<Vec<String>> {
+    if let Some(roles) = claims.extra.get("roles").and_then(|r| r.as_array()) {
+        return Some(roles.iter().filter_map(|r| r.as_str().map(str::to_string)).collect());
+    }
+    let role = claims.extra.get("role")?.as_str()?;
+    Some(role.split(',').map(|r| r.trim().to_string()).filter(|r| !r.is_empty()).collect())
+}
+
+/// Simulate Rego evaluation logic against the `input` document
+/// In production: use regorus crate or OPA REST API
+fn simulate_rego_evaluation(policy: &Policy, input: &serde_json::Value) -> Option<bool> {
+    let subject = input["subject"].as_str().unwrap_or_default();
+    let resource = input["resource"].as_str().unwrap_or_default();
+    let action = input["action"].as_str().unwrap_or_default();
+
+    // Check if any subject matches (exclusions are checked by `evaluate_policy`)
+    let subject_matches = policy.subjects.is_empty()
+        || policy
+            .subjects
+            .iter()
+            .any(|s| subject_matches(s, subject) || s.starts_with("role:"));
+
+    // Check if any resource matches
+    let resource_matches = policy.resources.is_empty()
+        || policy.resources.iter().any(|r| pattern_matches(r, resource));
+
+    // Check if action matches
+    let action_matches = policy.actions.is_empty()
+        || policy.actions.iter().any(|a| a == action || a == "*");
+
+    if subject_matches && resource_matches && action_matches {
         Some(true)
     } else {
         None
     }
-}
+}
+
+/// Whether the request's subject or resource is one of the policy's
+/// exclusions, which take it out of scope whatever the policy kind
+fn is_excluded(policy: &Policy, input: &serde_json::Value) -> bool {
+    let subject = input["subject"].as_str().unwrap_or_default();
+    let resource = input["resource"].as_str().unwrap_or_default();
+    policy.excluded_subjects.iter().any(|s| subject_matches(s, subject))
+        || policy.excluded_resources.iter().any(|r| pattern_matches(r, resource))
+}
+
+/// The subject entry through which `policy` selects an identity known by
+/// `names` (SPIFFE ID, id, email) and `roles`
+fn identity_match(policy: &Policy, names: &[String], roles: &[String]) -> Option<String> {
+    let matches = |pattern: &str| names.iter().any(|n| subject_matches(pattern, n));
+    if policy.excluded_subjects.iter().any(|s| matches(s)) {
+        return None;
+    }
+    if policy.kind == PolicyKind::IstioAuthz {
+        return names
+            .iter()
+            .any(|n| n.starts_with("spiffe://") && istio::selects_principal(&policy.content, n))
+            .then(|| "source.principal".to_string());
+    }
+    if policy.subjects.is_empty() {
+        return Some("*".to_string());
+    }
+    policy
+        .subjects
+        .iter()
+        .find(|s| match s.strip_prefix("role:") {
+            Some(role) => roles.iter().any(|r| r == role),
+            None => matches(s),
+        })
+        .cloned()
+}
+
+/// Istio DENY policies deny; Rego and Cedar policies without an enabled
+/// allow rule or `permit` statement only ever deny
+fn policy_effect(policy: &Policy) -> PolicyEffect {
+    let denies = match policy.kind {
+        PolicyKind::IstioAuthz => serde_yaml::from_str::<serde_yaml::Value>(&policy.content)
+            .is_ok_and(|doc| doc["spec"]["action"].as_str() == Some("DENY")),
+        PolicyKind::Rego | PolicyKind::Cedar => !statements::can_grant(policy),
+        PolicyKind::RbacYaml => false,
+    };
+    if denies {
+        PolicyEffect::Deny
+    } else {
+        PolicyEffect::Allow
+    }
+}
+
+/// Whether `policy` applies to `resource`: an empty list covers everything,
+/// and exclusions take precedence
+fn governs_resource(policy: &Policy, resource: &str) -> bool {
+    (policy.resources.is_empty() || policy.resources.iter().any(|r| pattern_matches(r, resource)))
+        && !policy.excluded_resources.iter().any(|r| pattern_matches(r, resource))
+}
+
+/// `*` matches anything, `prefix/*` matches values under `prefix/`,
+/// anything else must match exactly
+pub(crate) fn pattern_matches(pattern: &str, value: &str) -> bool {
+    if pattern == "*" {
+        return true;
+    }
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => value
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/')),
+        None => pattern == value,
+    }
+}
+
+/// `pattern_matches` for subjects, comparing SPIFFE IDs in normalized form
+fn subject_matches(pattern: &str, subject: &str) -> bool {
+    pattern_matches(&normalize_spiffe_id(pattern), &normalize_spiffe_id(subject))
+}
+
+// Demo Rego policies
+const DEMO_REGO_POLICY_1: &str = r#"package zedid.production.inventory
+
+import future.keywords.if
+import future.keywords.in
+
+default allow := false
+
+# Allow checkout service to read inventory
+allow if {
+    input.subject == "spiffe://tetrate.io/ns/production/sa/checkout"
+    input.action in {"GET", "LIST"}
+    input.resource == "inventory-service"
+    input.trust_level >= 3
+}
+
+# Deny all write operations from checkout
+deny if {
+    input.subject == "spiffe://tetrate.io/ns/production/sa/checkout"
+    input.action in {"POST", "PUT", "DELETE", "PATCH"}
+}
+"#;
+
+const DEMO_REGO_POLICY_2: &str = r#"package zedid.ai.tars_routing
+
+import future.keywords.if
+import future.keywords.in
+
+default allow := false
+
+# AI agents with sufficient trust may route through TARS
+allow if {
+    startswith(input.subject, "spiffe://tetrate.io/ns/ai-platform/agent/")
+    input.action == "route"
+    input.resource == "tars-router"
+    input.trust_level >= 2
+    not budget_exceeded
+}
+
+# Budget enforcement: max 10000 tokens per day per agent
+budget_exceeded if {
+    input.context.daily_tokens_used > 10000
+}
+
+# High-risk model access requires higher trust
+deny if {
+    input.context.target_model in {"gpt-4o", "claude-3-opus"}
+    input.trust_level < 3
+}
+"#;
+
+const DEMO_REGO_POLICY_3: &str = r#"package zedid.system.admin
+
+import future.keywords.if
+import future.keywords.in
+
+default allow := false
+
+# Platform admins have full access
+allow if {
+    "platform-admin" in input.roles
+    input.trust_level >= 4
+    valid_session
+}
+
+valid_session if {
+    input.context.mfa_verified == true
+    input.context.session_age_minutes < 60
+}
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DecisionContext, DecisionContextInput};
+
+    fn checkout_get() -> PolicyDecisionRequest {
+        PolicyDecisionRequest {
+            subject: "spiffe://tetrate.io/ns/production/sa/checkout".to_string(),
+            resource: "inventory-service".to_string(),
+            action: "GET".to_string(),
+            namespace: "production".to_string(),
+            context: DecisionContext::new().mfa_verified(true).into(),
+            skip_enrichment: false,
+            group: None,
+            subject_token: None,
+            identity_snapshot: None,
+            identity: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_policy_stats_count_hits_and_reset() {
+        let engine = PolicyEngine::new();
+        engine.seed_demo_policies().await;
+
+        let allowed = engine.evaluate(&checkout_get()).await.unwrap();
+        engine.evaluate(&checkout_get()).await.unwrap();
+        let id = allowed.policy_id.unwrap();
+        let stats = engine.policy_stats(id).await.unwrap();
+        assert_eq!((stats.evaluations, stats.allows, stats.denies), (2, 2, 0));
+        assert_eq!(stats.rule_hits["allow"], 2);
+        assert!(stats.last_hit.is_some());
+
+        engine.reset_policy_stats(id).await.unwrap();
+        assert_eq!(engine.policy_stats(id).await.unwrap().evaluations, 0);
+        assert!(engine.policy_stats(Uuid::new_v4()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_decision_cache_hit_and_invalidation() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            decision_cache_ttl_ms: 60_000,
+            ..Default::default()
+        });
+        engine.seed_demo_policies().await;
+
+        let first = engine.evaluate(&checkout_get()).await.unwrap();
+        let second = engine.evaluate(&checkout_get()).await.unwrap();
+        assert!(!first.cached);
+        assert!(second.cached);
+        assert_eq!(first.allowed, second.allowed);
+        assert_ne!(first.decision_id, second.decision_id);
+
+        // A status change in the namespace must flush its cached decisions
+        let id = first.policy_id.unwrap();
+        engine.update_policy_status(id, PolicyStatus::Disabled).await.unwrap();
+        let third = engine.evaluate(&checkout_get()).await.unwrap();
+        assert!(!third.cached);
+    }
+
+    #[tokio::test]
+    async fn test_decision_cache_keeps_unenriched_requests_apart() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            decision_cache_ttl_ms: 60_000,
+            ..Default::default()
+        });
+        engine.seed_demo_policies().await;
+        let unenriched = PolicyDecisionRequest { skip_enrichment: true, ..checkout_get() };
+
+        assert!(!engine.evaluate(&checkout_get()).await.unwrap().cached);
+        assert!(!engine.evaluate(&unenriched).await.unwrap().cached);
+        assert!(engine.evaluate(&unenriched).await.unwrap().cached);
+        assert_eq!(engine.decision_cache.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_review_workflow_gates_activation() {
+        let engine = PolicyEngine::new();
+        let draft = engine
+            .add_policy(Policy::new(
+                "draft",
+                "draft policy",
+                PolicyKind::Rego,
+                AccessModel::ZeroTrust,
+                "package x\nallow := true",
+                "production",
+                "test",
+            ))
+            .await
+            .unwrap();
+
+        let direct = engine.update_policy_status(draft.id, PolicyStatus::Active).await;
+        assert!(matches!(direct, Err(PolicyError::Conflict(_))));
+
+        engine
+            .submit_for_review(draft.id, vec!["alice".to_string()])
+            .await
+            .unwrap();
+        let outsider = engine.approve_policy(draft.id, "mallory", None).await;
+        assert!(matches!(outsider, Err(PolicyError::Unauthorized(_))));
+
+        let approved = engine.approve_policy(draft.id, "alice", None).await.unwrap();
+        assert_eq!(approved.status, PolicyStatus::Active);
+        assert_eq!(approved.approved_by.as_deref(), Some("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_status_transitions_follow_state_machine() {
+        use PolicyStatus::*;
+        let engine = PolicyEngine::with_config(EngineConfig {
+            allow_direct_activation: true,
+            ..Default::default()
+        });
+        let allowed = [
+            (Draft, Review), (Draft, Active), (Draft, Archived),
+            (Review, Draft), (Review, Active), (Review, Archived),
+            (Active, Disabled), (Active, Archived),
+            (Disabled, Active), (Disabled, Draft), (Disabled, Archived),
+            (Archived, Draft),
+        ];
+        let all = [Draft, Review, Active, Disabled, Archived];
+        for from in &all {
+            for to in &all {
+                let mut policy = Policy::new("p", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+                policy.status = from.clone();
+                let policy = engine.add_policy(policy).await.unwrap();
+                let result = engine.update_policy_status(policy.id, to.clone()).await;
+                let expected = allowed.contains(&(from.clone(), to.clone()));
+                assert_eq!(result.is_ok(), expected, "{:?} -> {:?}", from, to);
+                if !expected {
+                    assert!(matches!(result, Err(PolicyError::Conflict(_))));
+                    assert_eq!(engine.get_policy(policy.id).await.unwrap().status, *from);
+                }
+            }
+        }
+
+        // Without direct activation, Review -> Active is left to approval
+        let gated = PolicyEngine::new();
+        let mut policy = Policy::new("p", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.status = Review;
+        let policy = gated.add_policy(policy).await.unwrap();
+        assert_eq!(gated.next_statuses(&policy), [Draft, Archived]);
+        assert!(matches!(gated.update_policy_status(policy.id, Active).await, Err(PolicyError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_failing_policy_tests_block_activation() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            allow_direct_activation: true,
+            require_passing_tests: true,
+            ..Default::default()
+        });
+        let mut policy = Policy::new("inventory-read", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.resources = vec!["inventory-service".to_string()];
+        policy.tests = vec![
+            PolicyTestCase { name: "checkout can GET".to_string(), request: checkout_get(), expect: PolicyEffect::Allow },
+            PolicyTestCase {
+                name: "checkout cannot DELETE".to_string(),
+                request: PolicyDecisionRequest { action: "DELETE".to_string(), ..checkout_get() },
+                expect: PolicyEffect::Deny,
+            },
+        ];
+        let policy = engine.add_policy(policy).await.unwrap();
+
+        // No action list: DELETE is allowed too, so the second case fails
+        let report = engine.run_policy_tests(policy.id).await.unwrap();
+        assert_eq!((report.total, report.failed, report.passed), (2, 1, false));
+        assert!(report.results[0].passed);
+        assert_eq!(report.results[1].actual, PolicyEffect::Allow);
+        let blocked = engine.activate_policy(policy.id, false).await;
+        assert!(matches!(blocked, Err(PolicyError::Conflict(ref e)) if e.contains("checkout cannot DELETE")));
+        let bulk = engine.activate_policies(&[policy.id], true).await;
+        assert!(bulk.aborted);
+
+        engine.modify_policy(policy.id, |p| {
+            p.actions = vec!["GET".to_string()];
+            Ok(())
+        })
+        .await
+        .unwrap();
+        assert!(engine.run_policy_tests(policy.id).await.unwrap().passed);
+        assert_eq!(engine.activate_policy(policy.id, false).await.unwrap().status, PolicyStatus::Active);
+
+        let duplicate = vec![policy.tests[0].clone(), policy.tests[0].clone()];
+        assert!(matches!(engine.set_policy_tests(policy.id, duplicate).await, Err(PolicyError::ValidationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_mode_reports_would_deny() {
+        let engine = PolicyEngine::new();
+        engine.seed_demo_namespaces().await;
+        let mut policy = Policy::new(
+            "checkout-reads-inventory",
+            "",
+            PolicyKind::Rego,
+            AccessModel::ZeroTrust,
+            "package x",
+            "production",
+            "test",
+        );
+        policy.status = PolicyStatus::Active;
+        policy.resources = vec!["inventory-service".to_string()];
+        engine.add_policy(policy).await.unwrap();
+        let write = PolicyDecisionRequest {
+            resource: "payments-db".to_string(),
+            ..checkout_get()
+        };
+
+        let enforced = engine.evaluate(&write).await.unwrap();
+        assert!(!enforced.allowed);
+        assert!(!enforced.would_deny);
+
+        engine
+            .update_namespace(
+                "production",
+                crate::models::UpdateNamespaceRequest {
+                    description: None,
+                    owner: None,
+                    default_trust_level: None,
+                    enforcement_mode: Some(EnforcementMode::Monitor),
+                    variables: None,
+                    reason_verbosity: None,
+                    context_allowlist: None,
+                    combining_algorithm: None,
+                },
+            )
+            .await
+            .unwrap();
+        let monitored = engine.evaluate(&write).await.unwrap();
+        assert!(monitored.allowed);
+        assert!(monitored.would_deny);
+    }
 
-// Demo Rego policies
-const DEMO_REGO_POLICY_1: &str = r#"package zedid.production.inventory
+    #[test]
+    fn test_build_input_enriches_identity() {
+        let identity = Identity::new_ai_agent("tars-policy-agent", "ai-platform", "tetrate.io");
+        let mut req = PolicyDecisionRequest {
+            subject: identity.spiffe_id.clone().unwrap(),
+            ..checkout_get()
+        };
+        req.identity = Some(identity);
 
-import future.keywords.if
-import future.keywords.in
+        let input = build_input(&req);
+        assert_eq!(input["identity"]["kind"], "ai_agent");
+        assert_eq!(input["identity"]["trust_level"], 2);
+        assert_eq!(input["identity"]["is_active"], true);
 
-default allow := false
+        req.identity = None;
+        let bare = build_input(&req);
+        assert!(bare.get("identity").is_none());
+    }
 
-# Allow checkout service to read inventory
-allow if {
-    input.subject == "spiffe://tetrate.io/ns/production/sa/checkout"
-    input.action in {"GET", "LIST"}
-    input.resource == "inventory-service"
-    input.trust_level >= 3
-}
+    #[tokio::test]
+    async fn test_identity_snapshot_replaces_live_identity() {
+        let checkout = Identity::new_workload("checkout", "production", "tetrate.io");
+        let identities = Arc::new(RwLock::new(vec![checkout.clone()]));
+        let config = |allow_identity_snapshots| EngineConfig { allow_identity_snapshots, ..Default::default() };
+        let engine = PolicyEngine::with_config(config(true)).with_identities(identities.clone());
+        engine.seed_demo_policies().await;
 
-# Deny all write operations from checkout
-deny if {
-    input.subject == "spiffe://tetrate.io/ns/production/sa/checkout"
-    input.action in {"POST", "PUT", "DELETE", "PATCH"}
-}
-"#;
+        let mut snapshot = checkout.clone();
+        snapshot.trust_level = zedid_identity::TrustLevel::Low;
+        snapshot.labels.insert("team".to_string(), "retired".to_string());
+        let req = PolicyDecisionRequest {
+            subject: checkout.spiffe_id.clone().unwrap(),
+            identity_snapshot: Some(snapshot),
+            ..checkout_get()
+        };
+        let enriched = engine.pre_evaluate(req.clone(), None).await;
+        let input = build_input(&enriched);
+        assert_eq!(input["identity"]["trust_level"], 1);
+        assert_eq!(input["identity"]["labels"]["team"], "retired");
+        assert!(engine.evaluate(&req).await.is_ok());
+        assert!(engine.decision_cache_key(&req).is_none());
 
-const DEMO_REGO_POLICY_2: &str = r#"package zedid.ai.tars_routing
+        let live = engine.pre_evaluate(PolicyDecisionRequest { identity_snapshot: None, ..req.clone() }, None).await;
+        assert_eq!(build_input(&live)["identity"]["trust_level"], 3);
 
-import future.keywords.if
-import future.keywords.in
+        let other = PolicyDecisionRequest { subject: "spiffe://tetrate.io/ns/production/sa/other".to_string(), ..req.clone() };
+        assert!(matches!(engine.evaluate(&other).await, Err(PolicyError::ValidationFailed(_))));
+        let locked = PolicyEngine::with_config(config(false)).with_identities(identities);
+        assert!(matches!(locked.evaluate(&req).await, Err(PolicyError::ValidationFailed(_))));
+    }
 
-default allow := false
+    /// Adds a risk score to the context and denies high-risk decisions
+    struct RiskScoreHook;
 
-# AI agents with sufficient trust may route through TARS
-allow if {
-    startswith(input.subject, "spiffe://tetrate.io/ns/ai-platform/agent/")
-    input.action == "route"
-    input.resource == "tars-router"
-    input.trust_level >= 2
-    not budget_exceeded
-}
+    #[async_trait::async_trait]
+    impl EvaluationHook for RiskScoreHook {
+        fn name(&self) -> &str {
+            "risk-score"
+        }
 
-# Budget enforcement: max 10000 tokens per day per agent
-budget_exceeded if {
-    input.context.daily_tokens_used > 10000
-}
+        async fn pre_evaluate(&self, req: &mut PolicyDecisionRequest) {
+            let mut context = req.context.to_value();
+            context["risk_score"] = serde_json::json!(90);
+            req.context = context.into();
+        }
 
-# High-risk model access requires higher trust
-deny if {
-    input.context.target_model in {"gpt-4o", "claude-3-opus"}
-    input.trust_level < 3
-}
-"#;
+        async fn post_evaluate(&self, req: &PolicyDecisionRequest, response: &mut PolicyDecisionResponse) {
+            let enriched = req.identity.as_ref().map(|i| i.name.as_str()).unwrap_or("unknown");
+            if req.context.to_value()["risk_score"].as_u64() > Some(80) {
+                response.allowed = false;
+                response.reason = format!("Risk score too high for {}", enriched);
+            }
+        }
+    }
 
-const DEMO_REGO_POLICY_3: &str = r#"package zedid.system.admin
+    #[tokio::test]
+    async fn test_hooks_run_in_order_around_evaluation() {
+        let checkout = Identity::new_workload("checkout", "production", "tetrate.io");
+        let identities = Arc::new(RwLock::new(vec![checkout]));
+        let engine = PolicyEngine::new()
+            .with_hook(Arc::new(RiskScoreHook))
+            .with_identities(identities);
+        engine.seed_demo_policies().await;
+        assert_eq!(
+            engine.hooks.iter().map(|h| h.name()).collect::<Vec<_>>(),
+            ["identity-enrichment", "risk-score"]
+        );
 
-import future.keywords.if
-import future.keywords.in
+        let response = engine.evaluate(&checkout_get()).await.unwrap();
+        assert!(!response.allowed);
+        assert_eq!(response.reason, "Risk score too high for checkout");
+    }
 
-default allow := false
+    #[tokio::test]
+    async fn test_subject_token_overrides_asserted_subject_and_fails_closed() {
+        let jwt = Arc::new(JwtService::new("test-secret", "zedid.tetrate.io"));
+        let engine = PolicyEngine::new().with_jwt_service(Arc::clone(&jwt));
+        engine.seed_demo_policies().await;
 
-# Platform admins have full access
-allow if {
-    "platform-admin" in input.roles
-    input.trust_level >= 4
-    valid_session
-}
+        // The token belongs to cart; the caller claims to be checkout
+        let cart = "spiffe://tetrate.io/ns/production/sa/cart".to_string();
+        let token = jwt
+            .issue_token("cart-id", "cart", "production", "workload", 3, Some(cart.clone()), 5, None)
+            .unwrap();
+        let spoofed = PolicyDecisionRequest { subject_token: Some(token), ..checkout_get() };
+        let (verified, claims) = engine.verify_subject_token(&spoofed).await.unwrap();
+        assert_eq!(verified.subject, cart);
+        assert_eq!(claims.unwrap().trust_level, 3);
+        assert!(engine.evaluate(&checkout_get()).await.unwrap().allowed);
+        assert!(!engine.evaluate(&spoofed).await.unwrap().allowed);
 
-valid_session if {
-    input.context.mfa_verified == true
-    input.context.session_age_minutes < 60
+        let forged = PolicyDecisionRequest { subject_token: Some("not-a-jwt".to_string()), ..checkout_get() };
+        let denied = engine.evaluate(&forged).await.unwrap();
+        assert!(!denied.allowed);
+        assert!(denied.reason.starts_with("Invalid subject token"), "{}", denied.reason);
+        let unconfigured = PolicyEngine::new();
+        unconfigured.seed_demo_policies().await;
+        assert!(!unconfigured.evaluate(&forged).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_subject_token_of_inactive_identity_fails_closed() {
+        let jwt = Arc::new(JwtService::new("test-secret", "zedid.tetrate.io"));
+        let checkout = Identity::new_workload("checkout", "production", "tetrate.io");
+        let identities = Arc::new(RwLock::new(vec![checkout.clone()]));
+        let engine = PolicyEngine::new()
+            .with_jwt_service(Arc::clone(&jwt))
+            .with_identities(identities.clone());
+        engine.seed_demo_policies().await;
+
+        let token = jwt
+            .issue_token(&checkout.id.to_string(), "checkout", "production", "workload", 3, checkout.spiffe_id.clone(), 5, None)
+            .unwrap();
+        let req = PolicyDecisionRequest { subject_token: Some(token), ..checkout_get() };
+        assert!(engine.evaluate(&req).await.unwrap().allowed);
+
+        identities.write().await[0].is_active = false;
+        let denied = engine.evaluate(&req).await.unwrap();
+        assert!(!denied.allowed);
+        assert!(denied.reason.starts_with("Invalid subject token"), "{}", denied.reason);
+    }
+
+    #[tokio::test]
+    async fn test_impact_on_identity_reports_action_matrix() {
+        let checkout = Identity::new_workload("checkout", "production", "tetrate.io");
+        let engine = PolicyEngine::new().with_identities(Arc::new(RwLock::new(vec![checkout.clone()])));
+        let rbac = "kind: RBACPolicy\nspec:\n  roles:\n    - name: reader\n      rules:\n        - resources: [\"inventory-service\"]\n          verbs: [\"get\"]\n  roleBindings:\n    - role: reader\n      subjects:\n        - kind: Namespace\n          name: production\n";
+        let mut policy = Policy::new("onboarding", "", PolicyKind::RbacYaml, AccessModel::Rbac, rbac, "production", "test");
+        policy.resources = vec!["inventory-service".to_string(), "payments-service".to_string()];
+        policy.actions = vec!["GET".to_string(), "DELETE".to_string()];
+        let policy = engine.add_policy(policy).await.unwrap();
+
+        let impact = engine.impact_on_identity(policy.id, &checkout).await.unwrap();
+        assert_eq!(impact.subject, checkout.spiffe_id.clone().unwrap());
+        assert_eq!(impact.resources[0].allowed, ["GET"]);
+        assert_eq!(impact.resources[0].denied, ["DELETE"]);
+        assert!(impact.resources[1].allowed.is_empty());
+        assert_eq!((impact.allowed, impact.denied), (1, 3));
+
+        let bare = engine
+            .add_policy(Policy::new("bare", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test"))
+            .await
+            .unwrap();
+        assert!(matches!(engine.impact_on_identity(bare.id, &checkout).await, Err(PolicyError::ValidationFailed(_))));
+    }
+
+    #[test]
+    fn test_exclusions_apply_to_rbac_policies() {
+        let rbac = "kind: RBACPolicy\nspec:\n  roles:\n    - name: reader\n      rules:\n        - resources: [\"inventory-service\"]\n          verbs: [\"get\"]\n  roleBindings:\n    - role: reader\n      subjects:\n        - kind: Namespace\n          name: production\n";
+        let mut policy = Policy::new("readers", "", PolicyKind::RbacYaml, AccessModel::Rbac, rbac, "production", "test");
+        let input = build_input(&checkout_get());
+        assert_eq!(evaluate_policy(&policy, &input), Some(true));
+
+        policy.excluded_subjects = vec!["spiffe://tetrate.io/ns/production/sa/checkout".to_string()];
+        assert_eq!(evaluate_policy(&policy, &input), None);
+        policy.excluded_subjects.clear();
+        policy.excluded_resources = vec!["inventory-service".to_string()];
+        assert_eq!(evaluate_policy(&policy, &input), None);
+    }
+
+    #[tokio::test]
+    async fn test_exclusions_apply_to_istio_policies() {
+        let engine = PolicyEngine::new();
+        let allow = "spec:\n  action: ALLOW\n  rules:\n    - from:\n        - source:\n            namespaces: [\"production\"]\n";
+        let mut policy = Policy::new("prod-callers", "", PolicyKind::IstioAuthz, AccessModel::ZeroTrust, allow, "production", "test");
+        policy.status = PolicyStatus::Active;
+        let policy = engine.add_policy(policy).await.unwrap();
+        assert!(engine.evaluate(&checkout_get()).await.unwrap().allowed);
+
+        engine
+            .modify_policy(policy.id, |p| {
+                p.excluded_subjects = vec!["spiffe://tetrate.io/ns/production/sa/checkout".to_string()];
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert!(!engine.evaluate(&checkout_get()).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_token_roles_grant_rbac_group_bindings() {
+        let jwt = Arc::new(JwtService::new("test-secret", "zedid.tetrate.io"));
+        let engine = PolicyEngine::new().with_jwt_service(Arc::clone(&jwt));
+        let rbac = "kind: RBACPolicy\nspec:\n  roles:\n    - name: reader\n      rules:\n        - resources: [\"inventory-service\"]\n          verbs: [\"get\"]\n  roleBindings:\n    - role: reader\n      subjects:\n        - kind: Group\n          name: auditors\n";
+        let mut policy = Policy::new("auditors", "", PolicyKind::RbacYaml, AccessModel::Rbac, rbac, "production", "test");
+        policy.status = PolicyStatus::Active;
+        engine.add_policy(policy).await.unwrap();
+
+        let cart = "spiffe://tetrate.io/ns/production/sa/cart";
+        let token = |roles: serde_json::Value| {
+            let extra = HashMap::from([("roles".to_string(), roles)]);
+            jwt.issue_token_with_claims(cart, "cart", "production", "workload", 3, Some(cart.to_string()), 5, 0, None, extra)
+                .unwrap()
+        };
+        let req = |token: String| PolicyDecisionRequest { subject: cart.to_string(), subject_token: Some(token), ..checkout_get() };
+        assert!(engine.evaluate(&req(token(serde_json::json!(["auditors"])))).await.unwrap().allowed);
+        assert!(!engine.evaluate(&req(token(serde_json::json!(["viewers"])))).await.unwrap().allowed);
+
+        // Callers can't assert roles in the context themselves
+        let mut asserted = PolicyDecisionRequest { subject: cart.to_string(), ..checkout_get() };
+        asserted.context = serde_json::json!({"roles": ["auditors"]}).into();
+        assert!(!engine.evaluate(&asserted).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_decision_cache_keeps_token_roles_apart() {
+        let jwt = Arc::new(JwtService::new("test-secret", "zedid.tetrate.io"));
+        let engine = PolicyEngine::with_config(EngineConfig {
+            decision_cache_ttl_ms: 60_000,
+            ..Default::default()
+        })
+        .with_jwt_service(Arc::clone(&jwt));
+        let rbac = "kind: RBACPolicy\nspec:\n  roles:\n    - name: reader\n      rules:\n        - resources: [\"inventory-service\"]\n          verbs: [\"get\"]\n  roleBindings:\n    - role: reader\n      subjects:\n        - kind: Group\n          name: auditors\n";
+        let mut policy = Policy::new("auditors", "", PolicyKind::RbacYaml, AccessModel::Rbac, rbac, "production", "test");
+        policy.status = PolicyStatus::Active;
+        engine.add_policy(policy).await.unwrap();
+
+        let cart = "spiffe://tetrate.io/ns/production/sa/cart";
+        let extra = HashMap::from([("roles".to_string(), serde_json::json!(["auditors"]))]);
+        let token = jwt
+            .issue_token_with_claims(cart, "cart", "production", "workload", 3, Some(cart.to_string()), 5, 0, None, extra)
+            .unwrap();
+        let tokenless = PolicyDecisionRequest { subject: cart.to_string(), ..checkout_get() };
+        let with_token = PolicyDecisionRequest { subject_token: Some(token), ..tokenless.clone() };
+
+        assert!(engine.evaluate(&with_token).await.unwrap().allowed);
+        let anonymous = engine.evaluate(&tokenless).await.unwrap();
+        assert!(!anonymous.cached);
+        assert!(!anonymous.allowed);
+    }
+
+    #[test]
+    fn test_decision_context_typed_and_raw() {
+        let typed: PolicyDecisionRequest = serde_json::from_value(serde_json::json!({
+            "subject": "s", "resource": "r", "action": "GET", "namespace": "production",
+            "context": { "mfa_verified": true, "region": "eu-west-1" }
+        }))
+        .unwrap();
+        assert!(matches!(typed.context, DecisionContextInput::Typed(_)));
+        let input = build_input(&typed);
+        assert_eq!(input["context"]["mfa_verified"], true);
+        assert_eq!(input["context"]["region"], "eu-west-1");
+
+        // A mistyped well-known field is passed through as-is rather than rejected
+        let raw: PolicyDecisionRequest = serde_json::from_value(serde_json::json!({
+            "subject": "s", "resource": "r", "action": "GET", "namespace": "production",
+            "context": { "mfa_verified": "yes" }
+        }))
+        .unwrap();
+        assert!(matches!(raw.context, DecisionContextInput::Raw(_)));
+        assert_eq!(build_input(&raw)["context"]["mfa_verified"], "yes");
+    }
+
+    #[tokio::test]
+    async fn test_eval_timeout_fails_closed() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            eval_timeout_ms: 20,
+            ..Default::default()
+        });
+        engine.seed_demo_policies().await;
+
+        // Holding the store lock stalls evaluation past the deadline
+        let _guard = engine.policies.write().await;
+        let decision = engine.evaluate(&checkout_get()).await.unwrap();
+
+        assert!(!decision.allowed);
+        assert_eq!(decision.reason, "evaluation timed out — fail closed");
+        assert_eq!(engine.metrics().get(metrics::EVAL_TIMEOUTS_TOTAL), 1);
+    }
+
+    #[tokio::test]
+    async fn test_latency_percentiles_and_slow_evaluations() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            eval_timeout_ms: 20,
+            slow_eval_ms: 15,
+            ..Default::default()
+        });
+        engine.seed_demo_policies().await;
+        for _ in 0..3 {
+            engine.evaluate(&checkout_get()).await.unwrap();
+        }
+        {
+            // A stalled evaluation times out after 20ms, past the slow threshold
+            let _guard = engine.policies.write().await;
+            engine.evaluate(&checkout_get()).await.unwrap();
+        }
+
+        let stats = engine.evaluation_stats();
+        assert_eq!((stats.evaluations, stats.samples, stats.timeouts), (4, 4, 1));
+        assert_eq!((stats.slow_evaluations, stats.slow_threshold_ms), (1, 15));
+        assert!(stats.p50_ms < 15.0, "{:?}", stats);
+        assert!(stats.p99_ms >= 20.0 && stats.max_ms == stats.p99_ms, "{:?}", stats);
+    }
+
+    #[tokio::test]
+    async fn test_opa_backend_failure_is_an_error() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            opa_url: Some("http://127.0.0.1:1".to_string()),
+            ..Default::default()
+        });
+        engine.seed_demo_policies().await;
+
+        // Local policies would allow this; the unreachable backend must not fall back to them
+        let result = engine.evaluate(&checkout_get()).await;
+        assert!(matches!(result, Err(PolicyError::OpaError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_templated_policy_renders_namespace_variables() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            trust_domain: "tetrate.io".to_string(),
+            ..Default::default()
+        });
+        let mut ns = Namespace::new("production", "", None);
+        ns.variables.insert("caller".to_string(), "checkout".to_string());
+        engine.create_namespace(ns).await.unwrap();
+
+        let mut policy = Policy::new(
+            "templated",
+            "",
+            PolicyKind::Rego,
+            AccessModel::ZeroTrust,
+            "package zedid.{{ namespace }}\ndefault allow := false",
+            "production",
+            "test",
+        );
+        policy.status = PolicyStatus::Active;
+        policy.subjects = vec!["spiffe://{{ trust_domain }}/ns/{{ namespace }}/sa/{{ caller }}".to_string()];
+        policy.resources = vec!["inventory-service".to_string()];
+        engine.add_policy(policy).await.unwrap();
+
+        let decision = engine.evaluate(&checkout_get()).await.unwrap();
+        assert!(decision.allowed, "{}", decision.reason);
+
+        let bundle = engine.export_bundle(Some("production")).await;
+        assert!(bundle.policies[0].content.starts_with("package zedid.production"));
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_marks_subject_seen() {
+        let mut checkout = Identity::new_workload("checkout", "production", "tetrate.io");
+        let old = chrono::Utc::now() - chrono::Duration::days(3);
+        checkout.last_seen = old;
+        let identities = Arc::new(RwLock::new(vec![checkout]));
+        let engine = PolicyEngine::new().with_identities(Arc::clone(&identities));
+
+        engine.evaluate(&checkout_get()).await.unwrap();
+        let seen = identities.read().await[0].last_seen;
+        assert!(seen > old);
+
+        // Within the resolution window the stored value is left alone
+        engine.evaluate(&checkout_get()).await.unwrap();
+        assert_eq!(identities.read().await[0].last_seen, seen);
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_as_of_uses_historical_policy_set() {
+        let engine = PolicyEngine::with_config(EngineConfig {
+            allow_direct_activation: true,
+            ..Default::default()
+        });
+        let mut policy = Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.status = PolicyStatus::Active;
+        policy.subjects = vec!["spiffe://tetrate.io/ns/production/sa/checkout".to_string()];
+        let policy = engine.add_policy(policy).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let while_active = chrono::Utc::now();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        engine.update_policy_status(policy.id, PolicyStatus::Disabled).await.unwrap();
+
+        assert!(!engine.evaluate(&checkout_get()).await.unwrap().allowed);
+
+        let (past, used) = engine.evaluate_as_of(&checkout_get(), while_active).await;
+        assert!(past.allowed);
+        assert_eq!(used.len(), 1);
+        assert_eq!(used[0].revision, 1);
+
+        // Group scoping applies to replays too; an unknown group matches nothing
+        let scoped = PolicyDecisionRequest { group: Some(Uuid::new_v4()), ..checkout_get() };
+        let (past, used) = engine.evaluate_as_of(&scoped, while_active).await;
+        assert!(!past.allowed);
+        assert!(used.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_seeded_policies_pass_startup_self_check() {
+        let engine = PolicyEngine::new();
+        assert!(engine.seed_demo_policies().await.is_empty());
+        let policies = engine.list_policies(None).await;
+        assert_eq!(policies.len(), 3);
+        assert!(policies.iter().all(|p| p.validation_passed));
+    }
+
+    #[tokio::test]
+    async fn test_evaluate_proposed_applies_overlay_without_storing() {
+        let engine = PolicyEngine::new();
+        let mut policy = Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.status = PolicyStatus::Active;
+        policy.subjects = vec!["spiffe://tetrate.io/ns/production/sa/checkout".to_string()];
+        let policy = engine.add_policy(policy).await.unwrap();
+
+        let mut disabled = policy.clone();
+        disabled.status = PolicyStatus::Disabled;
+        let (proposed, current) = engine
+            .evaluate_proposed("production", vec![disabled.clone()], &checkout_get())
+            .await
+            .unwrap();
+        assert!(current.allowed);
+        assert!(!proposed.allowed);
+        assert_eq!(engine.get_policy(policy.id).await.unwrap().status, PolicyStatus::Active);
+
+        // A draft overlay takes part as if activated, and is not stored
+        let mut draft = policy.clone();
+        draft.id = Uuid::new_v4();
+        draft.status = PolicyStatus::Draft;
+        let (proposed, _) = engine
+            .evaluate_proposed("production", vec![disabled, draft.clone()], &checkout_get())
+            .await
+            .unwrap();
+        assert!(proposed.allowed);
+        assert_eq!(proposed.policy_id, Some(draft.id));
+        assert!(engine.get_policy(draft.id).await.is_none());
+
+        let err = engine.evaluate_proposed("staging", vec![], &checkout_get()).await;
+        assert!(matches!(err, Err(PolicyError::ValidationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_caller_cannot_assert_mfa_unless_allowlisted() {
+        let engine = PolicyEngine::new();
+        engine.seed_demo_policies().await;
+        let req = checkout_get();
+
+        let stripped = engine.filter_context(&req, true).await.unwrap().expect("mfa_verified removed");
+        assert!(stripped.context.to_value().get("mfa_verified").is_none());
+        assert_eq!(engine.metrics().get(metrics::CONTEXT_FIELDS_STRIPPED_TOTAL), 1);
+
+        let mut ns = Namespace::new("production", "", None);
+        ns.context_allowlist = Some(vec!["mfa_verified".to_string()]);
+        engine.create_namespace(ns).await.unwrap();
+        assert!(engine.filter_context(&req, true).await.unwrap().is_none());
+
+        let strict = PolicyEngine::with_config(EngineConfig {
+            reject_disallowed_context: true,
+            ..Default::default()
+        });
+        assert!(matches!(strict.evaluate(&req).await, Err(PolicyError::ValidationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_policy_outside_active_window_is_excluded() {
+        let engine = PolicyEngine::new();
+        engine.seed_demo_policies().await;
+        let id = engine.evaluate(&checkout_get()).await.unwrap().policy_id.unwrap();
+        let now = chrono::Utc::now();
+
+        engine
+            .modify_policy(id, |p| {
+                p.active_from = Some(now + chrono::Duration::hours(1));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert!(!engine.evaluate(&checkout_get()).await.unwrap().allowed);
+
+        engine
+            .modify_policy(id, |p| {
+                p.active_from = Some(now - chrono::Duration::hours(2));
+                p.active_until = Some(now - chrono::Duration::hours(1));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert!(!engine.evaluate(&checkout_get()).await.unwrap().allowed);
+
+        // A schedule that excludes the current month keeps the policy out too
+        let other_month = chrono::Datelike::month(&now) % 12 + 1;
+        engine
+            .modify_policy(id, |p| {
+                p.active_until = None;
+                p.schedule = Some(format!("* * * {} *", other_month));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert!(!engine.evaluate(&checkout_get()).await.unwrap().allowed);
+
+        engine
+            .modify_policy(id, |p| {
+                p.schedule = Some("* * * * *".to_string());
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert!(engine.evaluate(&checkout_get()).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_policies_for_identity_matches_patterns_roles_and_exclusions() {
+        let engine = PolicyEngine::new();
+        let mut identity = Identity::new_workload("checkout", "production", "tetrate.io");
+        identity.labels.insert("role".to_string(), "reader, auditor".to_string());
+        let policy = |name: &str, kind: PolicyKind, content: &str, subjects: &[&str]| {
+            let mut p = Policy::new(name, "", kind, AccessModel::ZeroTrust, content, "production", "test");
+            p.subjects = subjects.iter().map(|s| s.to_string()).collect();
+            p
+        };
+        engine.add_policy(policy("wildcard", PolicyKind::Rego, "package t\nallow if { true }\n", &["spiffe://tetrate.io/ns/production/*"])).await.unwrap();
+        engine.add_policy(policy("by-role", PolicyKind::Rego, "package t\nallow if { true }\n", &["role:auditor"])).await.unwrap();
+        engine.add_policy(policy("other-role", PolicyKind::Rego, "package t\nallow if { true }\n", &["role:admin"])).await.unwrap();
+        let mut excluded = policy("excluded", PolicyKind::Rego, "package t\nallow if { true }\n", &[]);
+        excluded.excluded_subjects = vec![identity.spiffe_id.clone().unwrap()];
+        engine.add_policy(excluded).await.unwrap();
+        let deny = "spec:\n  action: DENY\n  rules:\n    - from:\n        - source:\n            namespaces: [\"production\"]\n";
+        engine.add_policy(policy("istio-deny", PolicyKind::IstioAuthz, deny, &[])).await.unwrap();
+
+        let mut found: Vec<(String, PolicyEffect, String)> = engine
+            .policies_for_identity(&identity)
+            .await
+            .into_iter()
+            .map(|g| (g.name, g.effect, g.matched_by))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            found,
+            [
+                ("by-role".to_string(), PolicyEffect::Allow, "role:auditor".to_string()),
+                ("istio-deny".to_string(), PolicyEffect::Deny, "source.principal".to_string()),
+                ("wildcard".to_string(), PolicyEffect::Allow, "spiffe://tetrate.io/ns/production/*".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validation_warns_on_unknown_or_foreign_spiffe_subjects() {
+        let checkout = Identity::new_workload("checkout", "production", "tetrate.io");
+        let engine = PolicyEngine::with_config(EngineConfig {
+            trust_domain: "tetrate.io".to_string(),
+            ..Default::default()
+        })
+        .with_identities(Arc::new(RwLock::new(vec![checkout.clone()])));
+        let mut policy = Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t\nallow if { true }", "production", "test");
+        policy.resources = vec!["inventory".to_string()];
+        policy.subjects = vec![
+            checkout.spiffe_id.clone().unwrap(),
+            "spiffe://tetrate.io/ns/production/*".to_string(),
+            "spiffe://tetrate.io/ns/production/sa/chekout".to_string(),
+            "spiffe://example.org/ns/production/sa/checkout".to_string(),
+            "spiffe://tetrate.io".to_string(),
+        ];
+
+        let result = engine.validate_policy(&policy);
+        assert!(result.passed);
+        assert_eq!(result.warnings.len(), 3, "{:?}", result.warnings);
+        assert!(result.warnings[0].contains("sa/chekout does not match any known identity"));
+        assert!(result.warnings[1].contains("trust domain example.org"));
+        assert!(result.warnings[2].contains("not a valid SPIFFE ID"));
+    }
+
+    #[tokio::test]
+    async fn test_effective_permissions_expand_wildcards_and_denies() {
+        let checkout = Identity::new_workload("checkout", "production", "tetrate.io");
+        let spiffe_id = checkout.spiffe_id.clone().unwrap();
+        let engine = PolicyEngine::new().with_identities(Arc::new(RwLock::new(vec![checkout.clone()])));
+        let mut api = Policy::new("api-access", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        api.status = PolicyStatus::Active;
+        api.subjects = vec![spiffe_id.clone()];
+        api.resources = vec!["zedid-api/*".to_string()];
+        api.actions = vec!["GET".to_string(), "DELETE".to_string()];
+        let api = engine.add_policy(api).await.unwrap();
+        let deny = "spec:\n  action: DENY\n  rules:\n    - to:\n        - operation:\n            methods: [\"DELETE\"]\n            paths: [\"zedid-api/admin\"]\n";
+        let mut admin = Policy::new("no-admin-deletes", "", PolicyKind::IstioAuthz, AccessModel::ZeroTrust, deny, "production", "test");
+        admin.status = PolicyStatus::Active;
+        admin.resources = vec!["zedid-api/admin".to_string()];
+        admin.actions = vec!["DELETE".to_string()];
+        engine.add_policy(admin).await.unwrap();
+        let mut other = Policy::new("cart-only", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        other.status = PolicyStatus::Active;
+        other.subjects = vec!["spiffe://tetrate.io/ns/production/sa/cart".to_string()];
+        other.resources = vec!["cart-service".to_string()];
+        engine.add_policy(other).await.unwrap();
+
+        let view = engine.effective_permissions("spiffe://TETRATE.io/ns/production/sa/checkout").await.unwrap();
+        assert_eq!((view.subject.as_str(), view.identity_id), (spiffe_id.as_str(), Some(checkout.id)));
+        let pairs: Vec<(&str, &str)> = view.permissions.iter().map(|p| (p.resource.as_str(), p.action.as_str())).collect();
+        assert_eq!(pairs, [("zedid-api/*", "GET"), ("zedid-api/*", "DELETE")]);
+        assert!(view.permissions.iter().all(|p| p.policy_id == api.id));
+        assert!(view.permissions[0].except.is_empty());
+        assert_eq!(view.permissions[1].except, ["DELETE zedid-api/admin"]);
+        assert_eq!(view.denied, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mixed_case_spiffe_subjects_match() {
+        let engine = PolicyEngine::new();
+        let mut policy = Policy::new("checkout-reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.status = PolicyStatus::Active;
+        policy.subjects = vec!["spiffe://Tetrate.io/ns/production/sa/checkout".to_string()];
+        policy.resources = vec!["inventory-service".to_string()];
+        let policy = engine.add_policy(policy).await.unwrap();
+
+        let shouting = PolicyDecisionRequest {
+            subject: "SPIFFE://TETRATE.IO//ns/production/sa/checkout/".to_string(),
+            ..checkout_get()
+        };
+        let allowed = engine.evaluate(&shouting).await.unwrap();
+        assert_eq!((allowed.allowed, allowed.policy_id), (true, Some(policy.id)));
+        // The path stays case-sensitive
+        let other = PolicyDecisionRequest { subject: "spiffe://tetrate.io/ns/production/sa/Checkout".to_string(), ..checkout_get() };
+        assert!(!engine.evaluate(&other).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_simulated_policy_requires_force_to_activate() {
+        let engine = PolicyEngine::new();
+        let mut demo = Policy::new("demo", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        demo.status = PolicyStatus::Disabled;
+        demo.is_simulated = true;
+        let demo = engine.add_policy(demo).await.unwrap();
+
+        assert!(matches!(engine.activate_policy(demo.id, false).await, Err(PolicyError::Conflict(_))));
+        let bulk = engine.activate_policies(&[demo.id], false).await;
+        assert_eq!(bulk.skipped[0].reason, "generated in simulation mode");
+
+        let forced = engine.activate_policy(demo.id, true).await.unwrap();
+        assert_eq!(forced.status, PolicyStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_activation_atomic_and_partial() {
+        let engine = PolicyEngine::new();
+        let mut reviewed = Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        reviewed.status = PolicyStatus::Disabled;
+        let reviewed = engine.add_policy(reviewed).await.unwrap();
+        let mut broken = Policy::new("broken", "", PolicyKind::Rego, AccessModel::ZeroTrust, "", "production", "test");
+        broken.status = PolicyStatus::Disabled;
+        let broken = engine.add_policy(broken).await.unwrap();
+
+        let aborted = engine.activate_policies(&[reviewed.id, broken.id], true).await;
+        assert!(aborted.aborted && aborted.activated.is_empty());
+        assert_eq!(aborted.skipped.len(), 2);
+        assert_eq!(engine.get_policy(reviewed.id).await.unwrap().status, PolicyStatus::Disabled);
+
+        let partial = engine.activate_policies(&[reviewed.id, broken.id], false).await;
+        assert_eq!(partial.activated, vec![reviewed.id]);
+        assert_eq!(partial.skipped[0].id, broken.id);
+        assert!(partial.skipped[0].reason.starts_with("validation failed"));
+        assert_eq!(engine.get_policy(reviewed.id).await.unwrap().status, PolicyStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_report_derives_and_samples_requests() {
+        let identities = Arc::new(RwLock::new(vec![
+            Identity::new_workload("checkout", "production", "tetrate.io"),
+            Identity::new_workload("cart", "production", "tetrate.io"),
+            Identity::new_workload("search", "production", "tetrate.io"),
+        ]));
+        let engine = PolicyEngine::new().with_identities(identities);
+        engine.seed_demo_policies().await;
+
+        // 3 identities × inventory-service × {GET, LIST}
+        let report = engine.evaluation_report("production", vec![], None).await.unwrap();
+        assert!(report.derived);
+        assert_eq!((report.candidates, report.rows.len(), report.allowed, report.denied), (6, 6, 2, 4));
+        let allowed: Vec<_> = report.rows.iter().filter(|r| r.allowed).map(|r| r.policy_name.as_deref()).collect();
+        assert_eq!(allowed, [Some("checkout-reads-inventory"); 2]);
+        assert_eq!(engine.metrics().get(metrics::EVALUATIONS_TOTAL), 0);
+
+        let sampled = engine.evaluation_report("production", vec![], Some(4)).await.unwrap();
+        assert_eq!((sampled.candidates, sampled.rows.len()), (6, 4));
+        let again = engine.evaluation_report("production", vec![], Some(4)).await.unwrap();
+        let key = |r: &EvaluationReport| r.rows.iter().map(|r| (r.subject.clone(), r.action.clone())).collect::<Vec<_>>();
+        assert_eq!(key(&sampled), key(&again));
+
+        let case = ReportCase {
+            subject: "spiffe://tetrate.io/ns/production/sa/checkout".to_string(),
+            resource: "inventory-service".to_string(),
+            action: "DELETE".to_string(),
+            context: Default::default(),
+        };
+        let explicit = engine.evaluation_report("production", vec![case.clone()], None).await.unwrap();
+        assert!(!explicit.derived && !explicit.rows[0].allowed);
+        let too_many = vec![case; MAX_REPORT_REQUESTS + 1];
+        assert!(matches!(engine.evaluation_report("production", too_many, None).await, Err(PolicyError::ValidationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_git_sync_upserts_reports_conflicts_and_is_read_only() {
+        let engine = PolicyEngine::new();
+        let rego = |content: &str| Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, content, "production", "alice");
+        let local = engine.add_policy(rego("package t\nallow if { true }")).await.unwrap();
+        let git = |name: &str, content: &str| {
+            let mut policy = Policy { name: name.to_string(), status: PolicyStatus::Active, ..rego(content) };
+            policy.subjects = vec!["spiffe://tetrate.io/ns/production/sa/checkout".to_string()];
+            policy.source = PolicySource::Git;
+            policy.source_path = Some(format!("production/{}.rego", name));
+            policy
+        };
+
+        let report = engine
+            .sync_git_policies("c1", vec![git("writes", "package w\nallow if { true }"), git("reads", "package r\nallow if { true }"), git("broken", "")])
+            .await;
+        assert_eq!(report.created, ["production/writes.rego"]);
+        assert_eq!((report.conflicts[0].path.as_str(), report.conflicts[0].policy_id), ("production/reads.rego", Some(local.id)));
+        assert_eq!(report.invalid[0].path, "production/broken.rego");
+        let synced = engine.list_policies(None).await.into_iter().find(|p| p.name == "writes").unwrap();
+        assert_eq!(synced.status, PolicyStatus::Active);
+        assert!(matches!(engine.update_policy_status(synced.id, PolicyStatus::Disabled).await, Err(PolicyError::Conflict(_))));
+        assert!(matches!(engine.set_explanation(synced.id, "edited".to_string()).await, Err(PolicyError::Conflict(_))));
+
+        let changed = vec![git("writes", "package w\nallow if { false }")];
+        let report = engine.sync_git_policies("c2", changed.clone()).await;
+        assert_eq!(report.updated, ["production/writes.rego"]);
+        let updated = engine.get_policy(synced.id).await.unwrap();
+        assert_eq!((updated.version, updated.content.as_str()), (2, "package w\nallow if { false }"));
+        assert_eq!(engine.sync_git_policies("c3", changed).await.unchanged, 1);
+
+        let report = engine.sync_git_policies("c4", vec![]).await;
+        assert_eq!(report.removed, ["production/writes.rego"]);
+        assert_eq!(engine.get_policy(synced.id).await.unwrap().status, PolicyStatus::Disabled);
+        assert_eq!(engine.get_policy(local.id).await.unwrap().status, local.status);
+    }
+
+    #[tokio::test]
+    async fn test_import_keeps_active_status_only_through_activation_gates() {
+        let bundle = || {
+            let mut valid = Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+            valid.status = PolicyStatus::Active;
+            let mut invalid = Policy { name: "broken".to_string(), content: "allow".to_string(), ..valid.clone() };
+            invalid.id = Uuid::new_v4();
+            PolicyBundle {
+                schema_version: POLICY_BUNDLE_SCHEMA_VERSION,
+                exported_at: chrono::Utc::now(),
+                namespace: None,
+                policies: vec![valid, invalid],
+            }
+        };
+        let statuses = |engine: PolicyEngine| async move {
+            let results = engine.import_bundle(bundle(), false, true).await.unwrap();
+            assert!(results.iter().all(|r| r.imported));
+            let mut statuses = vec![];
+            for result in results {
+                statuses.push(engine.get_policy(result.id.unwrap()).await.unwrap().status);
+            }
+            statuses
+        };
+
+        // Without direct activation, Active policies still need review
+        assert_eq!(statuses(PolicyEngine::new()).await, [PolicyStatus::Draft, PolicyStatus::Draft]);
+        let direct = PolicyEngine::with_config(EngineConfig {
+            allow_direct_activation: true,
+            ..Default::default()
+        });
+        assert_eq!(statuses(direct).await, [PolicyStatus::Active, PolicyStatus::Draft]);
+    }
+
+    #[tokio::test]
+    async fn test_git_sync_rejects_unannotated_files() {
+        let engine = PolicyEngine::new();
+        let mut baseline = Policy::new(
+            "baseline",
+            "",
+            PolicyKind::Rego,
+            AccessModel::ZeroTrust,
+            "package zedid.baseline\ndefault allow := false\n",
+            "production",
+            "git",
+        );
+        baseline.status = PolicyStatus::Active;
+        baseline.source = PolicySource::Git;
+        baseline.source_path = Some("production/baseline.rego".to_string());
+
+        let report = engine.sync_git_policies("c1", vec![baseline]).await;
+        assert!(report.created.is_empty());
+        assert_eq!(report.invalid[0].path, "production/baseline.rego");
+        assert!(engine.list_policies(None).await.is_empty());
+        assert!(!engine.evaluate(&checkout_get()).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_group_activation_is_all_or_nothing() {
+        let engine = PolicyEngine::new();
+        let mut reviewed = Policy::new("reads", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        reviewed.status = PolicyStatus::Disabled;
+        reviewed.subjects = vec!["spiffe://tetrate.io/ns/production/sa/checkout".to_string()];
+        let reviewed = engine.add_policy(reviewed).await.unwrap();
+        let draft = engine
+            .add_policy(Policy::new("draft", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test"))
+            .await
+            .unwrap();
+        let group = engine
+            .create_group(PolicyGroup::new("pci", "", vec![reviewed.id, draft.id]))
+            .await
+            .unwrap();
+
+        // The draft blocks activation, and the disabled member is left untouched
+        let result = engine.update_group_status(group.id, PolicyStatus::Active).await;
+        assert!(matches!(result, Err(PolicyError::Conflict(_))));
+        assert_eq!(engine.get_policy(reviewed.id).await.unwrap().status, PolicyStatus::Disabled);
+
+        engine.update_group_members(group.id, vec![], vec![draft.id]).await.unwrap();
+        let activated = engine.update_group_status(group.id, PolicyStatus::Active).await.unwrap();
+        assert_eq!(activated.len(), 1);
+
+        let scoped = PolicyDecisionRequest { group: Some(group.id), ..checkout_get() };
+        assert!(engine.evaluate(&scoped).await.unwrap().allowed);
+        let other = engine.create_group(PolicyGroup::new("empty", "", vec![])).await.unwrap();
+        let scoped = PolicyDecisionRequest { group: Some(other.id), ..checkout_get() };
+        assert!(!engine.evaluate(&scoped).await.unwrap().allowed);
+    }
+
+    #[test]
+    fn test_search_snippet_is_case_insensitive() {
+        let snippet = search_snippet("allow if {\n    input.resource == \"Inventory-Service\"\n}", "inventory-service").unwrap();
+        assert_eq!(snippet, "allow if {     input.resource == \"**Inventory-Service**\" }");
+
+        let long = format!("{}checkout{}", "x".repeat(100), "y".repeat(100));
+        let snippet = search_snippet(&long, "CHECKOUT").unwrap();
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(search_snippet("payments", "inventory").is_none());
+        assert!(search_snippet("payments", "  ").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_exclusion_overrides_wildcard_inclusion() {
+        let engine = PolicyEngine::new();
+        let mut policy = Policy::new("all-but-payments", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.status = PolicyStatus::Active;
+        policy.subjects = vec!["spiffe://tetrate.io/ns/production/*".to_string()];
+        policy.excluded_subjects = vec!["spiffe://tetrate.io/ns/production/sa/payment-service".to_string()];
+        policy.resources = vec!["inventory-service".to_string()];
+        engine.add_policy(policy).await.unwrap();
+
+        assert!(engine.evaluate(&checkout_get()).await.unwrap().allowed);
+
+        let payments = PolicyDecisionRequest {
+            subject: "spiffe://tetrate.io/ns/production/sa/payment-service".to_string(),
+            ..checkout_get()
+        };
+        let decision = engine.evaluate(&payments).await.unwrap();
+        assert!(!decision.allowed);
+        assert_eq!(decision.reason, "No matching policy rule — implicit deny");
+    }
+
+    #[tokio::test]
+    async fn test_namespace_default_policy_applies_last_and_deny_overrides() {
+        let engine = PolicyEngine::new();
+        engine.create_namespace(Namespace::new("production", "", None)).await.unwrap();
+        let mut allow = Policy::new("inventory-read", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        allow.status = PolicyStatus::Active;
+        allow.resources = vec!["inventory-service".to_string()];
+        let allow = engine.add_policy(allow).await.unwrap();
+        assert!(engine.evaluate(&checkout_get()).await.unwrap().allowed);
+
+        // The baseline lives in its own namespace and is cached against it
+        let deny = "spec:\n  action: DENY\n  rules:\n    - from:\n        - source:\n            namespaces: [\"production\"]\n";
+        let mut baseline = Policy::new("baseline-deny", "", PolicyKind::IstioAuthz, AccessModel::ZeroTrust, deny, "baseline", "test");
+        baseline.status = PolicyStatus::Active;
+        let baseline = engine.add_policy(baseline).await.unwrap();
+        let namespace = engine.set_default_policy("production", Some(baseline.id)).await.unwrap();
+        assert_eq!(namespace.default_policy_id, Some(baseline.id));
+
+        let denied = engine.evaluate(&checkout_get()).await.unwrap();
+        assert!(!denied.allowed);
+        assert_eq!(denied.policy_id, Some(baseline.id));
+
+        engine.update_policy_status(baseline.id, PolicyStatus::Disabled).await.unwrap();
+        let allowed = engine.evaluate(&checkout_get()).await.unwrap();
+        assert!(allowed.allowed && !allowed.cached);
+        assert_eq!(allowed.policy_id, Some(allow.id));
+
+        assert!(matches!(
+            engine.set_default_policy("production", Some(Uuid::new_v4())).await,
+            Err(PolicyError::NotFound(_))
+        ));
+        assert!(matches!(engine.set_default_policy("staging", None).await, Err(PolicyError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_combining_algorithms_decide_same_policies_differently() {
+        let engine = PolicyEngine::new();
+        engine.create_namespace(Namespace::new("production", "", None)).await.unwrap();
+        let deny = "spec:\n  action: DENY\n  rules:\n    - to:\n        - operation:\n            methods: [\"DELETE\"]\n";
+        let mut deny = Policy::new("deny-deletes", "", PolicyKind::IstioAuthz, AccessModel::ZeroTrust, deny, "production", "test");
+        deny.status = PolicyStatus::Active;
+        let deny = engine.add_policy(deny).await.unwrap();
+        let mut allow = Policy::new("inventory-all", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        allow.status = PolicyStatus::Active;
+        allow.resources = vec!["inventory-service".to_string()];
+        allow.actions = vec!["*".to_string()];
+        let allow = engine.add_policy(allow).await.unwrap();
+
+        let delete = PolicyDecisionRequest { action: "DELETE".to_string(), ..checkout_get() };
+        let unmatched = PolicyDecisionRequest { resource: "billing-service".to_string(), ..checkout_get() };
+        let set = |algorithm| {
+            let engine = &engine;
+            async move {
+                let update = crate::models::UpdateNamespaceRequest {
+                    description: None,
+                    owner: None,
+                    default_trust_level: None,
+                    enforcement_mode: None,
+                    variables: None,
+                    reason_verbosity: None,
+                    context_allowlist: None,
+                    combining_algorithm: Some(algorithm),
+                };
+                engine.update_namespace("production", update).await.unwrap();
+            }
+        };
+
+        let denied = engine.evaluate(&delete).await.unwrap();
+        assert_eq!((denied.allowed, denied.policy_id), (false, Some(deny.id)));
+        assert!(!engine.evaluate(&unmatched).await.unwrap().allowed);
+
+        set(CombiningAlgorithm::AllowOverrides).await;
+        let allowed = engine.evaluate(&delete).await.unwrap();
+        assert_eq!((allowed.allowed, allowed.policy_id, allowed.cached), (true, Some(allow.id), false));
+
+        // Store order decides: the deny was added first
+        set(CombiningAlgorithm::FirstApplicable).await;
+        assert_eq!(engine.evaluate(&delete).await.unwrap().policy_id, Some(deny.id));
+
+        set(CombiningAlgorithm::PermitUnlessDeny).await;
+        let permitted = engine.evaluate(&unmatched).await.unwrap();
+        assert_eq!((permitted.allowed, permitted.policy_id), (true, None));
+        assert!(!engine.evaluate(&delete).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_policy_fails_closed_when_exceeded() {
+        let engine = PolicyEngine::new();
+        let mut namespace = Namespace::new("production", "", None);
+        namespace.enforcement_mode = EnforcementMode::Monitor;
+        engine.create_namespace(namespace).await.unwrap();
+        let mut policy = Policy::new("inventory-read", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.status = PolicyStatus::Active;
+        policy.resources = vec!["inventory-service".to_string()];
+        policy.rate_limit = Some(2);
+        let policy = engine.add_policy(policy).await.unwrap();
+
+        for _ in 0..2 {
+            let allowed = engine.evaluate(&checkout_get()).await.unwrap();
+            assert!(allowed.allowed && !allowed.throttled);
+        }
+        // Throttling is enforced even though the namespace only monitors
+        let throttled = engine.evaluate(&checkout_get()).await.unwrap();
+        assert!(!throttled.allowed && throttled.throttled);
+        assert_eq!(throttled.policy_id, Some(policy.id));
+        assert!(throttled.reason.contains("rate limit of 2/s exceeded"));
+        let label = format!("{}{{policy_id=\"{}\"}}", metrics::POLICY_THROTTLED_TOTAL, policy.id);
+        assert_eq!(engine.metrics().get(&label), 1);
+        assert_eq!(engine.policy_stats(policy.id).await.unwrap().evaluations, 2);
+
+        // What-if evaluation does not count against the limit
+        assert!(engine.evaluate_quietly(&checkout_get()).await.unwrap().allowed);
+
+        tokio::time::sleep(Duration::from_millis(1010)).await;
+        assert!(engine.evaluate(&checkout_get()).await.unwrap().allowed);
+    }
+
+    #[tokio::test]
+    async fn test_coverage_heatmap_counts_governing_policies() {
+        let engine = PolicyEngine::new();
+        engine.create_namespace(Namespace::new("staging", "", None)).await.unwrap();
+        let allow = "package t\n\nallow if {\n    input.action == \"GET\"\n}\n";
+        let mut reads = Policy::new("inventory-read", "", PolicyKind::Rego, AccessModel::ZeroTrust, allow, "production", "test");
+        reads.status = PolicyStatus::Active;
+        reads.resources = vec!["inventory-service".to_string(), "orders/*".to_string()];
+        engine.add_policy(reads).await.unwrap();
+        let deny = "spec:\n  action: DENY\n  rules:\n    - to:\n        - operation:\n            methods: [\"DELETE\"]\n";
+        let mut guard = Policy::new("no-deletes", "", PolicyKind::IstioAuthz, AccessModel::ZeroTrust, deny, "system", "test");
+        guard.status = PolicyStatus::Active;
+        guard.resources = vec!["payments-service".to_string()];
+        engine.add_policy(guard).await.unwrap();
+        let mut draft = Policy::new("draft", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "staging", "test");
+        draft.resources = vec!["billing-service".to_string()];
+        engine.add_policy(draft).await.unwrap();
+
+        let heatmap = engine.coverage_heatmap().await;
+        assert_eq!(heatmap.namespaces, ["production", "staging"]);
+        // Wildcards are not cells, but drafts still name resources
+        assert_eq!(heatmap.resources, ["billing-service", "inventory-service", "payments-service"]);
+        let cell = |ns: usize, resource: usize| {
+            let c = &heatmap.cells[ns][resource];
+            (c.policies, c.denies, c.coverage)
+        };
+        assert_eq!(cell(0, 0), (0, 0, CellCoverage::Uncovered));
+        assert_eq!(cell(0, 1), (1, 0, CellCoverage::AllowOnly));
+        assert_eq!(cell(0, 2), (1, 1, CellCoverage::HasDenies));
+        assert_eq!(cell(1, 1), (0, 0, CellCoverage::Uncovered));
+        assert_eq!(cell(1, 2), (1, 1, CellCoverage::HasDenies));
+    }
+
+    #[test]
+    fn test_pattern_matches_wildcards() {
+        assert!(pattern_matches("*", "anything"));
+        assert!(pattern_matches("zedid-api/*", "zedid-api/policies"));
+        assert!(!pattern_matches("zedid-api/*", "inventory-service"));
+        assert!(!pattern_matches("zedid-api/*", "zedid-api-admin/x"));
+        assert!(pattern_matches("inventory-service", "inventory-service"));
+    }
+
+    #[test]
+    fn test_redact_context_masks_listed_fields() {
+        let context = serde_json::json!({"mfa_verified": true, "session_token": "abc", "region": "eu"});
+        let redacted = redact_context(context, &["session_token".to_string(), "missing".to_string()]);
+        assert_eq!(redacted["session_token"], "[REDACTED]");
+        assert_eq!(redacted["mfa_verified"], true);
+        assert!(redacted.get("missing").is_none());
+    }
 }
-"#;
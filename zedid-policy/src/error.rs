@@ -20,9 +20,21 @@ pub enum PolicyError {
     #[error("Policy conflict: {0}")]
     Conflict(String),
 
+    #[error("Policy quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    #[error("Request too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
     #[error("HTTP error: {0}")]
     HttpError(String),
+
+    #[error("Policy source error: {0}")]
+    SourceError(String),
 }
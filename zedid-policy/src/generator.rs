@@ -1,22 +1,147 @@
 use crate::error::PolicyError;
 use crate::models::{
-    AccessModel, GeneratePolicyRequest, GeneratePolicyResponse, Policy, PolicyKind, PolicyStatus,
+    AccessModel, ExplainPolicyResponse, GeneratePolicyRequest, GeneratePolicyResponse, Policy,
+    PolicyDecisionResponse, PolicyKind, PolicySource, PolicyStatus,
 };
 use crate::tars::TarsClient;
 use crate::engine::PolicyEngine;
-use std::time::Instant;
-use tracing::info;
+use crate::metrics;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 use uuid::Uuid;
 
+/// Deciding policy (`None` for a default deny) and deny reason
+type DenialKey = (Option<Uuid>, String);
+
 /// AI-powered policy generator using TARS for LLM routing
 pub struct PolicyGenerator {
     tars: TarsClient,
     engine: std::sync::Arc<PolicyEngine>,
+    fallback: GenerationFallback,
+    /// How long a result is reused for an identical request (zero disables reuse)
+    dedup_window: Duration,
+    /// Recent results by `dedup_key`, with when they were generated
+    recent: Mutex<HashMap<String, (Instant, GeneratePolicyResponse)>>,
+    /// Content hash each policy was last explained for, by policy id
+    explained: Mutex<HashMap<Uuid, u64>>,
+    /// Denial explanations by (deciding policy, reason), with the policy
+    /// content hash they were written for
+    denial_explanations: Mutex<HashMap<DenialKey, (u64, String)>>,
+    limits: GenerationLimits,
+}
+
+/// Bounds on caller input embedded in the generation prompt; 0 disables a
+/// limit. Over-long fields are truncated; a prompt still over the token
+/// budget is rejected before TARS is called.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct GenerationLimits {
+    /// Characters of `intent` kept
+    pub max_intent_chars: usize,
+    /// Entries kept in each of `subjects`, `resources` and `actions`
+    pub max_hint_items: usize,
+    /// Characters kept per hint entry
+    pub max_hint_chars: usize,
+    /// Largest estimated prompt size, in tokens
+    pub max_prompt_tokens: usize,
+}
+
+impl Default for GenerationLimits {
+    fn default() -> Self {
+        Self { max_intent_chars: 2000, max_hint_items: 50, max_hint_chars: 256, max_prompt_tokens: 4000 }
+    }
+}
+
+impl GenerationLimits {
+    /// `req` cut down to the limits, with the names of the fields that were cut
+    fn apply(&self, req: &GeneratePolicyRequest) -> (GeneratePolicyRequest, Vec<String>) {
+        let mut bounded = req.clone();
+        let mut truncated = vec![];
+        if let Some(intent) = truncate_chars(&req.intent, self.max_intent_chars) {
+            bounded.intent = intent;
+            truncated.push("intent".to_string());
+        }
+        for (field, hints) in [
+            ("subjects", &mut bounded.subjects),
+            ("resources", &mut bounded.resources),
+            ("actions", &mut bounded.actions),
+        ] {
+            let Some(list) = hints else { continue };
+            let mut cut = self.max_hint_items > 0 && list.len() > self.max_hint_items;
+            if cut {
+                list.truncate(self.max_hint_items);
+            }
+            for item in list.iter_mut() {
+                if let Some(short) = truncate_chars(item, self.max_hint_chars) {
+                    *item = short;
+                    cut = true;
+                }
+            }
+            if cut {
+                truncated.push(field.to_string());
+            }
+        }
+        (bounded, truncated)
+    }
+}
+
+/// `text` cut to `max` characters, or `None` if it already fits (or `max` is 0)
+fn truncate_chars(text: &str, max: usize) -> Option<String> {
+    (max > 0 && text.chars().count() > max).then(|| text.chars().take(max).collect())
+}
+
+/// Rough token count for budget checks: about four characters per token
+fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// What generation does when a live TARS request fails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GenerationFallback {
+    /// Fail the request (fail-fast; the production default)
+    #[default]
+    Error,
+    /// Return a simulated policy flagged as degraded
+    Simulate,
 }
 
 impl PolicyGenerator {
     pub fn new(tars: TarsClient, engine: std::sync::Arc<PolicyEngine>) -> Self {
-        Self { tars, engine }
+        Self {
+            tars,
+            engine,
+            fallback: GenerationFallback::default(),
+            dedup_window: Duration::ZERO,
+            recent: Mutex::new(HashMap::new()),
+            explained: Mutex::new(HashMap::new()),
+            denial_explanations: Mutex::new(HashMap::new()),
+            limits: GenerationLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: GenerationLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_fallback(mut self, fallback: GenerationFallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Answer identical requests within `window` from the earlier result
+    pub fn with_dedup_window(mut self, window: Duration) -> Self {
+        self.dedup_window = window;
+        self
+    }
+
+    /// The TARS client generation goes through
+    pub fn tars(&self) -> &TarsClient {
+        &self.tars
     }
 
     /// Generate a policy from natural language intent
@@ -26,14 +151,50 @@ impl PolicyGenerator {
         created_by: &str,
     ) -> Result<GeneratePolicyResponse, PolicyError> {
         let start = Instant::now();
-        info!("Generating {} policy for intent: {}", format!("{:?}", req.kind), req.intent);
+        // Generated policies are saved as Drafts, so don't spend tokens on one that can't be
+        self.engine.ensure_capacity(&req.namespace, true).await?;
 
-        // Build the prompt for the LLM
+        let (bounded, input_truncated) = self.limits.apply(req);
+        let req = &bounded;
+        if !input_truncated.is_empty() {
+            warn!(
+                fields = %input_truncated.join(", "),
+                max_intent_chars = self.limits.max_intent_chars,
+                max_hint_items = self.limits.max_hint_items,
+                max_hint_chars = self.limits.max_hint_chars,
+                "Generation input truncated"
+            );
+        }
         let prompt = self.build_prompt(req);
+        let estimated_tokens = estimate_tokens(&prompt);
+        if self.limits.max_prompt_tokens > 0 && estimated_tokens > self.limits.max_prompt_tokens {
+            return Err(PolicyError::PayloadTooLarge(format!(
+                "generation prompt is about {} tokens, over the {} token limit; shorten the intent or hints",
+                estimated_tokens, self.limits.max_prompt_tokens
+            )));
+        }
+
+        let key = dedup_key(req);
+        if let Some(mut reused) = self.recent_result(&key) {
+            info!("Reusing recent generation for identical intent: {}", req.intent);
+            self.engine.metrics().incr(metrics::GENERATION_REUSED_TOTAL);
+            reused.reused = true;
+            return Ok(reused);
+        }
+        info!("Generating {} policy for intent: {}", format!("{:?}", req.kind), req.intent);
 
         // Route through TARS to get the best LLM for policy generation
-        let (generated_content, model_used, tokens_used) =
-            self.tars.generate_policy(&prompt, &req.kind).await?;
+        let (generated_content, model_used, routing_reason, tokens_used, degraded) =
+            match self.tars.generate_policy(&prompt, &req.kind).await {
+                Ok((content, model, reason, tokens)) => (content, model, reason, tokens, false),
+                Err(e) if self.fallback == GenerationFallback::Simulate => {
+                    warn!("TARS generation failed, falling back to a simulated policy: {}", e);
+                    self.engine.metrics().incr(metrics::GENERATION_FALLBACKS_TOTAL);
+                    let (content, model, reason, tokens) = self.tars.simulate_fallback(&prompt, &req.kind, &e);
+                    (content, model, reason, tokens, true)
+                }
+                Err(e) => return Err(e),
+            };
 
         // Parse the generated content
         let (policy_code, explanation) = parse_llm_response(&generated_content, &req.kind);
@@ -53,6 +214,8 @@ impl PolicyGenerator {
             subjects: req.subjects.clone().unwrap_or_default(),
             resources: req.resources.clone().unwrap_or_default(),
             actions: req.actions.clone().unwrap_or_default(),
+            excluded_subjects: vec![],
+            excluded_resources: vec![],
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             created_by: created_by.to_string(),
@@ -60,12 +223,42 @@ impl PolicyGenerator {
             tags: vec!["ai-generated".to_string()],
             ai_generated: true,
             ai_model_used: Some(model_used.clone()),
+            is_simulated: self.tars.is_simulated() || degraded,
             validation_passed: false,
+            reviewers: vec![],
+            approved_by: None,
+            review_comments: vec![],
+            requires_manual_review: false,
+            variables: HashMap::new(),
+            active_from: None,
+            active_until: None,
+            schedule: None,
+            rate_limit: None,
+            disabled_statements: vec![],
+            tests: vec![],
+            source: PolicySource::Local,
+            source_path: None,
+            source_commit: None,
         };
 
-        // Validate the generated policy
+        if degraded {
+            policy.tags.push("degraded".to_string());
+        }
+
+        // Guardrails: LLM output is untrusted until a human has looked at it
+        let guardrail_findings = guardrail_findings(&policy.content, &req.kind, req);
+        if !guardrail_findings.is_empty() {
+            warn!(
+                "Generated policy flagged for manual review: {}",
+                guardrail_findings.join("; ")
+            );
+            policy.requires_manual_review = true;
+            policy.tags.push("requires-review".to_string());
+        }
+
+        // Validate the generated policy — flagged output never auto-passes
         let validation = self.engine.validate_policy(&policy);
-        policy.validation_passed = validation.passed;
+        policy.validation_passed = validation.passed && !policy.requires_manual_review;
 
         let elapsed = start.elapsed().as_millis() as u64;
         info!(
@@ -73,13 +266,92 @@ impl PolicyGenerator {
             elapsed, model_used, validation.passed
         );
 
-        Ok(GeneratePolicyResponse {
+        let response = GeneratePolicyResponse {
             policy,
             validation_result: validation,
             generation_time_ms: elapsed,
             model_used,
+            routing_reason,
             tokens_used,
-        })
+            guardrail_findings,
+            degraded,
+            reused: false,
+            input_truncated,
+        };
+        // A degraded fallback is not worth repeating; the next request retries TARS
+        if !self.dedup_window.is_zero() && !degraded {
+            let mut recent = self.recent.lock().unwrap();
+            recent.retain(|_, (at, _)| at.elapsed() < self.dedup_window);
+            recent.insert(key, (Instant::now(), response.clone()));
+        }
+        Ok(response)
+    }
+
+    /// Ask TARS for a fresh plain-language explanation of a stored policy's
+    /// current content (the policy itself is not regenerated). Skipped when
+    /// the content has not changed since it was last explained.
+    pub async fn explain(&self, id: Uuid) -> Result<ExplainPolicyResponse, PolicyError> {
+        let policy = self.engine.get_policy(id).await.ok_or_else(|| PolicyError::NotFound(id.to_string()))?;
+        let hash = content_hash(&policy);
+        let unchanged = self.explained.lock().unwrap().get(&id) == Some(&hash);
+        if unchanged && !policy.explanation.is_empty() {
+            return Ok(ExplainPolicyResponse { policy, model_used: "cached".to_string(), cached: true });
+        }
+
+        let (explanation, model_used) = if self.tars.is_simulated() {
+            (simulated_explanation(&policy), "simulation-mode".to_string())
+        } else {
+            info!("Explaining policy {} ({})", policy.name, policy.id);
+            let (content, model, _, _) = self.tars.generate_policy(&explain_prompt(&policy), &policy.kind).await?;
+            (parse_explanation(&content), model)
+        };
+
+        let policy = self.engine.set_explanation(id, explanation).await?;
+        self.explained.lock().unwrap().insert(id, hash);
+        Ok(ExplainPolicyResponse { policy, model_used, cached: false })
+    }
+
+    /// Plain-language explanation of why `decision` was denied, for end users,
+    /// from its reason and the deciding policy's content. Explanations are
+    /// reused per (policy, reason) until the policy content changes; in
+    /// simulation mode a templated explanation is returned.
+    pub async fn explain_denial(&self, decision: &PolicyDecisionResponse) -> Result<String, PolicyError> {
+        let policy = match decision.policy_id {
+            Some(id) => self.engine.get_policy(id).await,
+            None => None,
+        };
+        if self.tars.is_simulated() {
+            return Ok(simulated_denial_explanation(&decision.reason, policy.as_ref()));
+        }
+
+        let key = (decision.policy_id, decision.reason.clone());
+        let hash = policy.as_ref().map(content_hash).unwrap_or_default();
+        if let Some((known, explanation)) = self.denial_explanations.lock().unwrap().get(&key) {
+            if *known == hash {
+                return Ok(explanation.clone());
+            }
+        }
+
+        let kind = policy.as_ref().map_or(PolicyKind::Rego, |p| p.kind.clone());
+        let prompt = denial_prompt(&decision.reason, policy.as_ref());
+        let (content, _, _, _) = self.tars.generate_policy(&prompt, &kind).await?;
+        let explanation = parse_explanation(&content);
+        self.denial_explanations
+            .lock()
+            .unwrap()
+            .insert(key, (hash, explanation.clone()));
+        Ok(explanation)
+    }
+
+    fn recent_result(&self, key: &str) -> Option<GeneratePolicyResponse> {
+        if self.dedup_window.is_zero() {
+            return None;
+        }
+        let recent = self.recent.lock().unwrap();
+        recent
+            .get(key)
+            .filter(|(at, _)| at.elapsed() < self.dedup_window)
+            .map(|(_, response)| response.clone())
     }
 
     fn build_prompt(&self, req: &GeneratePolicyRequest) -> String {
@@ -173,6 +445,185 @@ fn parse_llm_response(response: &str, _kind: &PolicyKind) -> (String, String) {
     (response.to_string(), "AI-generated policy".to_string())
 }
 
+fn content_hash(policy: &Policy) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    format!("{:?}", policy.kind).hash(&mut hasher);
+    policy.content.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn explain_prompt(policy: &Policy) -> String {
+    format!(
+        r#"You are ZedID, an expert identity and access management policy reviewer.
+
+Explain the following {kind:?} policy in plain language for a non-technical reader:
+who it applies to, what it allows or denies, and under which conditions.
+Do not rewrite or improve the policy.
+
+POLICY:
+{content}
+
+OUTPUT FORMAT:
+---EXPLANATION---
+[The explanation here]
+---END---"#,
+        kind = policy.kind,
+        content = policy.content,
+    )
+}
+
+fn denial_prompt(reason: &str, policy: Option<&Policy>) -> String {
+    let policy = match policy {
+        Some(p) => format!("DECIDING POLICY ({kind:?}, \"{name}\"):\n{content}", kind = p.kind, name = p.name, content = p.content),
+        None => "DECIDING POLICY: none — no policy allowed the request, so it was denied by default".to_string(),
+    };
+    format!(
+        r#"You are ZedID, an identity and access management assistant.
+
+A request was denied. Explain to a non-technical end user, in two or three
+sentences, why access was denied and what would typically need to change for
+it to be allowed. Do not quote policy code, and do not mention specific
+identities: the explanation is shown for every request denied for this reason.
+
+DENIAL REASON:
+{reason}
+
+{policy}
+
+OUTPUT FORMAT:
+---EXPLANATION---
+[The explanation here]
+---END---"#
+    )
+}
+
+fn simulated_denial_explanation(reason: &str, policy: Option<&Policy>) -> String {
+    match policy {
+        Some(p) => format!(
+            "Simulated explanation (no TARS API key configured). Your request was denied by the \"{}\" policy in namespace {} ({}). Ask the owner of that policy if you need this access.",
+            p.name, p.namespace, reason,
+        ),
+        None => format!(
+            "Simulated explanation (no TARS API key configured). Your request was denied because no policy allows it ({}). Access must be granted by a policy before it is allowed.",
+            reason,
+        ),
+    }
+}
+
+/// The text between the explanation markers, or the whole reply without them
+fn parse_explanation(response: &str) -> String {
+    let body = match response.find("---EXPLANATION---") {
+        Some(start) => &response[start + 17..],
+        None => response,
+    };
+    let body = body.find("---END---").map_or(body, |end| &body[..end]);
+    body.trim().to_string()
+}
+
+fn simulated_explanation(policy: &Policy) -> String {
+    let list = |values: &[String]| if values.is_empty() { "any".to_string() } else { values.join(", ") };
+    format!(
+        "Simulated explanation (no TARS API key configured). This {:?} policy in namespace {} covers subjects: {}; resources: {}; actions: {}.",
+        policy.kind,
+        policy.namespace,
+        list(&policy.subjects),
+        list(&policy.resources),
+        list(&policy.actions),
+    )
+}
+
+/// Detect generated content that widens access beyond what was asked for:
+/// a dropped default-deny, unconditional allows, or wildcard grants and
+/// subjects that were not part of the original request.
+fn guardrail_findings(
+    content: &str,
+    kind: &PolicyKind,
+    req: &GeneratePolicyRequest,
+) -> Vec<String> {
+    let mut findings = vec![];
+    // Whitespace-insensitive view so `allow if { true }` and `allow if {true}` match alike
+    let compact: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+
+    match kind {
+        PolicyKind::Rego => {
+            if !compact.contains("defaultallow:=false") && !compact.contains("defaultallow=false") {
+                findings
+                    .push("Missing `default allow := false` (default-deny removed)".to_string());
+            }
+            let unconditional = [
+                "allow{true}",
+                "allowif{true}",
+                "defaultallow:=true",
+                "defaultallow=true",
+            ];
+            let has_bare_true = content.lines().any(|line| {
+                let line: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+                line == "allow:=true" || line == "allow=true"
+            });
+            if has_bare_true || unconditional.iter().any(|p| compact.contains(p)) {
+                findings.push("Unconditional allow rule".to_string());
+            }
+        }
+        PolicyKind::Cedar if compact.contains("permit(principal,action,resource);") => {
+            findings.push(
+                "Unconstrained Cedar permit for all principals/actions/resources".to_string(),
+            );
+        }
+        _ => {}
+    }
+
+    let wildcard_requested = req
+        .actions
+        .as_ref()
+        .is_some_and(|a| a.iter().any(|a| a == "*"));
+    let wildcard_patterns = [
+        "action==\"*\"",
+        "actions:[\"*\"]",
+        "methods:[\"*\"]",
+        "verbs:[\"*\"]",
+    ];
+    if !wildcard_requested && wildcard_patterns.iter().any(|p| compact.contains(p)) {
+        findings.push("Grants wildcard action `*` that was not requested".to_string());
+    }
+
+    if let Some(subjects) = &req.subjects {
+        for id in spiffe_ids_in(content) {
+            let requested = subjects.iter().any(|s| {
+                s == &id || (s.ends_with("/*") && id.starts_with(s.trim_end_matches('*')))
+            });
+            if !requested {
+                findings.push(format!("References subject not in request: {}", id));
+            }
+        }
+    }
+
+    findings
+}
+
+/// SPIFFE IDs appearing anywhere in the content (deduplicated, in order)
+fn spiffe_ids_in(content: &str) -> Vec<String> {
+    let mut ids: Vec<String> = vec![];
+    for (start, _) in content.match_indices("spiffe://") {
+        let id: String = content[start..]
+            .chars()
+            .take_while(|c| !c.is_whitespace() && !matches!(c, '"' | '\'' | ',' | ']' | ')' | '}'))
+            .collect();
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+/// Requests that differ only in intent case or whitespace are identical
+fn dedup_key(req: &GeneratePolicyRequest) -> String {
+    let intent = req.intent.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    format!(
+        "{}|{:?}|{:?}|{}|{:?}|{:?}|{:?}",
+        intent, req.kind, req.access_model, req.namespace, req.subjects, req.resources, req.actions
+    )
+}
+
 fn derive_policy_name(intent: &str) -> String {
     // Convert intent to a slug-like policy name
     let words: Vec<&str> = intent.split_whitespace().take(5).collect();
@@ -183,3 +634,122 @@ fn derive_policy_name(intent: &str) -> String {
         .collect::<String>();
     format!("policy-{}", name)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(subjects: Option<Vec<String>>) -> GeneratePolicyRequest {
+        GeneratePolicyRequest {
+            intent: "Allow checkout to read inventory".to_string(),
+            kind: PolicyKind::Rego,
+            access_model: AccessModel::ZeroTrust,
+            namespace: "production".to_string(),
+            subjects,
+            resources: None,
+            actions: Some(vec!["GET".to_string()]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tars_failure_falls_back_to_flagged_simulation() {
+        let engine = std::sync::Arc::new(PolicyEngine::new());
+        // Live mode against a closed port fails at connect time
+        let tars = || TarsClient::new("http://127.0.0.1:9", Some("key".to_string()));
+
+        let strict = PolicyGenerator::new(tars(), engine.clone());
+        assert!(matches!(strict.generate(&request(None), "test").await, Err(PolicyError::TarsError(_))));
+
+        let lenient = PolicyGenerator::new(tars(), engine.clone()).with_fallback(GenerationFallback::Simulate);
+        let response = lenient.generate(&request(None), "test").await.unwrap();
+        assert!(response.degraded && response.policy.is_simulated);
+        assert_eq!(response.policy.ai_model_used.as_deref(), Some("simulation-fallback"));
+        assert_eq!(engine.metrics().get(metrics::GENERATION_FALLBACKS_TOTAL), 1);
+    }
+
+    #[tokio::test]
+    async fn test_explain_updates_explanation_once_per_content() {
+        let engine = std::sync::Arc::new(PolicyEngine::new());
+        let generator = PolicyGenerator::new(TarsClient::new("simulation://tars.tetrate.io", None), engine.clone());
+        let mut policy = Policy::new("hand-written", "", PolicyKind::Rego, AccessModel::ZeroTrust, "package t", "production", "test");
+        policy.resources = vec!["inventory-service".to_string()];
+        let policy = engine.add_policy(policy).await.unwrap();
+
+        let first = generator.explain(policy.id).await.unwrap();
+        assert!(!first.cached);
+        assert!(first.policy.explanation.contains("inventory-service"));
+        assert_eq!(engine.get_policy(policy.id).await.unwrap().explanation, first.policy.explanation);
+
+        let second = generator.explain(policy.id).await.unwrap();
+        assert!(second.cached);
+        assert!(matches!(generator.explain(Uuid::new_v4()).await, Err(PolicyError::NotFound(_))));
+        assert_eq!(parse_explanation("---EXPLANATION---\n Reads only.\n---END---"), "Reads only.");
+    }
+
+    #[tokio::test]
+    async fn test_identical_intents_reuse_and_draft_quota_blocks_generation() {
+        let engine = std::sync::Arc::new(PolicyEngine::with_config(crate::engine::EngineConfig {
+            max_drafts_per_namespace: 1,
+            ..Default::default()
+        }));
+        let generator = PolicyGenerator::new(TarsClient::new("simulation", None), engine.clone())
+            .with_dedup_window(Duration::from_secs(60));
+
+        let first = generator.generate(&request(None), "test").await.unwrap();
+        let mut same = request(None);
+        same.intent = "  allow checkout to READ inventory ".to_string();
+        let second = generator.generate(&same, "test").await.unwrap();
+        assert!(!first.reused && second.reused);
+        assert_eq!(first.policy.id, second.policy.id);
+        assert_eq!(engine.metrics().get(metrics::GENERATION_REUSED_TOTAL), 1);
+
+        engine.add_policy(first.policy).await.unwrap();
+        let mut other = request(None);
+        other.intent = "Allow cart to read inventory".to_string();
+        assert!(matches!(generator.generate(&other, "test").await, Err(PolicyError::Conflict(_))));
+        let mut draft = second.policy;
+        draft.id = Uuid::new_v4();
+        assert!(matches!(engine.add_policy(draft).await, Err(PolicyError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_generation_input_is_truncated_and_token_budget_enforced() {
+        let engine = std::sync::Arc::new(PolicyEngine::new());
+        let limits = GenerationLimits { max_intent_chars: 100, max_hint_items: 2, max_hint_chars: 64, max_prompt_tokens: 600 };
+        let generator = PolicyGenerator::new(TarsClient::new("simulation", None), engine).with_limits(limits);
+
+        let mut long = request(Some(vec!["spiffe://tetrate.io/ns/production/sa/checkout".to_string(); 3]));
+        long.intent = "Allow checkout to read inventory. ".repeat(20);
+        let response = generator.generate(&long, "test").await.unwrap();
+        assert_eq!(response.input_truncated, ["intent", "subjects"]);
+        assert_eq!(response.policy.description.chars().count(), 100);
+        assert_eq!(response.policy.subjects.len(), 2);
+
+        let generator = PolicyGenerator::new(TarsClient::new("simulation", None), std::sync::Arc::new(PolicyEngine::new()))
+            .with_limits(GenerationLimits { max_hint_items: 50, ..limits });
+        let mut bloated = request(None);
+        bloated.resources = Some(vec!["r".repeat(64); 50]);
+        let err = generator.generate(&bloated, "test").await.unwrap_err();
+        assert!(matches!(err, PolicyError::PayloadTooLarge(_)), "{}", err);
+    }
+
+    #[test]
+    fn test_guardrails_flag_broadening_output() {
+        let content = "package zedid\n\nallow if {\n    true\n}\n\nallow if {\n    input.subject == \"spiffe://tetrate.io/ns/prod/sa/attacker\"\n    input.action == \"*\"\n}\n";
+        let subjects = Some(vec![
+            "spiffe://tetrate.io/ns/production/sa/checkout".to_string()
+        ]);
+        let findings = guardrail_findings(content, &PolicyKind::Rego, &request(subjects));
+
+        assert_eq!(findings.len(), 4, "{:?}", findings);
+    }
+
+    #[test]
+    fn test_guardrails_accept_default_deny_policy() {
+        let content = "package zedid\n\ndefault allow := false\n\nallow if {\n    input.subject == \"spiffe://tetrate.io/ns/production/sa/checkout\"\n    input.action == \"GET\"\n}\n";
+        let subjects = Some(vec![
+            "spiffe://tetrate.io/ns/production/sa/checkout".to_string()
+        ]);
+        assert!(guardrail_findings(content, &PolicyKind::Rego, &request(subjects)).is_empty());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::models::{PolicyDecisionRequest, PolicyDecisionResponse};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use zedid_identity::{Identity, TrustLevel};
+
+/// Custom logic around policy evaluation, registered on the engine with
+/// `PolicyEngine::with_hook` and run in registration order.
+///
+/// `pre_evaluate` runs after context filtering and before the decision cache
+/// is consulted, so anything it adds to the context takes part in caching
+/// (with `ZEDID_CACHE_INCLUDE_CONTEXT`) and reaches the policies as `input`.
+/// `post_evaluate` runs on every live decision, cached or not, after the
+/// namespace enforcement mode has been applied.
+#[async_trait]
+pub trait EvaluationHook: Send + Sync {
+    /// Shown in logs
+    fn name(&self) -> &str;
+
+    async fn pre_evaluate(&self, _req: &mut PolicyDecisionRequest) {}
+
+    async fn post_evaluate(&self, _req: &PolicyDecisionRequest, _response: &mut PolicyDecisionResponse) {}
+}
+
+/// Resolves the decision subject (SPIFFE ID or identity id) in the identity
+/// store and attaches it, so its attributes appear as `input.identity`.
+/// A request's `identity_snapshot` takes precedence over the live lookup and
+/// is used as supplied, without SVID-expiry trust decay; `skip_enrichment`
+/// still wins over both. Installed first by `PolicyEngine::with_identities`.
+pub struct IdentityEnrichmentHook {
+    identities: Arc<RwLock<Vec<Identity>>>,
+    /// Keep High trust for identities whose SVID has lapsed
+    keep_trust_on_svid_expiry: bool,
+}
+
+impl IdentityEnrichmentHook {
+    pub fn new(identities: Arc<RwLock<Vec<Identity>>>, keep_trust_on_svid_expiry: bool) -> Self {
+        Self { identities, keep_trust_on_svid_expiry }
+    }
+}
+
+#[async_trait]
+impl EvaluationHook for IdentityEnrichmentHook {
+    fn name(&self) -> &str {
+        "identity-enrichment"
+    }
+
+    async fn pre_evaluate(&self, req: &mut PolicyDecisionRequest) {
+        if req.skip_enrichment {
+            return;
+        }
+        if let Some(snapshot) = &req.identity_snapshot {
+            req.identity = Some(snapshot.clone());
+            return;
+        }
+        let identities = self.identities.read().await;
+        req.identity = identities
+            .iter()
+            .find(|i| i.spiffe_id.as_deref() == Some(req.subject.as_str()) || i.id.to_string() == req.subject)
+            .cloned()
+            .map(|mut identity| {
+                // A deactivated identity, or one past its lease, is not trusted at all
+                if !identity.is_live(chrono::Utc::now()) {
+                    identity.is_active = false;
+                    identity.trust_level = TrustLevel::Untrusted;
+                } else if !self.keep_trust_on_svid_expiry {
+                    identity.trust_level = identity.effective_trust_level();
+                }
+                identity
+            });
+    }
+}
@@ -0,0 +1,207 @@
+use serde_yaml::Value;
+use tracing::warn;
+
+/// Evaluate an Istio `AuthorizationPolicy` against the engine `input` document.
+///
+/// The subject is matched as the source principal, the action as the request
+/// method and the resource as the request path. Returns `Some(true)` when an
+/// ALLOW policy's rules match, `Some(false)` when a DENY policy's rules match,
+/// and `None` otherwise. An ALLOW policy that doesn't match yields no result,
+/// so the engine's implicit deny applies — Istio's default-deny once ALLOW
+/// policies exist. AUDIT and CUSTOM policies never decide.
+pub fn evaluate(content: &str, input: &serde_json::Value) -> Option<bool> {
+    let doc: Value = match serde_yaml::from_str(content) {
+        Ok(doc) => doc,
+        Err(e) => {
+            warn!("Skipping unparseable AuthorizationPolicy: {}", e);
+            return None;
+        }
+    };
+    let spec = &doc["spec"];
+    let request = Request::from_input(input);
+
+    // A missing `rules` field matches nothing; `rules: [{}]` matches everything
+    let matched = spec["rules"]
+        .as_sequence()
+        .is_some_and(|rules| rules.iter().any(|rule| rule_matches(rule, &request)));
+    match spec["action"].as_str().unwrap_or("ALLOW") {
+        "ALLOW" if matched => Some(true),
+        "DENY" if matched => Some(false),
+        _ => None,
+    }
+}
+
+/// Whether some rule can select `subject` as its source: the rule has no
+/// `from` section, or a source matches the principal and its namespace.
+/// Operations and conditions are ignored — this answers "who is governed",
+/// not "what is allowed".
+pub fn selects_principal(content: &str, subject: &str) -> bool {
+    let Ok(doc) = serde_yaml::from_str::<Value>(content) else {
+        return false;
+    };
+    let input = serde_json::json!({ "subject": subject });
+    let request = Request::from_input(&input);
+    doc["spec"]["rules"].as_sequence().is_some_and(|rules| {
+        rules.iter().any(|rule| {
+            rule["from"]
+                .as_sequence()
+                .is_none_or(|from| from.iter().any(|f| source_matches(&f["source"], &request)))
+        })
+    })
+}
+
+struct Request<'a> {
+    principal: &'a str,
+    namespace: &'a str,
+    method: &'a str,
+    path: &'a str,
+}
+
+impl<'a> Request<'a> {
+    fn from_input(input: &'a serde_json::Value) -> Self {
+        let subject = input["subject"].as_str().unwrap_or_default();
+        // Istio principals are SPIFFE IDs without the scheme
+        let principal = subject.strip_prefix("spiffe://").unwrap_or(subject);
+        let namespace = principal
+            .split('/')
+            .skip_while(|segment| *segment != "ns")
+            .nth(1)
+            .unwrap_or_default();
+        Self {
+            principal,
+            namespace,
+            method: input["action"].as_str().unwrap_or_default(),
+            path: input["resource"].as_str().unwrap_or_default(),
+        }
+    }
+
+    fn attribute(&self, key: &str) -> Option<&str> {
+        match key {
+            "source.principal" => Some(self.principal),
+            "source.namespace" => Some(self.namespace),
+            "request.method" => Some(self.method),
+            "request.path" => Some(self.path),
+            _ => None,
+        }
+    }
+}
+
+/// A rule matches when every present section matches: any `from` source,
+/// any `to` operation, and all `when` conditions
+fn rule_matches(rule: &Value, request: &Request) -> bool {
+    let any_of = |section: &str, field: &str, matches: &dyn Fn(&Value) -> bool| {
+        rule[section]
+            .as_sequence()
+            .is_none_or(|entries| entries.iter().any(|entry| matches(&entry[field])))
+    };
+    any_of("from", "source", &|source| source_matches(source, request))
+        && any_of("to", "operation", &|operation| operation_matches(operation, request))
+        && rule["when"]
+            .as_sequence()
+            .is_none_or(|conditions| conditions.iter().all(|c| condition_matches(c, request)))
+}
+
+fn source_matches(source: &Value, request: &Request) -> bool {
+    field_matches(source, "principals", "notPrincipals", |p| principal_pattern_matches(p, request.principal))
+        && field_matches(source, "namespaces", "notNamespaces", |n| string_matches(n, request.namespace))
+}
+
+fn operation_matches(operation: &Value, request: &Request) -> bool {
+    field_matches(operation, "methods", "notMethods", |m| string_matches(m, request.method))
+        && field_matches(operation, "paths", "notPaths", |p| string_matches(p, request.path))
+}
+
+fn condition_matches(condition: &Value, request: &Request) -> bool {
+    let Some(value) = condition["key"].as_str().and_then(|key| request.attribute(key)) else {
+        return false;
+    };
+    field_matches(condition, "values", "notValues", |v| string_matches(v, value))
+}
+
+/// Values within a field are ORed: the positive list (if present) must
+/// contain a match and the negative list must not
+fn field_matches(section: &Value, positive: &str, negative: &str, matches: impl Fn(&str) -> bool) -> bool {
+    let values = |key: &str| -> Option<Vec<&str>> {
+        section[key]
+            .as_sequence()
+            .map(|seq| seq.iter().filter_map(Value::as_str).collect())
+    };
+    values(positive).is_none_or(|v| v.into_iter().any(&matches))
+        && !values(negative).is_some_and(|v| v.into_iter().any(&matches))
+}
+
+fn principal_pattern_matches(pattern: &str, principal: &str) -> bool {
+    string_matches(pattern.strip_prefix("spiffe://").unwrap_or(pattern), principal)
+}
+
+/// Istio string matching: exact, `*` (any non-empty value), `prefix*` or `*suffix`
+fn string_matches(pattern: &str, value: &str) -> bool {
+    if pattern == "*" {
+        return !value.is_empty();
+    }
+    if let Some(prefix) = pattern.strip_suffix('*') {
+        return value.starts_with(prefix);
+    }
+    if let Some(suffix) = pattern.strip_prefix('*') {
+        return value.ends_with(suffix);
+    }
+    pattern == value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(subject: &str, action: &str, resource: &str) -> serde_json::Value {
+        serde_json::json!({"subject": subject, "action": action, "resource": resource})
+    }
+
+    const ALLOW_CHECKOUT_READS: &str = r#"
+apiVersion: security.istio.io/v1beta1
+kind: AuthorizationPolicy
+spec:
+  action: ALLOW
+  rules:
+    - from:
+        - source:
+            principals: ["cluster.local/ns/production/sa/checkout"]
+      to:
+        - operation:
+            methods: ["GET", "HEAD"]
+            paths: ["/inventory/*"]
+            notPaths: ["/inventory/admin*"]
+"#;
+
+    #[test]
+    fn test_allow_rule_matches_principal_method_and_path() {
+        let checkout = "spiffe://cluster.local/ns/production/sa/checkout";
+        assert_eq!(evaluate(ALLOW_CHECKOUT_READS, &input(checkout, "GET", "/inventory/items")), Some(true));
+        // Default deny: an ALLOW policy that doesn't match gives no allow
+        assert_eq!(evaluate(ALLOW_CHECKOUT_READS, &input(checkout, "POST", "/inventory/items")), None);
+        assert_eq!(evaluate(ALLOW_CHECKOUT_READS, &input(checkout, "GET", "/inventory/admin/users")), None);
+        let other = "spiffe://cluster.local/ns/production/sa/cart";
+        assert_eq!(evaluate(ALLOW_CHECKOUT_READS, &input(other, "GET", "/inventory/items")), None);
+    }
+
+    #[test]
+    fn test_deny_action_and_rule_presence_semantics() {
+        let deny = "spec:\n  action: DENY\n  rules:\n    - from:\n        - source:\n            notNamespaces: [\"production\"]\n";
+        let staging = "spiffe://cluster.local/ns/staging/sa/job";
+        let prod = "spiffe://cluster.local/ns/production/sa/job";
+        assert_eq!(evaluate(deny, &input(staging, "GET", "/")), Some(false));
+        assert_eq!(evaluate(deny, &input(prod, "GET", "/")), None);
+
+        // `rules: [{}]` matches everything; no `rules` matches nothing
+        assert_eq!(evaluate("spec:\n  rules:\n    - {}\n", &input(prod, "DELETE", "/x")), Some(true));
+        assert_eq!(evaluate("spec:\n  action: ALLOW\n", &input(prod, "GET", "/")), None);
+    }
+
+    #[test]
+    fn test_when_conditions_must_all_hold() {
+        let policy = "spec:\n  rules:\n    - when:\n        - key: request.method\n          values: [\"GET\"]\n        - key: source.namespace\n          notValues: [\"staging\"]\n";
+        assert_eq!(evaluate(policy, &input("spiffe://td/ns/production/sa/a", "GET", "/")), Some(true));
+        assert_eq!(evaluate(policy, &input("spiffe://td/ns/staging/sa/a", "GET", "/")), None);
+        assert_eq!(evaluate(policy, &input("spiffe://td/ns/production/sa/a", "PUT", "/")), None);
+        assert!(string_matches("*-reader", "inventory-reader") && !string_matches("*", ""));
+    }
+}
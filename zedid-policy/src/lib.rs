@@ -1,7 +1,17 @@
 pub mod engine;
 pub mod generator;
+pub mod git;
+pub mod hooks;
+pub mod istio;
+pub mod metrics;
+pub mod opa;
+pub mod rbac;
+pub mod schedule;
+pub mod sinks;
+pub mod statements;
 pub mod models;
 pub mod tars;
+pub mod template;
 pub mod error;
 
 pub use models::*;
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Policy evaluations performed (cache hits included)
+pub const EVALUATIONS_TOTAL: &str = "zedid_policy_evaluations_total";
+/// Evaluations served from the decision cache
+pub const DECISION_CACHE_HITS_TOTAL: &str = "zedid_decision_cache_hits_total";
+/// SVIDs found close to (or past) expiry without having been renewed
+pub const SVID_EXPIRING_TOTAL: &str = "zedid_svid_expiring_total";
+/// Identities deactivated because their lease ran out
+pub const IDENTITY_LEASES_EXPIRED_TOTAL: &str = "zedid_identity_leases_expired_total";
+/// Audit events that could not be written (decision audits included)
+pub const AUDIT_WRITE_FAILURES_TOTAL: &str = "zedid_audit_write_failures_total";
+/// Deny reasons replaced with a generic message for untrusted callers
+pub const DENY_REASONS_REDACTED_TOTAL: &str = "zedid_deny_reasons_redacted_total";
+/// Evaluations that exceeded the configured timeout and failed closed
+pub const EVAL_TIMEOUTS_TOTAL: &str = "zedid_policy_eval_timeouts_total";
+/// Evaluations slower than `ZEDID_SLOW_EVAL_MS`
+pub const SLOW_EVALUATIONS_TOTAL: &str = "zedid_policy_slow_evaluations_total";
+/// Decisions a `DecisionSink` failed to record
+pub const DECISION_SINK_ERRORS_TOTAL: &str = "zedid_decision_sink_errors_total";
+/// Evaluations denied by a policy's `rate_limit`, by `policy_id`
+pub const POLICY_THROTTLED_TOTAL: &str = "zedid_policy_throttled_total";
+/// HTTP connections accepted by the REST listener
+pub const HTTP_CONNECTIONS_TOTAL: &str = "zedid_http_connections_total";
+/// HTTP connections currently open (gauge)
+pub const HTTP_CONNECTIONS_ACTIVE: &str = "zedid_http_connections_active";
+/// HTTP connections that ended with a protocol or I/O error
+pub const HTTP_CONNECTION_ERRORS_TOTAL: &str = "zedid_http_connection_errors_total";
+/// Caller-supplied context fields dropped because they were not allowlisted
+pub const CONTEXT_FIELDS_STRIPPED_TOTAL: &str = "zedid_context_fields_stripped_total";
+/// Generation requests answered with a simulated policy after a TARS failure
+pub const GENERATION_FALLBACKS_TOTAL: &str = "zedid_generation_fallbacks_total";
+/// Generate requests answered from the recent-intent cache instead of TARS
+pub const GENERATION_REUSED_TOTAL: &str = "zedid_generation_reused_total";
+/// Live TARS requests, by `outcome` (`success`, `failure`, or `short_circuited`
+/// when the circuit breaker refused to send)
+pub const TARS_REQUESTS_TOTAL: &str = "zedid_tars_requests_total";
+/// Failed TARS requests, by `category` (`network`, `auth`, `status`, `parse`)
+pub const TARS_ERRORS_TOTAL: &str = "zedid_tars_errors_total";
+/// Latency of live TARS requests (histogram, milliseconds)
+pub const TARS_REQUEST_DURATION_MS: &str = "zedid_tars_request_duration_ms";
+/// TARS circuit breaker state changes, by target state `to`
+pub const TARS_BREAKER_TRANSITIONS_TOTAL: &str = "zedid_tars_breaker_transitions_total";
+
+/// Upper bounds of the latency histogram buckets, in milliseconds
+pub const LATENCY_BUCKETS_MS: &[u64] = &[50, 100, 250, 500, 1000, 2500, 5000, 10000, 30000, 60000];
+
+/// In-process counter registry, shared between the engine and the API layer
+/// (in production: exported to Prometheus)
+#[derive(Debug, Default)]
+pub struct Metrics {
+    counters: Mutex<BTreeMap<String, u64>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn incr(&self, name: &str) {
+        self.add(name, 1);
+    }
+
+    pub fn add(&self, name: &str, value: u64) {
+        let mut counters = self.counters.lock().unwrap();
+        *counters.entry(name.to_string()).or_insert(0) += value;
+    }
+
+    /// Decrement a gauge, saturating at zero
+    pub fn sub(&self, name: &str, value: u64) {
+        let mut counters = self.counters.lock().unwrap();
+        let entry = counters.entry(name.to_string()).or_insert(0);
+        *entry = entry.saturating_sub(value);
+    }
+
+    /// Record a histogram observation as Prometheus-style counters:
+    /// cumulative `<name>_bucket{le="..."}`, `<name>_sum` and `<name>_count`
+    pub fn observe(&self, name: &str, value: u64) {
+        let mut counters = self.counters.lock().unwrap();
+        for le in LATENCY_BUCKETS_MS.iter().filter(|le| value <= **le) {
+            *counters.entry(format!("{}_bucket{{le=\"{}\"}}", name, le)).or_insert(0) += 1;
+        }
+        *counters.entry(format!("{}_bucket{{le=\"+Inf\"}}", name)).or_insert(0) += 1;
+        *counters.entry(format!("{}_sum", name)).or_insert(0) += value;
+        *counters.entry(format!("{}_count", name)).or_insert(0) += 1;
+    }
+
+    pub fn get(&self, name: &str) -> u64 {
+        self.counters.lock().unwrap().get(name).copied().unwrap_or(0)
+    }
+
+    /// Point-in-time copy of all counters, sorted by name
+    pub fn snapshot(&self) -> BTreeMap<String, u64> {
+        self.counters.lock().unwrap().clone()
+    }
+}
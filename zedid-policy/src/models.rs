@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
+use zedid_identity::{Identity, TrustLevel};
+use crate::schedule::CronSchedule;
 
 /// Policy type — what language/format the policy is in
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -16,8 +19,18 @@ pub enum PolicyKind {
     IstioAuthz,
 }
 
+impl PolicyKind {
+    /// Content-Type for the policy's native (raw) format
+    pub fn media_type(&self) -> &'static str {
+        match self {
+            PolicyKind::Rego | PolicyKind::Cedar => "text/plain; charset=utf-8",
+            PolicyKind::RbacYaml | PolicyKind::IstioAuthz => "application/yaml",
+        }
+    }
+}
+
 /// Policy lifecycle state
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
 pub enum PolicyStatus {
     /// AI-generated, awaiting human review
@@ -32,6 +45,25 @@ pub enum PolicyStatus {
     Archived,
 }
 
+impl PolicyStatus {
+    /// Statuses a policy may move to from this one. Archived policies come
+    /// back only as Drafts, so they are reviewed again before enforcement.
+    pub fn transitions(&self) -> &'static [PolicyStatus] {
+        use PolicyStatus::*;
+        match self {
+            Draft => &[Review, Active, Archived],
+            Review => &[Draft, Active, Archived],
+            Active => &[Disabled, Archived],
+            Disabled => &[Active, Draft, Archived],
+            Archived => &[Draft],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: &PolicyStatus) -> bool {
+        self.transitions().contains(next)
+    }
+}
+
 /// Access control model
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -65,6 +97,12 @@ pub struct Policy {
     pub resources: Vec<String>,
     /// Actions this policy controls
     pub actions: Vec<String>,
+    /// Subjects carved out of `subjects` (exact values or `prefix/*`)
+    #[serde(default)]
+    pub excluded_subjects: Vec<String>,
+    /// Resources carved out of `resources` (exact values or `prefix/*`)
+    #[serde(default)]
+    pub excluded_resources: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub created_by: String,
@@ -72,7 +110,273 @@ pub struct Policy {
     pub tags: Vec<String>,
     pub ai_generated: bool,
     pub ai_model_used: Option<String>,
+    /// Generated by TARS in simulation mode — a demo stub, not real model output
+    #[serde(default)]
+    pub is_simulated: bool,
     pub validation_passed: bool,
+    /// Reviewers assigned when the policy was submitted for review
+    #[serde(default)]
+    pub reviewers: Vec<String>,
+    /// Reviewer who approved the policy into Active
+    #[serde(default)]
+    pub approved_by: Option<String>,
+    /// Review history (approvals and rejections)
+    #[serde(default)]
+    pub review_comments: Vec<ReviewComment>,
+    /// Set when generation guardrails flagged the content as suspicious
+    #[serde(default)]
+    pub requires_manual_review: bool,
+    /// Values for `{{ name }}` placeholders in content/subjects/resources
+    /// (override namespace variables and the built-in `trust_domain`/`namespace`)
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Start of the window in which the policy applies (inclusive)
+    #[serde(default)]
+    pub active_from: Option<DateTime<Utc>>,
+    /// End of the window in which the policy applies (exclusive)
+    #[serde(default)]
+    pub active_until: Option<DateTime<Utc>>,
+    /// Cron expression (UTC) for the minutes the policy applies, e.g. `* 9-17 * * 1-5`
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Most live evaluations per second the policy may take part in; requests
+    /// over the limit are denied (fail closed) until the next second
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
+    /// Names of Rego rules / Cedar statements switched off (see `GET /policies/:id/statements`)
+    #[serde(default)]
+    pub disabled_statements: Vec<String>,
+    /// Regression tests run by `POST /policies/:id/run-tests` and before activation
+    #[serde(default)]
+    pub tests: Vec<PolicyTestCase>,
+    /// Where the policy is maintained; Git-synced policies are read-only here
+    #[serde(default)]
+    pub source: PolicySource,
+    /// File the policy was synced from, relative to the repository root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    /// Commit the policy was last synced from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_commit: Option<String>,
+}
+
+/// A request and the decision this policy alone must reach for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyTestCase {
+    pub name: String,
+    /// Evaluated with identity enrichment; `subject_token` is not verified
+    pub request: PolicyDecisionRequest,
+    pub expect: PolicyEffect,
+}
+
+/// Outcome of one attached test case
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyTestResult {
+    pub name: String,
+    pub expect: PolicyEffect,
+    pub actual: PolicyEffect,
+    pub passed: bool,
+    pub reason: String,
+}
+
+/// Outcome of a policy's attached tests
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyTestReport {
+    pub policy_id: Uuid,
+    pub total: usize,
+    pub failed: usize,
+    pub passed: bool,
+    pub results: Vec<PolicyTestResult>,
+}
+
+/// Replace a policy's attached tests
+#[derive(Debug, Deserialize)]
+pub struct SetPolicyTestsRequest {
+    pub tests: Vec<PolicyTestCase>,
+}
+
+/// Whether a policy grants or blocks access for the subjects it selects
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyEffect {
+    Allow,
+    Deny,
+}
+
+/// A policy whose subjects select a given identity
+#[derive(Debug, Clone, Serialize)]
+pub struct GoverningPolicy {
+    pub policy_id: Uuid,
+    pub name: String,
+    pub namespace: String,
+    pub kind: PolicyKind,
+    pub status: PolicyStatus,
+    pub effect: PolicyEffect,
+    /// The subject entry that matched: a SPIFFE ID or pattern, the identity id,
+    /// `role:<name>`, `*` for policies without subjects, or `source.principal`
+    /// for Istio rules
+    pub matched_by: String,
+}
+
+/// What one identity could do under one policy, per resource
+#[derive(Debug, Clone, Serialize)]
+pub struct IdentityImpact {
+    pub policy_id: Uuid,
+    pub policy_name: String,
+    pub identity_id: Uuid,
+    /// The decision subject used: the SPIFFE ID, or the identity id
+    pub subject: String,
+    /// Trust level the identity was evaluated at (after SVID expiry is applied)
+    pub trust_level: TrustLevel,
+    pub resources: Vec<ResourceImpact>,
+    pub allowed: usize,
+    pub denied: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceImpact {
+    pub resource: String,
+    pub allowed: Vec<String>,
+    pub denied: Vec<String>,
+}
+
+/// Most requests a single evaluation report decides
+pub const MAX_REPORT_REQUESTS: usize = 500;
+
+/// Baseline of current decisions in a namespace (`POST /policies/evaluate/report`)
+#[derive(Debug, Deserialize)]
+pub struct EvaluationReportRequest {
+    pub namespace: String,
+    /// Representative requests. When empty, requests are derived from the
+    /// active identities × the resources and actions of the namespace's
+    /// active policies.
+    #[serde(default)]
+    pub requests: Vec<ReportCase>,
+    /// Most derived requests to decide (default and maximum `MAX_REPORT_REQUESTS`)
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// One request of an evaluation report; the namespace is the report's
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReportCase {
+    pub subject: String,
+    pub resource: String,
+    pub action: String,
+    #[serde(default)]
+    pub context: DecisionContextInput,
+}
+
+/// How the active policies governing a heatmap cell decide
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CellCoverage {
+    /// Only allow policies apply
+    AllowOnly,
+    /// At least one deny policy applies
+    HasDenies,
+    /// No active policy applies, so requests are denied by default
+    Uncovered,
+}
+
+/// One namespace × resource cell of the coverage heatmap
+#[derive(Debug, Clone, Serialize)]
+pub struct HeatmapCell {
+    /// Active, in-window policies that govern the cell
+    pub policies: usize,
+    /// How many of them deny
+    pub denies: usize,
+    pub coverage: CellCoverage,
+}
+
+/// Which namespace/resource combinations active policies cover.
+/// `cells[i][j]` is `namespaces[i]` × `resources[j]`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageHeatmap {
+    pub generated_at: DateTime<Utc>,
+    pub namespaces: Vec<String>,
+    pub resources: Vec<String>,
+    pub cells: Vec<Vec<HeatmapCell>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationReport {
+    pub namespace: String,
+    pub generated_at: DateTime<Utc>,
+    /// Whether the requests were derived rather than supplied
+    pub derived: bool,
+    /// Derived requests before sampling down to `limit` (equals `rows` when supplied)
+    pub candidates: usize,
+    pub allowed: usize,
+    pub denied: usize,
+    pub errors: usize,
+    pub rows: Vec<EvaluationReportRow>,
+}
+
+/// One decision in an evaluation report, with the policy that decided it
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationReportRow {
+    pub subject: String,
+    pub resource: String,
+    pub action: String,
+    pub allowed: bool,
+    pub policy_id: Option<Uuid>,
+    pub policy_name: Option<String>,
+    pub reason: String,
+    /// Set when the request could not be decided (e.g. a disallowed context field)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Everything a subject is allowed to do right now, flattened
+#[derive(Debug, Clone, Serialize)]
+pub struct SubjectPermissions {
+    /// The decision subject, normalized
+    pub subject: String,
+    /// The identity the subject resolved to, if any
+    pub identity_id: Option<Uuid>,
+    pub permissions: Vec<EffectivePermission>,
+    /// Candidate (resource, action) pairs that were denied
+    pub denied: usize,
+}
+
+/// One allowed (resource, action) pair and the policy that allows it
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectivePermission {
+    pub namespace: String,
+    /// A resource name, or a `*` / `prefix/*` pattern taken from a policy
+    pub resource: String,
+    /// An action, or `*`
+    pub action: String,
+    pub policy_id: Uuid,
+    pub policy_name: String,
+    /// Pairs under this wildcard that are denied, as `ACTION resource`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub except: Vec<String>,
+}
+
+/// One Rego rule or Cedar statement of a policy, individually toggleable
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyStatement {
+    /// Rule name or Cedar `@id`; repeats are suffixed `#2`, `#3`, ...
+    pub name: String,
+    /// Comment lines directly above the statement
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ToggleStatementRequest {
+    pub enabled: bool,
+}
+
+/// A reviewer's comment recorded during the approval workflow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewComment {
+    pub reviewer: String,
+    pub comment: String,
+    pub created_at: DateTime<Utc>,
 }
 
 impl Policy {
@@ -99,6 +403,8 @@ impl Policy {
             subjects: vec![],
             resources: vec![],
             actions: vec![],
+            excluded_subjects: vec![],
+            excluded_resources: vec![],
             created_at: Utc::now(),
             updated_at: Utc::now(),
             created_by: created_by.to_string(),
@@ -106,13 +412,100 @@ impl Policy {
             tags: vec![],
             ai_generated: false,
             ai_model_used: None,
+            is_simulated: false,
             validation_passed: false,
+            reviewers: vec![],
+            approved_by: None,
+            review_comments: vec![],
+            requires_manual_review: false,
+            variables: HashMap::new(),
+            active_from: None,
+            active_until: None,
+            schedule: None,
+            rate_limit: None,
+            disabled_statements: vec![],
+            tests: vec![],
+            source: PolicySource::Local,
+            source_path: None,
+            source_commit: None,
         }
     }
+
+    /// Fields that differ from `before`, as `{"field": {"before": .., "after": ..}}`.
+    /// `updated_at` is left out, as it changes with everything else.
+    pub fn diff(&self, before: &Policy) -> serde_json::Map<String, serde_json::Value> {
+        let (serde_json::Value::Object(old), serde_json::Value::Object(new)) =
+            (serde_json::json!(before), serde_json::json!(self))
+        else {
+            return serde_json::Map::new();
+        };
+        new.into_iter()
+            .filter(|(field, value)| field != "updated_at" && old.get(field) != Some(value))
+            .map(|(field, value)| {
+                let previous = old.get(&field).cloned().unwrap_or_default();
+                (field, serde_json::json!({"before": previous, "after": value}))
+            })
+            .collect()
+    }
+
+    /// Whether `at` falls inside the policy's active window and schedule.
+    /// An unparseable schedule never matches.
+    pub fn is_in_window(&self, at: DateTime<Utc>) -> bool {
+        self.active_from.is_none_or(|from| at >= from)
+            && self.active_until.is_none_or(|until| at < until)
+            && self
+                .schedule
+                .as_deref()
+                .is_none_or(|expr| CronSchedule::parse(expr).is_ok_and(|s| s.matches(at)))
+    }
 }
 
-/// Request to generate a policy from natural language
+/// Largest accepted policy `content`, in bytes
+pub const MAX_POLICY_CONTENT_BYTES: usize = 64 * 1024;
+/// Most entries accepted in a policy's `subjects`, `resources` or `actions`
+pub const MAX_POLICY_LIST_ENTRIES: usize = 100;
+
+/// Request to create a hand-written policy. Server-managed fields (`id`,
+/// timestamps, `status`, validation) are not accepted; new policies start as Draft.
 #[derive(Debug, Deserialize)]
+pub struct CreatePolicyRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub kind: PolicyKind,
+    pub access_model: AccessModel,
+    pub content: String,
+    #[serde(default)]
+    pub explanation: String,
+    pub namespace: String,
+    #[serde(default)]
+    pub subjects: Vec<String>,
+    #[serde(default)]
+    pub resources: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<String>,
+    #[serde(default)]
+    pub excluded_subjects: Vec<String>,
+    #[serde(default)]
+    pub excluded_resources: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    #[serde(default)]
+    pub active_from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub active_until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub schedule: Option<String>,
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
+    #[serde(default)]
+    pub tests: Vec<PolicyTestCase>,
+}
+
+/// Request to generate a policy from natural language
+#[derive(Debug, Clone, Deserialize)]
 pub struct GeneratePolicyRequest {
     /// Natural language description of the desired policy
     pub intent: String,
@@ -131,13 +524,34 @@ pub struct GeneratePolicyRequest {
 }
 
 /// Result of policy generation
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct GeneratePolicyResponse {
     pub policy: Policy,
     pub validation_result: PolicyValidationResult,
     pub generation_time_ms: u64,
     pub model_used: String,
+    /// Why TARS routing picked `model_used`
+    pub routing_reason: String,
     pub tokens_used: Option<u32>,
+    /// Suspicious patterns found in the generated content (empty when clean)
+    pub guardrail_findings: Vec<String>,
+    /// TARS failed and the policy is a simulated fallback (`ZEDID_GEN_FALLBACK=simulate`)
+    pub degraded: bool,
+    /// An identical request was answered recently and its result is reused
+    /// (`ZEDID_GEN_DEDUP_WINDOW_SECS`); no tokens were spent
+    pub reused: bool,
+    /// Request fields shortened to fit the generation input limits
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub input_truncated: Vec<String>,
+}
+
+/// Result of re-explaining a stored policy
+#[derive(Debug, Clone, Serialize)]
+pub struct ExplainPolicyResponse {
+    pub policy: Policy,
+    pub model_used: String,
+    /// The content was already explained; TARS was not called
+    pub cached: bool,
 }
 
 /// Result of policy validation
@@ -150,17 +564,260 @@ pub struct PolicyValidationResult {
 }
 
 /// Policy decision request (for real-time enforcement)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolicyDecisionRequest {
     pub subject: String,
     pub resource: String,
     pub action: String,
     pub namespace: String,
-    pub context: serde_json::Value,
+    pub context: DecisionContextInput,
+    /// Opt out of merging the subject's identity attributes into `input.identity`
+    #[serde(default)]
+    pub skip_enrichment: bool,
+    /// Only consider Active policies that belong to this policy group
+    #[serde(default)]
+    pub group: Option<Uuid>,
+    /// ZedID JWT of the caller being asked about. When present it is verified
+    /// and its claims replace `subject` and `namespace` and set the context's
+    /// `trust_level` and `roles`; an invalid token is denied (fail closed).
+    #[serde(default, skip_serializing)]
+    pub subject_token: Option<String>,
+    /// Point-in-time identity to evaluate with instead of the live one, for
+    /// replayed or queued decisions. Must describe `subject`, and is only
+    /// accepted when the server allows snapshots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_snapshot: Option<Identity>,
+    /// Subject identity attached by `IdentityEnrichmentHook`; never
+    /// supplied by callers
+    #[serde(skip)]
+    pub identity: Option<Identity>,
 }
 
-/// Policy decision response
+/// A policy matched by `search_policies`, with one entry per matching field
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicySearchHit {
+    pub policy: Policy,
+    pub matches: Vec<SearchMatch>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchMatch {
+    /// `name`, `description`, `content`, `explanation`, `subjects`, `resources` or `actions`
+    pub field: String,
+    /// Surrounding text with the first occurrence wrapped in `**`
+    pub snippet: String,
+}
+
+/// Snapshot of a policy taken whenever it is created or changed
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyRevision {
+    /// 1-based, per policy
+    pub revision: u32,
+    pub recorded_at: DateTime<Utc>,
+    pub policy: Policy,
+}
+
+/// Usage counters for one policy since startup or the last reset
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyStats {
+    pub policy_id: Uuid,
+    /// Times the policy was consulted during an evaluation
+    pub evaluations: u64,
+    /// Decisions this policy produced, by outcome
+    pub allows: u64,
+    pub denies: u64,
+    /// Decisions by the rule that fired (`allow` / `deny` for simulated Rego)
+    pub rule_hits: HashMap<String, u64>,
+    pub last_hit: Option<DateTime<Utc>>,
+    pub since: DateTime<Utc>,
+}
+
+impl PolicyStats {
+    pub fn new(policy_id: Uuid) -> Self {
+        Self {
+            policy_id,
+            evaluations: 0,
+            allows: 0,
+            denies: 0,
+            rule_hits: HashMap::new(),
+            last_hit: None,
+            since: Utc::now(),
+        }
+    }
+}
+
+/// Engine-wide evaluation counters and latency percentiles. Percentiles cover
+/// the most recent evaluations only; counters run since startup.
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationStats {
+    pub evaluations: u64,
+    pub cache_hits: u64,
+    pub timeouts: u64,
+    /// Evaluations the percentiles are computed over
+    pub samples: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+    /// 0 when slow-evaluation logging is disabled
+    pub slow_threshold_ms: u64,
+    pub slow_evaluations: u64,
+}
+
+/// Which revision of a policy took part in a historical evaluation
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyRevisionRef {
+    pub policy_id: Uuid,
+    pub name: String,
+    pub revision: u32,
+    pub version: u32,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Decision reconstructed against the policy set as it was at `as_of`
+#[derive(Debug, Serialize)]
+pub struct HistoricalDecisionResponse {
+    #[serde(flatten)]
+    pub decision: PolicyDecisionResponse,
+    pub as_of: DateTime<Utc>,
+    /// Active policy revisions at `as_of`, in evaluation order
+    pub policy_versions: Vec<PolicyRevisionRef>,
+}
+
+/// Inline policy + request for `POST /policies/sandbox`
+#[derive(Debug, Deserialize)]
+pub struct SandboxRequest {
+    pub kind: PolicyKind,
+    pub content: String,
+    #[serde(default)]
+    pub subjects: Vec<String>,
+    #[serde(default)]
+    pub resources: Vec<String>,
+    #[serde(default)]
+    pub actions: Vec<String>,
+    #[serde(default)]
+    pub excluded_subjects: Vec<String>,
+    #[serde(default)]
+    pub excluded_resources: Vec<String>,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    pub request: PolicyDecisionRequest,
+}
+
+/// Sandbox outcome: the decision plus any compile/validation errors
 #[derive(Debug, Serialize)]
+pub struct SandboxResponse {
+    pub decision: PolicyDecisionResponse,
+    pub validation: PolicyValidationResult,
+}
+
+/// Unsaved policy changes + request for `POST /policies/evaluate/proposed`
+#[derive(Debug, Deserialize)]
+pub struct ProposedEvaluationRequest {
+    pub base_namespace: String,
+    /// Policies added to the active set, or replacing the stored policy with
+    /// the same id. A Disabled or Archived overlay takes that policy out.
+    #[serde(default)]
+    pub overlay_policies: Vec<Policy>,
+    pub request: PolicyDecisionRequest,
+}
+
+/// Decision under the proposed policy set, next to the current one
+#[derive(Debug, Serialize)]
+pub struct ProposedDecisionResponse {
+    pub decision: PolicyDecisionResponse,
+    pub current_decision: PolicyDecisionResponse,
+    /// Whether the overlay changes the outcome
+    pub changed: bool,
+}
+
+/// Well-known decision context attributes referenced by the built-in policies.
+/// Serializes to the same flat JSON object as a hand-built `context`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionContext {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mfa_verified: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_tokens_used: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_age_minutes: Option<u64>,
+    /// Any other attributes, flattened alongside the typed ones
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl DecisionContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mfa_verified(mut self, verified: bool) -> Self {
+        self.mfa_verified = Some(verified);
+        self
+    }
+
+    pub fn daily_tokens_used(mut self, tokens: u64) -> Self {
+        self.daily_tokens_used = Some(tokens);
+        self
+    }
+
+    pub fn target_model(mut self, model: &str) -> Self {
+        self.target_model = Some(model.to_string());
+        self
+    }
+
+    pub fn session_age_minutes(mut self, minutes: u64) -> Self {
+        self.session_age_minutes = Some(minutes);
+        self
+    }
+
+    pub fn extra(mut self, key: &str, value: serde_json::Value) -> Self {
+        self.extra.insert(key.to_string(), value);
+        self
+    }
+}
+
+/// Request context — an object whose well-known fields have the expected types
+/// parses as `Typed`; anything else is kept verbatim as `Raw`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DecisionContextInput {
+    Typed(DecisionContext),
+    Raw(serde_json::Value),
+}
+
+impl DecisionContextInput {
+    /// The context as it appears under `input.context`
+    pub fn to_value(&self) -> serde_json::Value {
+        match self {
+            Self::Typed(ctx) => serde_json::to_value(ctx).unwrap_or_default(),
+            Self::Raw(value) => value.clone(),
+        }
+    }
+}
+
+impl Default for DecisionContextInput {
+    fn default() -> Self {
+        Self::Typed(DecisionContext::default())
+    }
+}
+
+impl From<DecisionContext> for DecisionContextInput {
+    fn from(ctx: DecisionContext) -> Self {
+        Self::Typed(ctx)
+    }
+}
+
+impl From<serde_json::Value> for DecisionContextInput {
+    fn from(value: serde_json::Value) -> Self {
+        Self::Raw(value)
+    }
+}
+
+/// Policy decision response
+#[derive(Debug, Clone, Serialize)]
 pub struct PolicyDecisionResponse {
     pub allowed: bool,
     pub reason: String,
@@ -168,4 +825,290 @@ pub struct PolicyDecisionResponse {
     pub policy_name: Option<String>,
     pub evaluation_time_ms: u64,
     pub decision_id: Uuid,
+    /// True when served from the short-TTL decision cache
+    pub cached: bool,
+    /// Enforcement mode of the request's namespace
+    pub enforcement_mode: EnforcementMode,
+    /// Set in monitor/dry-run mode when the policy decision was a deny
+    /// that was not enforced
+    pub would_deny: bool,
+    /// Denied because a policy's `rate_limit` was exceeded; such denials are
+    /// never cached and are enforced in every mode
+    pub throttled: bool,
+    /// Plain-language account of a denial for end users, when
+    /// `ZEDID_EXPLAIN_DENIALS` is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub human_explanation: Option<String>,
+    /// Degraded-mode notices, e.g. the decision could not be audited
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+/// Current policy bundle format version
+pub const POLICY_BUNDLE_SCHEMA_VERSION: u32 = 1;
+
+/// Portable set of policies for moving between ZedID instances
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyBundle {
+    /// Bundle format version — importers reject versions they don't understand
+    pub schema_version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Namespace filter used at export time (None = all namespaces)
+    pub namespace: Option<String>,
+    pub policies: Vec<Policy>,
+}
+
+/// Where a policy is authored
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicySource {
+    /// Created through the API or dashboard
+    #[default]
+    Local,
+    /// Synced from the configured Git repository
+    Git,
+}
+
+/// Outcome of one sync from the policy Git repository
+#[derive(Debug, Clone, Serialize)]
+pub struct GitSyncReport {
+    pub commit: String,
+    pub synced_at: DateTime<Utc>,
+    /// Paths of policies added, changed or disabled by this sync
+    pub created: Vec<String>,
+    pub updated: Vec<String>,
+    /// Previously synced policies whose file is gone; they are disabled
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+    /// Files not synced because a local policy has the same name
+    pub conflicts: Vec<GitSyncIssue>,
+    /// Files not synced because they failed validation (the last good version stays)
+    pub invalid: Vec<GitSyncIssue>,
+}
+
+/// Policy repository settings and the outcome of the latest sync
+#[derive(Debug, Clone, Serialize)]
+pub struct GitSyncStatus {
+    /// Repository URL, credentials removed
+    pub url: String,
+    pub branch: String,
+    /// Directory within the repository that holds policies
+    pub path: String,
+    /// Latest successful sync, whose `commit` is the one in the store
+    pub last_sync: Option<GitSyncReport>,
+    /// Why the latest attempt failed, cleared by the next success
+    pub last_error: Option<String>,
+    pub last_attempt: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GitSyncIssue {
+    pub path: String,
+    pub reason: String,
+    /// The local policy in the way, for conflicts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub policy_id: Option<Uuid>,
+}
+
+/// Per-policy outcome of a bundle import
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyImportResult {
+    pub name: String,
+    pub id: Option<Uuid>,
+    pub imported: bool,
+    pub validation_passed: bool,
+    pub reason: Option<String>,
+}
+
+/// How decisions in a namespace are enforced
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnforcementMode {
+    /// Denies are returned as denies (zero-trust default)
+    #[default]
+    Enforce,
+    /// Denies are returned as `allowed: true` with `would_deny: true` and logged as warnings
+    Monitor,
+    /// Like monitor, but would-denies are not raised as warnings — for synthetic pre-rollout traffic
+    #[serde(alias = "dry-run")]
+    DryRun,
+}
+
+/// A registered namespace — the scope for identities and policies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Namespace {
+    pub name: String,
+    pub description: String,
+    pub owner: Option<String>,
+    /// Trust level suggested for identities created in this namespace
+    pub default_trust_level: TrustLevel,
+    #[serde(default)]
+    pub enforcement_mode: EnforcementMode,
+    /// Default template variables for policies in this namespace
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+    /// Deny-reason detail shown to untrusted callers (unset: server default)
+    #[serde(default)]
+    pub reason_verbosity: Option<ReasonVerbosity>,
+    /// Context fields callers may set (unset: server default)
+    #[serde(default)]
+    pub context_allowlist: Option<Vec<String>>,
+    /// Baseline policy consulted on every evaluation here, after all others
+    #[serde(default)]
+    pub default_policy_id: Option<Uuid>,
+    /// How the decisions of matching policies are combined (unset: server default)
+    #[serde(default)]
+    pub combining_algorithm: Option<CombiningAlgorithm>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Namespace {
+    pub fn new(name: &str, description: &str, owner: Option<&str>) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            owner: owner.map(str::to_string),
+            default_trust_level: TrustLevel::Low,
+            enforcement_mode: EnforcementMode::Enforce,
+            variables: HashMap::new(),
+            reason_verbosity: None,
+            context_allowlist: None,
+            default_policy_id: None,
+            combining_algorithm: None,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Request to register a namespace
+#[derive(Debug, Deserialize)]
+pub struct CreateNamespaceRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub default_trust_level: Option<TrustLevel>,
+    pub enforcement_mode: Option<EnforcementMode>,
+    pub variables: Option<HashMap<String, String>>,
+    pub reason_verbosity: Option<ReasonVerbosity>,
+    pub context_allowlist: Option<Vec<String>>,
+    pub combining_algorithm: Option<CombiningAlgorithm>,
+}
+
+/// How much of a deny reason untrusted callers see
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasonVerbosity {
+    /// Full reason, including the deciding policy
+    #[default]
+    Detailed,
+    /// Generic "access denied"; details go to the audit log only
+    Redacted,
+}
+
+/// How the decisions of several matching policies combine into one.
+/// Policies are consulted in store order, with the namespace default last.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CombiningAlgorithm {
+    /// Any deny wins; otherwise any allow; otherwise implicit deny
+    #[default]
+    DenyOverrides,
+    /// Any allow wins; otherwise any deny; otherwise implicit deny
+    AllowOverrides,
+    /// The first policy that allows or denies decides; otherwise implicit deny
+    FirstApplicable,
+    /// Allowed unless some policy denies, even when nothing allows
+    PermitUnlessDeny,
+}
+
+/// Partial update of a registered namespace
+#[derive(Debug, Deserialize)]
+pub struct UpdateNamespaceRequest {
+    pub description: Option<String>,
+    pub owner: Option<String>,
+    pub default_trust_level: Option<TrustLevel>,
+    pub enforcement_mode: Option<EnforcementMode>,
+    pub variables: Option<HashMap<String, String>>,
+    pub reason_verbosity: Option<ReasonVerbosity>,
+    pub context_allowlist: Option<Vec<String>>,
+    pub combining_algorithm: Option<CombiningAlgorithm>,
+}
+
+/// Set (or, with `null`, clear) a namespace's default policy
+#[derive(Debug, Deserialize)]
+pub struct SetDefaultPolicyRequest {
+    pub policy_id: Option<Uuid>,
+}
+
+/// Named set of policies that are activated and disabled together.
+/// A policy may belong to any number of groups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub policy_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PolicyGroup {
+    pub fn new(name: &str, description: &str, policy_ids: Vec<Uuid>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            description: description.to_string(),
+            policy_ids,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePolicyGroupRequest {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub policy_ids: Vec<Uuid>,
+}
+
+/// Promote a reviewed set of policies to Active in one call
+#[derive(Debug, Deserialize)]
+pub struct BulkActivateRequest {
+    pub ids: Vec<Uuid>,
+    /// Abort the whole batch if any policy fails its checks (default);
+    /// `false` activates the policies that pass and skips the rest
+    #[serde(default = "default_true")]
+    pub atomic: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A policy left out of a bulk activation, and why
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedPolicy {
+    pub id: Uuid,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkActivateResponse {
+    pub activated: Vec<Uuid>,
+    pub skipped: Vec<SkippedPolicy>,
+    /// True when an atomic batch was abandoned because a policy failed its checks
+    pub aborted: bool,
+}
+
+/// Membership change for a policy group
+#[derive(Debug, Deserialize)]
+pub struct UpdateGroupMembersRequest {
+    #[serde(default)]
+    pub add: Vec<Uuid>,
+    #[serde(default)]
+    pub remove: Vec<Uuid>,
 }
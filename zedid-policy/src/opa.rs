@@ -0,0 +1,78 @@
+use crate::error::PolicyError;
+use serde::Deserialize;
+use tracing::debug;
+
+/// Delegates decisions to an external OPA server via its Data API
+/// (`POST /v1/data/<package>/allow`), for deployments with central policy management
+pub struct OpaRestEngine {
+    base_url: String,
+    package: String,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpaDataResponse {
+    /// Absent when the rule is undefined for the given input
+    result: Option<serde_json::Value>,
+}
+
+impl OpaRestEngine {
+    /// `package` uses Rego dot notation, e.g. `zedid.authz`
+    pub fn new(base_url: &str, package: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            package: package.to_string(),
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(5))
+                .build()
+                .unwrap(),
+        }
+    }
+
+    /// Rego reference of the rule being queried, e.g. `data.zedid.allow`
+    pub fn rule(&self) -> String {
+        format!("data.{}.allow", self.package)
+    }
+
+    /// Query the `allow` rule. An undefined result is treated as deny.
+    pub async fn evaluate(&self, input: &serde_json::Value) -> Result<bool, PolicyError> {
+        let url = format!(
+            "{}/v1/data/{}/allow",
+            self.base_url,
+            self.package.replace('.', "/")
+        );
+        debug!("Querying OPA: {}", url);
+
+        let response = self
+            .http
+            .post(&url)
+            .json(&serde_json::json!({ "input": input }))
+            .send()
+            .await
+            .map_err(|e| PolicyError::OpaError(format!("Network error: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(PolicyError::OpaError(format!(
+                "OPA query failed: {} - {}",
+                status, text
+            )));
+        }
+
+        let body: OpaDataResponse = response
+            .json()
+            .await
+            .map_err(|e| PolicyError::OpaError(format!("Parse error: {}", e)))?;
+
+        match body.result {
+            None => Ok(false),
+            Some(serde_json::Value::Bool(allowed)) => Ok(allowed),
+            Some(other) => Err(PolicyError::OpaError(format!(
+                "Expected boolean from {}, got {}",
+                self.rule(),
+                other
+            ))),
+        }
+    }
+}
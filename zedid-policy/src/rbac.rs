@@ -0,0 +1,218 @@
+use crate::engine::pattern_matches;
+use serde::Deserialize;
+use tracing::warn;
+
+/// ZedID RBAC document (`kind: RBACPolicy`): roles grant verbs on resources,
+/// role bindings attach roles to subjects.
+///
+/// ```yaml
+/// apiVersion: zedid.tetrate.io/v1
+/// kind: RBACPolicy
+/// spec:
+///   roles:
+///     - name: inventory-reader
+///       rules:
+///         - resources: ["inventory-service"]
+///           verbs: ["get", "list"]
+///   roleBindings:
+///     - role: inventory-reader
+///       subjects:
+///         - kind: ServiceAccount
+///           spiffeId: "spiffe://tetrate.io/ns/production/sa/checkout"
+/// ```
+///
+/// Subject kinds: `ServiceAccount`/`Workload`/`Agent` (by `spiffeId`),
+/// `Namespace` (every workload in `name`), `User` (subject equal to `name`)
+/// and `Group` (identities whose `role` label includes `name`).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RbacPolicy {
+    pub kind: String,
+    pub spec: RbacSpec,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RbacSpec {
+    #[serde(default)]
+    pub roles: Vec<Role>,
+    #[serde(default)]
+    pub role_bindings: Vec<RoleBinding>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Role {
+    pub name: String,
+    #[serde(default)]
+    pub rules: Vec<RoleRule>,
+}
+
+/// Resources and verbs accept `*`; resources also accept a `prefix/*` pattern
+#[derive(Debug, Deserialize)]
+pub struct RoleRule {
+    pub resources: Vec<String>,
+    pub verbs: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RoleBinding {
+    pub role: String,
+    pub subjects: Vec<BindingSubject>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingSubject {
+    pub kind: String,
+    pub name: Option<String>,
+    pub spiffe_id: Option<String>,
+}
+
+const SUBJECT_KINDS: &[&str] = &["ServiceAccount", "Workload", "Agent", "Namespace", "User", "Group"];
+
+/// Schema errors for an RBAC document (empty when it is well-formed)
+pub fn validate(content: &str) -> Vec<String> {
+    let policy: RbacPolicy = match serde_yaml::from_str(content) {
+        Ok(policy) => policy,
+        Err(e) => return vec![format!("RBAC policy does not match the RBACPolicy schema: {}", e)],
+    };
+    let mut errors = vec![];
+    if policy.kind != "RBACPolicy" {
+        errors.push(format!("RBAC policy must have kind: RBACPolicy, not {}", policy.kind));
+    }
+    for role in &policy.spec.roles {
+        if role.rules.is_empty() {
+            errors.push(format!("Role {} grants nothing (no rules)", role.name));
+        }
+    }
+    for binding in &policy.spec.role_bindings {
+        if !policy.spec.roles.iter().any(|r| r.name == binding.role) {
+            errors.push(format!("Role binding references undefined role {}", binding.role));
+        }
+        for subject in &binding.subjects {
+            let identified = match subject.kind.as_str() {
+                "ServiceAccount" | "Workload" | "Agent" => subject.spiffe_id.is_some(),
+                _ => subject.name.is_some(),
+            };
+            if !SUBJECT_KINDS.contains(&subject.kind.as_str()) {
+                errors.push(format!("Unknown subject kind {} in binding for {}", subject.kind, binding.role));
+            } else if !identified {
+                errors.push(format!("{} subject in binding for {} has no spiffeId/name", subject.kind, binding.role));
+            }
+        }
+    }
+    if policy.spec.role_bindings.is_empty() {
+        errors.push("RBAC policy has no roleBindings".to_string());
+    }
+    errors
+}
+
+/// Evaluate an RBAC document against the engine `input`: resolve the roles
+/// bound to the subject and allow when one of their rules grants the action
+/// on the resource. RBAC only grants, so no match yields `None` (implicit deny).
+pub fn evaluate(content: &str, input: &serde_json::Value) -> Option<bool> {
+    let policy: RbacPolicy = match serde_yaml::from_str(content) {
+        Ok(policy) => policy,
+        Err(e) => {
+            warn!("Skipping unparseable RBAC policy: {}", e);
+            return None;
+        }
+    };
+    let subject = input["subject"].as_str().unwrap_or_default();
+    let resource = input["resource"].as_str().unwrap_or_default();
+    let action = input["action"].as_str().unwrap_or_default();
+    // Groups come from the identity's `role` label and the verified token's roles
+    let mut groups: Vec<&str> = input["identity"]["labels"]["role"]
+        .as_str()
+        .map(|r| r.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    if let Some(roles) = input["context"]["roles"].as_array() {
+        groups.extend(roles.iter().filter_map(|r| r.as_str()));
+    }
+
+    let granted = policy
+        .spec
+        .role_bindings
+        .iter()
+        .filter(|b| b.subjects.iter().any(|s| subject_matches(s, subject, &groups)))
+        .filter_map(|b| policy.spec.roles.iter().find(|r| r.name == b.role))
+        .flat_map(|role| &role.rules)
+        .any(|rule| {
+            rule.resources.iter().any(|r| pattern_matches(r, resource))
+                && rule.verbs.iter().any(|v| v == "*" || v.eq_ignore_ascii_case(action))
+        });
+    granted.then_some(true)
+}
+
+fn subject_matches(binding: &BindingSubject, subject: &str, groups: &[&str]) -> bool {
+    match binding.kind.as_str() {
+        "ServiceAccount" | "Workload" | "Agent" => binding
+            .spiffe_id
+            .as_deref()
+            .is_some_and(|id| pattern_matches(id, subject)),
+        "Namespace" => binding.name.as_deref().is_some_and(|ns| {
+            subject
+                .split('/')
+                .skip_while(|segment| *segment != "ns")
+                .nth(1)
+                == Some(ns)
+        }),
+        "User" => binding.name.as_deref() == Some(subject),
+        "Group" => binding.name.as_deref().is_some_and(|g| groups.contains(&g)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVENTORY_READER: &str = r#"
+apiVersion: zedid.tetrate.io/v1
+kind: RBACPolicy
+spec:
+  roles:
+    - name: inventory-reader
+      rules:
+        - resources: ["inventory-service"]
+          verbs: ["get", "list"]
+  roleBindings:
+    - role: inventory-reader
+      subjects:
+        - kind: ServiceAccount
+          spiffeId: "spiffe://tetrate.io/ns/production/sa/checkout"
+        - kind: Group
+          name: auditor
+"#;
+
+    fn input(subject: &str, action: &str, resource: &str) -> serde_json::Value {
+        serde_json::json!({"subject": subject, "action": action, "resource": resource})
+    }
+
+    #[test]
+    fn test_role_binding_grants_get_on_inventory() {
+        let checkout = "spiffe://tetrate.io/ns/production/sa/checkout";
+        assert!(validate(INVENTORY_READER).is_empty());
+        assert_eq!(evaluate(INVENTORY_READER, &input(checkout, "GET", "inventory-service")), Some(true));
+        assert_eq!(evaluate(INVENTORY_READER, &input(checkout, "DELETE", "inventory-service")), None);
+        assert_eq!(evaluate(INVENTORY_READER, &input(checkout, "GET", "payments-service")), None);
+        assert_eq!(
+            evaluate(INVENTORY_READER, &input("spiffe://tetrate.io/ns/production/sa/cart", "GET", "inventory-service")),
+            None
+        );
+
+        let mut auditor = input("alice@tetrate.io", "list", "inventory-service");
+        auditor["identity"] = serde_json::json!({"labels": {"role": "viewer, auditor"}});
+        assert_eq!(evaluate(INVENTORY_READER, &auditor), Some(true));
+    }
+
+    #[test]
+    fn test_validate_reports_schema_problems() {
+        let broken = "kind: RBACPolicy\nspec:\n  roles: []\n  roleBindings:\n    - role: missing\n      subjects:\n        - kind: ServiceAccount\n";
+        let errors = validate(broken);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("undefined role missing"));
+        assert!(errors[1].contains("has no spiffeId/name"));
+        assert!(!validate("kind: RBACPolicy\n").is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::PolicyError;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+/// Five-field cron expression (`minute hour day-of-month month day-of-week`,
+/// evaluated in UTC) describing the minutes during which a policy is active.
+/// Fields accept `*`, values, `a-b` ranges, `,` lists and `/n` steps;
+/// day-of-week runs 0–6 from Sunday (7 is also Sunday).
+///
+/// `* 9-17 * * 1-5` is business hours; `0-59 2 * * 0` is Sunday 02:00–02:59.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    minutes: Vec<u32>,
+    hours: Vec<u32>,
+    days: Vec<u32>,
+    months: Vec<u32>,
+    weekdays: Vec<u32>,
+    /// Both day fields restricted: cron matches either, not both
+    day_or_weekday: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, PolicyError> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(PolicyError::ValidationFailed(format!(
+                "Schedule must have 5 fields (minute hour day month weekday): {}",
+                expr
+            )));
+        };
+        let mut weekdays = parse_field(weekday, 0, 7)?;
+        for d in weekdays.iter_mut().filter(|d| **d == 7) {
+            *d = 0;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days: parse_field(day, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            weekdays,
+            day_or_weekday: day != "*" && weekday != "*",
+        })
+    }
+
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        let day = self.days.contains(&at.day());
+        let weekday = self.weekdays.contains(&at.weekday().num_days_from_sunday());
+        self.minutes.contains(&at.minute())
+            && self.hours.contains(&at.hour())
+            && self.months.contains(&at.month())
+            && if self.day_or_weekday { day || weekday } else { day && weekday }
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<u32>, PolicyError> {
+    let invalid = || PolicyError::ValidationFailed(format!("Invalid schedule field '{}' (range {}-{})", field, min, max));
+    let mut values = vec![];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0).ok_or_else(invalid)?),
+            None => (part, 1),
+        };
+        let (from, to) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((a, b)) => (a.parse().map_err(|_| invalid())?, b.parse().map_err(|_| invalid())?),
+                None => {
+                    let v = range.parse().map_err(|_| invalid())?;
+                    // `5/15` means every 15 starting at 5
+                    (v, if part.contains('/') { max } else { v })
+                }
+            },
+        };
+        if from < min || to > max || from > to {
+            return Err(invalid());
+        }
+        values.extend((from..=to).step_by(step as usize));
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_business_hours_schedule() {
+        let schedule = CronSchedule::parse("* 9-17 * * 1-5").unwrap();
+        // 2025-06-02 is a Monday
+        assert!(schedule.matches(Utc.with_ymd_and_hms(2025, 6, 2, 9, 30, 0).unwrap()));
+        assert!(!schedule.matches(Utc.with_ymd_and_hms(2025, 6, 2, 18, 0, 0).unwrap()));
+        assert!(!schedule.matches(Utc.with_ymd_and_hms(2025, 6, 1, 10, 0, 0).unwrap()));
+
+        let every_quarter = CronSchedule::parse("*/15 * * * 7").unwrap();
+        assert!(every_quarter.matches(Utc.with_ymd_and_hms(2025, 6, 1, 3, 45, 0).unwrap()));
+        assert!(!every_quarter.matches(Utc.with_ymd_and_hms(2025, 6, 1, 3, 46, 0).unwrap()));
+
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+    }
+}
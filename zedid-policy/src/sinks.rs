@@ -0,0 +1,156 @@
+use crate::engine::{build_input, redact_context};
+use crate::error::PolicyError;
+use crate::models::{PolicyDecisionRequest, PolicyDecisionResponse};
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Exports live decisions to an external system (a decision log, Kafka, a
+/// database), separately from the audit log. Registered on the engine with
+/// `PolicyEngine::with_sink`; every sink is called in registration order on a
+/// background task after the decision has been returned, so an error or a
+/// slow sink never changes or delays it. What-if evaluations (simulation,
+/// reports) are not exported.
+#[async_trait]
+pub trait DecisionSink: Send + Sync {
+    /// Shown in logs
+    fn name(&self) -> &str;
+
+    /// `req` is the request as evaluated: context-filtered and identity-enriched
+    async fn record(&self, req: &PolicyDecisionRequest, response: &PolicyDecisionResponse) -> Result<(), PolicyError>;
+}
+
+/// Discards every decision
+pub struct NoopSink;
+
+#[async_trait]
+impl DecisionSink for NoopSink {
+    fn name(&self) -> &str {
+        "noop"
+    }
+
+    async fn record(&self, _req: &PolicyDecisionRequest, _response: &PolicyDecisionResponse) -> Result<(), PolicyError> {
+        Ok(())
+    }
+}
+
+/// Appends one JSON object per decision to a file, in OPA's decision log
+/// format: `input` is the document policies see and `result` the allow
+/// decision, with ZedID's full response under `zedid`.
+pub struct JsonlFileSink {
+    path: PathBuf,
+    file: Mutex<File>,
+    /// Context fields written as `[REDACTED]`
+    redact: Vec<String>,
+}
+
+impl JsonlFileSink {
+    /// Open `path` for appending, creating it if needed
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self, PolicyError> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await
+            .map_err(|e| PolicyError::SourceError(format!("{}: {}", path.display(), e)))?;
+        Ok(Self { path, file: Mutex::new(file), redact: vec![] })
+    }
+
+    pub fn with_redacted(mut self, fields: Vec<String>) -> Self {
+        self.redact = fields;
+        self
+    }
+}
+
+#[async_trait]
+impl DecisionSink for JsonlFileSink {
+    fn name(&self) -> &str {
+        "jsonl-file"
+    }
+
+    async fn record(&self, req: &PolicyDecisionRequest, response: &PolicyDecisionResponse) -> Result<(), PolicyError> {
+        let mut input = build_input(req);
+        input["context"] = redact_context(input["context"].take(), &self.redact);
+        let entry = serde_json::json!({
+            "decision_id": response.decision_id,
+            "timestamp": chrono::Utc::now(),
+            "path": "zedid/allow",
+            "input": input,
+            "result": response.allowed,
+            "metrics": { "timer_server_handler_ns": response.evaluation_time_ms * 1_000_000 },
+            "zedid": response,
+        });
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        // One write per line, under the lock, so concurrent decisions never interleave
+        let mut file = self.file.lock().await;
+        file.write_all(&line)
+            .await
+            .map_err(|e| PolicyError::SourceError(format!("{}: {}", self.path.display(), e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::PolicyEngine;
+    use crate::metrics;
+    use std::sync::Arc;
+
+    struct FailingSink;
+
+    #[async_trait]
+    impl DecisionSink for FailingSink {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        async fn record(&self, _req: &PolicyDecisionRequest, _response: &PolicyDecisionResponse) -> Result<(), PolicyError> {
+            Err(PolicyError::HttpError("collector unreachable".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decisions_export_to_sinks_without_affecting_result() {
+        let path = std::env::temp_dir().join(format!("zedid-decisions-{}.jsonl", uuid::Uuid::new_v4()));
+        let jsonl = JsonlFileSink::open(&path).await.unwrap().with_redacted(vec!["session".to_string()]);
+        let engine = PolicyEngine::new()
+            .with_sink(Arc::new(FailingSink))
+            .with_sink(Arc::new(NoopSink))
+            .with_sink(Arc::new(jsonl));
+        engine.seed_demo_policies().await;
+
+        let req: PolicyDecisionRequest = serde_json::from_value(serde_json::json!({
+            "subject": "spiffe://tetrate.io/ns/production/sa/checkout",
+            "resource": "/api/payments",
+            "action": "POST",
+            "namespace": "production",
+            "context": { "session": "s3cret" },
+        }))
+        .unwrap();
+        let response = engine.evaluate(&req).await.unwrap();
+
+        let mut written = String::new();
+        for _ in 0..100 {
+            written = tokio::fs::read_to_string(&path).await.unwrap();
+            if !written.is_empty() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<serde_json::Value> = written.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 1);
+        let entry = &lines[0];
+        assert_eq!(entry["decision_id"], response.decision_id.to_string());
+        assert_eq!(entry["result"], response.allowed);
+        assert_eq!(entry["input"]["subject"], req.subject);
+        assert_eq!(entry["input"]["context"]["session"], "[REDACTED]");
+        assert_eq!(entry["zedid"]["reason"], response.reason);
+        assert_eq!(engine.metrics().get(metrics::DECISION_SINK_ERRORS_TOTAL), 1);
+    }
+}
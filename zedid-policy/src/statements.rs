@@ -0,0 +1,223 @@
+use crate::error::PolicyError;
+use crate::models::{Policy, PolicyKind, PolicyStatement};
+use std::collections::HashMap;
+
+/// A slice of policy content: a named statement, or shared text (package,
+/// imports, defaults, trailing comments) that is always kept
+struct Chunk {
+    name: Option<String>,
+    description: Option<String>,
+    /// Rego `allow` rules and Cedar `permit` statements
+    grants: bool,
+    text: String,
+}
+
+/// Split a Rego policy by rule or a Cedar policy by statement. Repeated
+/// names get a `#n` suffix (`allow`, `allow#2`); Cedar statements are named
+/// by their `@id` annotation when present.
+pub fn statements(policy: &Policy) -> Result<Vec<PolicyStatement>, PolicyError> {
+    if !matches!(policy.kind, PolicyKind::Rego | PolicyKind::Cedar) {
+        return Err(PolicyError::ValidationFailed(format!(
+            "{:?} policies have no statements; only Rego and Cedar can be split",
+            policy.kind
+        )));
+    }
+    Ok(chunks(policy)
+        .into_iter()
+        .filter_map(|chunk| {
+            let name = chunk.name?;
+            Some(PolicyStatement {
+                enabled: !policy.disabled_statements.contains(&name),
+                name,
+                description: chunk.description,
+                content: chunk.text.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Content with disabled statements removed
+pub fn enabled_content(policy: &Policy) -> String {
+    chunks(policy)
+        .into_iter()
+        .filter(|c| c.name.as_ref().is_none_or(|n| !policy.disabled_statements.contains(n)))
+        .map(|c| c.text)
+        .collect()
+}
+
+/// Whether any enabled statement can grant access. A policy whose allow
+/// rules are all disabled can no longer allow anything.
+pub fn can_grant(policy: &Policy) -> bool {
+    chunks(policy)
+        .into_iter()
+        .any(|c| c.grants && c.name.as_ref().is_some_and(|n| !policy.disabled_statements.contains(n)))
+}
+
+fn chunks(policy: &Policy) -> Vec<Chunk> {
+    match policy.kind {
+        PolicyKind::Rego => split_rego(&policy.content),
+        PolicyKind::Cedar => split_cedar(&policy.content),
+        _ => vec![Chunk {
+            name: None,
+            description: None,
+            grants: false,
+            text: policy.content.clone(),
+        }],
+    }
+}
+
+fn unique_name(base: &str, seen: &mut HashMap<String, usize>) -> String {
+    let count = seen.entry(base.to_string()).or_default();
+    *count += 1;
+    if *count == 1 {
+        base.to_string()
+    } else {
+        format!("{}#{}", base, count)
+    }
+}
+
+fn description(comments: &str, marker: &str) -> Option<String> {
+    let text: Vec<&str> = comments
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix(marker))
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    (!text.is_empty()).then(|| text.join(" "))
+}
+
+/// Each top-level rule runs until the next top-level line; comment lines
+/// directly above a rule belong to it
+fn split_rego(content: &str) -> Vec<Chunk> {
+    let mut chunks: Vec<Chunk> = vec![];
+    let mut comments = String::new();
+    let mut seen = HashMap::new();
+    for line in content.split_inclusive('\n') {
+        let top_level = !line.starts_with(char::is_whitespace) && !line.trim().is_empty();
+        let trimmed = line.trim();
+        if top_level && trimmed.starts_with('#') {
+            comments.push_str(line);
+            continue;
+        }
+        let head: String = trimmed
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+            .collect();
+        let starts_chunk = top_level && !trimmed.starts_with('}');
+        if starts_chunk {
+            let shared = head.is_empty() || matches!(head.as_str(), "package" | "import" | "default");
+            chunks.push(Chunk {
+                name: (!shared).then(|| unique_name(&head, &mut seen)),
+                description: if shared { None } else { description(&comments, "#") },
+                grants: head == "allow",
+                text: std::mem::take(&mut comments) + line,
+            });
+            continue;
+        }
+        match chunks.last_mut() {
+            Some(chunk) if comments.is_empty() => chunk.text.push_str(line),
+            _ => comments.push_str(line),
+        }
+    }
+    if !comments.is_empty() {
+        chunks.push(Chunk { name: None, description: None, grants: false, text: comments });
+    }
+    chunks
+}
+
+/// Statements end at a `;` outside strings, comments and brackets; leading
+/// comments and annotations belong to the statement that follows them
+fn split_cedar(content: &str) -> Vec<Chunk> {
+    let mut chunks = vec![];
+    let mut seen = HashMap::new();
+    let mut start = 0;
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut in_comment = false;
+    let mut chars = content.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\n' if in_comment => in_comment = false,
+            _ if in_comment => {}
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            _ if in_string => {}
+            '/' if chars.peek().is_some_and(|(_, n)| *n == '/') => in_comment = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ';' if depth == 0 => {
+                // Keep the rest of the line (usually just the newline) with the statement
+                let end = content[i..].find('\n').map_or(content.len(), |n| i + n + 1);
+                chunks.push(cedar_statement(&content[start..end], &mut seen));
+                while chars.peek().is_some_and(|(j, _)| *j < end) {
+                    chars.next();
+                }
+                start = end;
+            }
+            _ => {}
+        }
+    }
+    if start < content.len() {
+        chunks.push(Chunk {
+            name: None,
+            description: None,
+            grants: false,
+            text: content[start..].to_string(),
+        });
+    }
+    chunks
+}
+
+fn cedar_statement(text: &str, seen: &mut HashMap<String, usize>) -> Chunk {
+    let code: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("//"))
+        .collect();
+    let id = code.iter().find_map(|l| {
+        let rest = l.strip_prefix("@id(\"")?;
+        Some(rest[..rest.find('"')?].to_string())
+    });
+    let effect = code
+        .iter()
+        .find(|l| !l.starts_with('@'))
+        .map(|l| l.chars().take_while(char::is_ascii_alphabetic).collect::<String>())
+        .unwrap_or_default();
+    Chunk {
+        name: Some(unique_name(id.as_deref().unwrap_or(&effect), seen)),
+        description: description(text, "//"),
+        grants: effect == "permit",
+        text: text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AccessModel;
+
+    #[test]
+    fn test_split_rego_and_cedar_statements() {
+        let rego = "package t\n\ndefault allow := false\n\n# Reads\nallow if {\n    input.action == \"GET\"\n}\n\nallow if {\n    input.action == \"HEAD\"\n}\n\ndeny if {\n    input.action == \"DELETE\"\n}\n";
+        let mut policy = Policy::new("p", "", PolicyKind::Rego, AccessModel::ZeroTrust, rego, "ns", "test");
+        let names: Vec<String> = statements(&policy).unwrap().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["allow", "allow#2", "deny"]);
+        assert_eq!(statements(&policy).unwrap()[0].description.as_deref(), Some("Reads"));
+
+        policy.disabled_statements = vec!["allow#2".to_string()];
+        let content = enabled_content(&policy);
+        assert!(content.contains("\"GET\"") && !content.contains("\"HEAD\"") && content.starts_with("package t"));
+        assert!(can_grant(&policy));
+        policy.disabled_statements.push("allow".to_string());
+        assert!(!can_grant(&policy));
+
+        let cedar = "// Readers\n@id(\"reads\")\npermit (principal, action, resource)\nwhen { resource.name == \"a;b\" };\n\nforbid (principal, action, resource);\n";
+        let policy = Policy::new("c", "", PolicyKind::Cedar, AccessModel::ZeroTrust, cedar, "ns", "test");
+        let stmts = statements(&policy).unwrap();
+        assert_eq!(stmts.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), ["reads", "forbid"]);
+        assert_eq!(stmts[0].description.as_deref(), Some("Readers"));
+        assert_eq!(enabled_content(&policy), cedar);
+    }
+}
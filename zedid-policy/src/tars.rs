@@ -1,7 +1,10 @@
 use crate::error::PolicyError;
+use crate::metrics::{self, Metrics};
 use crate::models::PolicyKind;
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 /// Tetrate Agent Router Service (TARS) client
 /// Refactored from Python OpenAI client to Rust
@@ -10,6 +13,177 @@ pub struct TarsClient {
     api_key: Option<String>,
     http: reqwest::Client,
     mode: TarsMode,
+    routing: ModelRouting,
+    logging: TarsLogging,
+    metrics: Arc<Metrics>,
+    breaker: CircuitBreaker,
+}
+
+/// Stops calling TARS after `threshold` consecutive failed requests, so an
+/// outage fails generation fast instead of after the request timeout. After
+/// `cooldown` one probe request is let through (half-open): success closes
+/// the breaker, failure re-opens it for another cooldown.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// Consecutive failures that open the breaker (0 disables it)
+    threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<BreakerInner>,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    state: BreakerState,
+    consecutive_failures: u32,
+    /// When the breaker last opened, or the half-open probe was sent
+    since: Instant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Breaker state as reported by health and system info
+#[derive(Debug, Clone, Serialize)]
+pub struct BreakerStatus {
+    pub state: BreakerState,
+    pub consecutive_failures: u32,
+    pub threshold: u32,
+    /// Seconds until the next probe is allowed, while open
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_secs: Option<u64>,
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            inner: Mutex::new(BreakerInner {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                since: Instant::now(),
+            }),
+        }
+    }
+
+    /// Whether a request may be sent now; on `Err`, how long until a probe
+    /// is allowed. Moves an expired open breaker to half-open. A probe that
+    /// never reports back (e.g. a cancelled request) is replaced after
+    /// another cooldown.
+    fn try_acquire(&self, metrics: &Metrics) -> Result<(), Duration> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state == BreakerState::Closed {
+            return Ok(());
+        }
+        let elapsed = inner.since.elapsed();
+        if elapsed < self.cooldown {
+            return Err(self.cooldown - elapsed);
+        }
+        inner.since = Instant::now();
+        self.transition(&mut inner, BreakerState::HalfOpen, metrics);
+        Ok(())
+    }
+
+    fn record_success(&self, metrics: &Metrics) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures = 0;
+        self.transition(&mut inner, BreakerState::Closed, metrics);
+    }
+
+    fn record_failure(&self, metrics: &Metrics) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        let trips = match inner.state {
+            BreakerState::Closed => self.threshold > 0 && inner.consecutive_failures >= self.threshold,
+            BreakerState::HalfOpen | BreakerState::Open => true,
+        };
+        if trips {
+            inner.since = Instant::now();
+            self.transition(&mut inner, BreakerState::Open, metrics);
+        }
+    }
+
+    fn transition(&self, inner: &mut BreakerInner, to: BreakerState, metrics: &Metrics) {
+        if inner.state == to {
+            return;
+        }
+        match to {
+            BreakerState::Open => warn!(
+                failures = inner.consecutive_failures,
+                cooldown_secs = self.cooldown.as_secs(),
+                "TARS circuit breaker opened"
+            ),
+            _ => info!("TARS circuit breaker {:?} -> {:?}", inner.state, to),
+        }
+        inner.state = to;
+        metrics.incr(&format!("{}{{to=\"{}\"}}", metrics::TARS_BREAKER_TRANSITIONS_TOTAL, to.as_str()));
+    }
+
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.inner.lock().unwrap();
+        BreakerStatus {
+            state: inner.state,
+            consecutive_failures: inner.consecutive_failures,
+            threshold: self.threshold,
+            retry_in_secs: (inner.state == BreakerState::Open)
+                .then(|| self.cooldown.saturating_sub(inner.since.elapsed()).as_secs()),
+        }
+    }
+}
+
+/// Why a live TARS request failed, for the `TARS_ERRORS_TOTAL` counter
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TarsErrorCategory {
+    /// Connection, TLS or timeout failure
+    Network,
+    /// 401/403 — a missing, invalid or unauthorised API key
+    Auth,
+    /// Any other non-2xx response
+    Status,
+    /// The response body was not a chat completion
+    Parse,
+}
+
+impl TarsErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Auth => "auth",
+            Self::Status => "status",
+            Self::Parse => "parse",
+        }
+    }
+}
+
+/// What `generate_policy` writes to the debug log. Model, token count and
+/// latency are always logged; the API key never is.
+#[derive(Debug, Clone)]
+pub struct TarsLogging {
+    /// Also log the full prompt and raw response
+    pub log_payloads: bool,
+    /// Mask the security intent in logged prompts
+    pub redact_intent: bool,
+}
+
+impl Default for TarsLogging {
+    fn default() -> Self {
+        Self { log_payloads: false, redact_intent: true }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -18,6 +192,76 @@ pub enum TarsMode {
     Simulation,
 }
 
+/// Model selection table for live generation requests: a model per policy
+/// kind, a fallback, and an optional override for long (complex) prompts
+#[derive(Debug, Clone)]
+pub struct ModelRouting {
+    pub default_model: String,
+    pub by_kind: Vec<(PolicyKind, String)>,
+    /// Prompts of at least this many characters use `long_prompt_model` (0 disables)
+    pub long_prompt_chars: usize,
+    pub long_prompt_model: Option<String>,
+}
+
+impl Default for ModelRouting {
+    fn default() -> Self {
+        Self {
+            default_model: "gpt-4o-mini".to_string(),
+            by_kind: vec![
+                (PolicyKind::Rego, "gpt-4o".to_string()),
+                (PolicyKind::Cedar, "gpt-4o".to_string()),
+            ],
+            long_prompt_chars: 0,
+            long_prompt_model: None,
+        }
+    }
+}
+
+impl ModelRouting {
+    /// Parse `kind=model` routes, e.g. `rego=gpt-4o,default=gpt-4o-mini`.
+    /// Kinds missing from `routes` fall back to `default`.
+    pub fn from_routes(routes: &[String]) -> Result<Self, PolicyError> {
+        let mut routing = Self { by_kind: vec![], ..Self::default() };
+        for route in routes {
+            let (kind, model) = route
+                .split_once('=')
+                .map(|(k, m)| (k.trim(), m.trim()))
+                .filter(|(k, m)| !k.is_empty() && !m.is_empty())
+                .ok_or_else(|| PolicyError::ValidationFailed(format!("Invalid model route '{}': expected kind=model", route)))?;
+            if kind == "default" {
+                routing.default_model = model.to_string();
+                continue;
+            }
+            let kind: PolicyKind = serde_json::from_value(serde_json::Value::String(kind.to_string()))
+                .map_err(|_| PolicyError::ValidationFailed(format!("Unknown policy kind in model route: {}", kind)))?;
+            routing.by_kind.retain(|(k, _)| *k != kind);
+            routing.by_kind.push((kind, model.to_string()));
+        }
+        Ok(routing)
+    }
+
+    pub fn with_long_prompt_model(mut self, min_chars: usize, model: &str) -> Self {
+        self.long_prompt_chars = min_chars;
+        self.long_prompt_model = Some(model.to_string());
+        self
+    }
+
+    /// Pick a model for `prompt`, returning it with a human-readable rationale
+    pub fn route(&self, kind: &PolicyKind, prompt: &str) -> (String, String) {
+        let len = prompt.chars().count();
+        if let Some(model) = self.long_prompt_model.as_ref().filter(|_| self.long_prompt_chars > 0 && len >= self.long_prompt_chars) {
+            return (
+                model.clone(),
+                format!("prompt is {} chars (long-prompt threshold {})", len, self.long_prompt_chars),
+            );
+        }
+        match self.by_kind.iter().find(|(k, _)| k == kind) {
+            Some((_, model)) => (model.clone(), format!("{:?} policies are routed to {}", kind, model)),
+            None => (self.default_model.clone(), format!("default route for {:?} policies", kind)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct ChatCompletionRequest {
     model: String,
@@ -67,27 +311,119 @@ impl TarsClient {
                 .build()
                 .unwrap(),
             mode,
+            routing: ModelRouting::default(),
+            logging: TarsLogging::default(),
+            metrics: Arc::new(Metrics::new()),
+            breaker: CircuitBreaker::new(0, Duration::ZERO),
         }
     }
 
+    /// Open the circuit after `threshold` consecutive failures (0 disables)
+    /// and probe again after `cooldown`
+    pub fn with_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.breaker = CircuitBreaker::new(threshold, cooldown);
+        self
+    }
+
+    /// Circuit breaker state, or `None` in simulation mode or when disabled
+    pub fn breaker_status(&self) -> Option<BreakerStatus> {
+        (self.mode == TarsMode::Live && self.breaker.threshold > 0).then(|| self.breaker.status())
+    }
+
+    /// Report request counters and latency into a shared registry
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn with_logging(mut self, logging: TarsLogging) -> Self {
+        self.logging = logging;
+        self
+    }
+
+    pub fn with_routing(mut self, routing: ModelRouting) -> Self {
+        self.routing = routing;
+        self
+    }
+
+    /// Whether generation returns canned stubs instead of calling TARS
+    pub fn is_simulated(&self) -> bool {
+        self.mode == TarsMode::Simulation
+    }
+
     /// Route a policy generation request through TARS
-    /// Matches client.chat.completions.create(...) from the Python SDK
+    /// Matches client.chat.completions.create(...) from the Python SDK.
+    /// Returns (content, model, routing rationale, tokens used).
+    ///
+    /// Live requests run in a `tars_request` span and are counted in
+    /// `TARS_REQUESTS_TOTAL` (by outcome) and `TARS_ERRORS_TOTAL` (by
+    /// category), with latency in the `TARS_REQUEST_DURATION_MS` histogram.
+    #[tracing::instrument(
+        name = "tars_request",
+        skip_all,
+        fields(
+            mode = ?self.mode,
+            model = tracing::field::Empty,
+            request_bytes = tracing::field::Empty,
+            response_bytes = tracing::field::Empty,
+        )
+    )]
     pub async fn generate_policy(
         &self,
         prompt: &str,
         kind: &PolicyKind,
-    ) -> Result<(String, String, Option<u32>), PolicyError> {
+    ) -> Result<(String, String, String, Option<u32>), PolicyError> {
         if self.mode == TarsMode::Simulation {
             return Ok(self.simulate_response(prompt, kind));
         }
 
         // TARS routing: Select model based on complexity/type
-        let model = match kind {
-            PolicyKind::Rego => "gpt-4o",
-            PolicyKind::Cedar => "gpt-4o",
-            _ => "gpt-4o-mini",
+        let (model, routing_reason) = self.routing.route(kind, prompt);
+        tracing::Span::current().record("model", model.as_str());
+        debug!("TARS route: {} ({})", model, routing_reason);
+        if let Err(retry_in) = self.breaker.try_acquire(&self.metrics) {
+            self.metrics.incr(&format!("{}{{outcome=\"short_circuited\"}}", metrics::TARS_REQUESTS_TOTAL));
+            return Err(PolicyError::TarsError(format!(
+                "TARS circuit breaker is open; retrying in {}s",
+                retry_in.as_secs()
+            )));
+        }
+        if self.logging.log_payloads {
+            let logged = if self.logging.redact_intent { redact_intent(prompt) } else { prompt.to_string() };
+            debug!(model = %model, prompt = %logged, "TARS request");
+        }
+        let start = std::time::Instant::now();
+        let result = self.send(&model, prompt).await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+        self.metrics.observe(metrics::TARS_REQUEST_DURATION_MS, latency_ms);
+
+        let (content, tokens) = match result {
+            Ok(ok) => ok,
+            Err((category, e)) => {
+                self.breaker.record_failure(&self.metrics);
+                self.metrics.incr(&format!("{}{{outcome=\"failure\"}}", metrics::TARS_REQUESTS_TOTAL));
+                self.metrics.incr(&format!("{}{{category=\"{}\"}}", metrics::TARS_ERRORS_TOTAL, category.as_str()));
+                warn!(model = %model, category = category.as_str(), latency_ms, error = %e, "TARS request failed");
+                return Err(e);
+            }
         };
+        self.breaker.record_success(&self.metrics);
+        self.metrics.incr(&format!("{}{{outcome=\"success\"}}", metrics::TARS_REQUESTS_TOTAL));
+        debug!(
+            model = %model,
+            tokens = tokens.unwrap_or_default(),
+            latency_ms,
+            "TARS response"
+        );
+        if self.logging.log_payloads {
+            debug!(model = %model, response = %content, "TARS response content");
+        }
 
+        Ok((content, model, routing_reason, tokens))
+    }
+
+    /// One chat completion request; errors are tagged with their category
+    async fn send(&self, model: &str, prompt: &str) -> Result<(String, Option<u32>), (TarsErrorCategory, PolicyError)> {
         let request = ChatCompletionRequest {
             model: model.to_string(),
             messages: vec![
@@ -101,6 +437,9 @@ impl TarsClient {
                 },
             ],
         };
+        let body = serde_json::to_vec(&request)
+            .map_err(|e| (TarsErrorCategory::Parse, PolicyError::SerializationError(e)))?;
+        tracing::Span::current().record("request_bytes", body.len());
 
         // Construct URL: base_url + /chat/completions (Standard OpenAI API path)
         // If base_url is "https://api.router.tetrate.ai/v1", we append "/chat/completions"
@@ -108,49 +447,341 @@ impl TarsClient {
 
         debug!("Sending request to TARS: {}", url);
 
-        let mut req_builder = self.http.post(&url).json(&request);
+        let mut req_builder = self
+            .http
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body);
 
         if let Some(key) = &self.api_key {
             req_builder = req_builder.bearer_auth(key);
         }
 
-        let response = req_builder
-            .send()
-            .await
-            .map_err(|e| PolicyError::TarsError(format!("Network error: {}", e)))?;
+        let response = req_builder.send().await.map_err(|e| {
+            (TarsErrorCategory::Network, PolicyError::TarsError(format!("Network error: {}", e)))
+        })?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            return Err(PolicyError::TarsError(format!(
-                "TARS API failed: {} - {}",
-                status, text
-            )));
+        let status = response.status();
+        let bytes = response.bytes().await.map_err(|e| {
+            (TarsErrorCategory::Network, PolicyError::TarsError(format!("Network error: {}", e)))
+        })?;
+        tracing::Span::current().record("response_bytes", bytes.len());
+
+        if !status.is_success() {
+            let category = match status {
+                reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => TarsErrorCategory::Auth,
+                _ => TarsErrorCategory::Status,
+            };
+            let text = String::from_utf8_lossy(&bytes);
+            return Err((
+                category,
+                PolicyError::TarsError(format!("TARS API failed: {} - {}", status, text)),
+            ));
         }
 
-        let chat_resp: ChatCompletionResponse = response
-            .json()
-            .await
-            .map_err(|e| PolicyError::TarsError(format!("Parse error: {}", e)))?;
+        let chat_resp: ChatCompletionResponse = serde_json::from_slice(&bytes).map_err(|e| {
+            (TarsErrorCategory::Parse, PolicyError::TarsError(format!("Parse error: {}", e)))
+        })?;
 
         let content = chat_resp
             .choices
             .first()
             .map(|c| c.message.content.clone())
             .unwrap_or_default();
+        Ok((content, chat_resp.usage.map(|u| u.total_tokens)))
+    }
 
-        let tokens = chat_resp.usage.map(|u| u.total_tokens);
+    fn simulate_response(&self, prompt: &str, kind: &PolicyKind) -> (String, String, String, Option<u32>) {
+        self.simulate(prompt, kind, "simulation-mode", "no TARS API key configured")
+    }
 
-        Ok((content, model.to_string(), tokens))
+    /// Simulated stand-in for a live request that failed, for callers that
+    /// prefer a flagged stub over an error
+    pub fn simulate_fallback(&self, prompt: &str, kind: &PolicyKind, error: &PolicyError) -> (String, String, String, Option<u32>) {
+        self.simulate(prompt, kind, "simulation-fallback", &format!("TARS unavailable: {}", error))
     }
 
-    fn simulate_response(&self, prompt: &str, _kind: &PolicyKind) -> (String, String, Option<u32>) {
-        // Simulation mode: generate a realistic Rego policy stub
+    fn simulate(&self, prompt: &str, kind: &PolicyKind, model: &str, why: &str) -> (String, String, String, Option<u32>) {
+        // Simulation mode: generate a realistic, format-appropriate policy stub
         // In production, TARS routes to the optimal LLM (Gemini, GPT-4o, etc.)
+        let hints = PromptHints::parse(prompt);
+        let policy = match kind {
+            PolicyKind::Rego => simulate_rego(&hints),
+            PolicyKind::Cedar => simulate_cedar(&hints),
+            PolicyKind::IstioAuthz => simulate_istio(&hints),
+            PolicyKind::RbacYaml => simulate_rbac_yaml(&hints),
+        };
         let content = format!(
-            "# Simulated Rego Policy\n# Intent: {}\npackage zedid.generated\n\nimport future.keywords.if\n\ndefault allow := false\n\nallow if {{\n    input.trust_level >= 2\n}}\n",
-            &prompt[..prompt.len().min(80)]
+            "---POLICY---\n{}\n---EXPLANATION---\nSimulated policy ({}). {}\n---END---",
+            policy,
+            why,
+            hints.summary()
         );
-        (content, "simulation-mode".to_string(), Some(42))
+        (content, model.to_string(), format!("simulation mode: {}", why), Some(42))
+    }
+}
+
+/// Replace the security intent (the line after `SECURITY INTENT:`) so
+/// logged prompts don't carry what the caller asked for
+fn redact_intent(prompt: &str) -> String {
+    let mut redact_next = false;
+    prompt
+        .lines()
+        .map(|line| {
+            let line = if redact_next { "[REDACTED]" } else { line };
+            redact_next = line.trim() == "SECURITY INTENT:";
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Request details recovered from the generation prompt, so simulated
+/// output reflects what the caller asked for
+#[derive(Debug, Default)]
+struct PromptHints {
+    intent: String,
+    namespace: String,
+    subjects: Vec<String>,
+    resources: Vec<String>,
+    actions: Vec<String>,
+}
+
+impl PromptHints {
+    fn parse(prompt: &str) -> Self {
+        let list = |line: &str, prefix: &str| -> Option<Vec<String>> {
+            line.strip_prefix(prefix)
+                .map(|rest| rest.split(", ").map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect())
+        };
+
+        let mut hints = PromptHints::default();
+        let mut lines = prompt.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            if line == "SECURITY INTENT:" {
+                hints.intent = lines.next().unwrap_or_default().to_string();
+            } else if let Some(ns) = line.strip_prefix("- Namespace: ") {
+                hints.namespace = ns.to_string();
+            } else if let Some(subjects) = list(line, "Subjects: ") {
+                hints.subjects = subjects;
+            } else if let Some(resources) = list(line, "Resources: ") {
+                hints.resources = resources;
+            } else if let Some(actions) = list(line, "Actions: ") {
+                hints.actions = actions;
+            }
+        }
+        if hints.intent.is_empty() {
+            hints.intent = prompt.chars().take(80).collect();
+        }
+        if hints.namespace.is_empty() {
+            hints.namespace = "default".to_string();
+        }
+        if hints.actions.is_empty() {
+            hints.actions = vec!["GET".to_string()];
+        }
+        hints
+    }
+
+    fn summary(&self) -> String {
+        let subjects = if self.subjects.is_empty() {
+            format!("workloads in the {} namespace", self.namespace)
+        } else {
+            self.subjects.join(", ")
+        };
+        let resources = if self.resources.is_empty() {
+            "any resource".to_string()
+        } else {
+            self.resources.join(", ")
+        };
+        format!(
+            "Allows {} to perform {} on {}; everything else is denied.",
+            subjects,
+            self.actions.join("/"),
+            resources
+        )
+    }
+}
+
+fn quoted(values: &[String]) -> String {
+    values.iter().map(|v| format!("\"{}\"", v)).collect::<Vec<_>>().join(", ")
+}
+
+fn simulate_rego(h: &PromptHints) -> String {
+    let mut rules = vec![];
+    if h.subjects.is_empty() {
+        rules.push(format!("    input.namespace == \"{}\"", h.namespace));
+    } else {
+        rules.push(format!("    input.subject in {{{}}}", quoted(&h.subjects)));
+    }
+    if !h.resources.is_empty() {
+        rules.push(format!("    input.resource in {{{}}}", quoted(&h.resources)));
+    }
+    rules.push(format!("    input.action in {{{}}}", quoted(&h.actions)));
+    rules.push("    input.trust_level >= 2".to_string());
+    format!(
+        "# Simulated Rego Policy\n# Intent: {}\npackage zedid.generated\n\nimport future.keywords.if\nimport future.keywords.in\n\ndefault allow := false\n\nallow if {{\n{}\n}}",
+        h.intent,
+        rules.join("\n")
+    )
+}
+
+fn simulate_cedar(h: &PromptHints) -> String {
+    let mut conditions = vec![];
+    if h.subjects.is_empty() {
+        conditions.push(format!("    principal.namespace == \"{}\"", h.namespace));
+    } else {
+        conditions.push(format!("    [{}].contains(principal.spiffe_id)", quoted(&h.subjects)));
+    }
+    if !h.resources.is_empty() {
+        conditions.push(format!("    [{}].contains(resource.name)", quoted(&h.resources)));
+    }
+    conditions.push(format!("    [{}].contains(action.name)", quoted(&h.actions)));
+    conditions.push("    principal.trust_level >= 2".to_string());
+    format!(
+        "// Simulated Cedar Policy\n// Intent: {}\npermit (\n    principal,\n    action,\n    resource\n)\nwhen {{\n{}\n}};",
+        h.intent,
+        conditions.join(" &&\n")
+    )
+}
+
+fn simulate_istio(h: &PromptHints) -> String {
+    let selector = match h.resources.first() {
+        Some(app) => format!("  selector:\n    matchLabels:\n      app: {}\n", app),
+        None => String::new(),
+    };
+    let source = if h.subjects.is_empty() {
+        format!("            namespaces: [\"{}\"]", h.namespace)
+    } else {
+        format!(
+            "            principals:\n{}",
+            h.subjects
+                .iter()
+                .map(|s| format!("              - \"{}\"", s))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    };
+    format!(
+        "# Simulated Istio AuthorizationPolicy\n# Intent: {}\napiVersion: security.istio.io/v1beta1\nkind: AuthorizationPolicy\nmetadata:\n  name: zedid-generated\n  namespace: {}\nspec:\n{}  action: ALLOW\n  rules:\n    - from:\n        - source:\n{}\n      to:\n        - operation:\n            methods: [{}]",
+        h.intent,
+        h.namespace,
+        selector,
+        source,
+        quoted(&h.actions)
+    )
+}
+
+fn simulate_rbac_yaml(h: &PromptHints) -> String {
+    let resources = if h.resources.is_empty() {
+        format!("[\"{}/*\"]", h.namespace)
+    } else {
+        format!("[{}]", quoted(&h.resources))
+    };
+    let verbs: Vec<String> = h.actions.iter().map(|a| a.to_lowercase()).collect();
+    let subjects = if h.subjects.is_empty() {
+        format!("        - kind: Namespace\n          name: \"{}\"", h.namespace)
+    } else {
+        h.subjects
+            .iter()
+            .map(|s| format!("        - kind: ServiceAccount\n          spiffeId: \"{}\"", s))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    format!(
+        "# Simulated RBAC Policy\n# Intent: {}\napiVersion: zedid.tetrate.io/v1\nkind: RBACPolicy\nspec:\n  roles:\n    - name: generated-role\n      rules:\n        - resources: {}\n          verbs: [{}]\n  roleBindings:\n    - role: generated-role\n      subjects:\n{}",
+        h.intent,
+        resources,
+        quoted(&verbs),
+        subjects
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_failed_request_is_counted_by_category_with_latency() {
+        let metrics = Arc::new(Metrics::new());
+        // Nothing listens on the discard port, so the connect fails
+        let client = TarsClient::new("http://127.0.0.1:9", Some("key".to_string())).with_metrics(metrics.clone());
+        assert!(client.generate_policy("intent", &PolicyKind::Rego).await.is_err());
+
+        assert_eq!(metrics.get("zedid_tars_requests_total{outcome=\"failure\"}"), 1);
+        assert_eq!(metrics.get("zedid_tars_errors_total{category=\"network\"}"), 1);
+        assert_eq!(metrics.get("zedid_tars_request_duration_ms_count"), 1);
+        assert_eq!(metrics.get("zedid_tars_request_duration_ms_bucket{le=\"+Inf\"}"), 1);
+
+        // Simulated generation makes no request and records nothing
+        let simulated = TarsClient::new("simulation://tars.tetrate.io", None).with_metrics(metrics.clone());
+        simulated.generate_policy("intent", &PolicyKind::Rego).await.unwrap();
+        assert_eq!(metrics.get("zedid_tars_request_duration_ms_count"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_after_consecutive_failures_and_probes() {
+        let metrics = Arc::new(Metrics::new());
+        let client = TarsClient::new("http://127.0.0.1:9", Some("key".to_string()))
+            .with_metrics(metrics.clone())
+            .with_breaker(2, Duration::from_millis(50));
+        for _ in 0..2 {
+            assert!(client.generate_policy("intent", &PolicyKind::Rego).await.is_err());
+        }
+        assert_eq!(client.breaker_status().unwrap().state, BreakerState::Open);
+
+        let refused = client.generate_policy("intent", &PolicyKind::Rego).await.unwrap_err();
+        assert!(refused.to_string().contains("circuit breaker is open"));
+        assert_eq!(metrics.get("zedid_tars_requests_total{outcome=\"short_circuited\"}"), 1);
+        assert_eq!(metrics.get("zedid_tars_request_duration_ms_count"), 2);
+
+        // After the cooldown one probe goes out; its failure re-opens the breaker
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(client.generate_policy("intent", &PolicyKind::Rego).await.is_err());
+        assert_eq!(metrics.get("zedid_tars_request_duration_ms_count"), 3);
+        assert_eq!(client.breaker_status().unwrap().state, BreakerState::Open);
+        assert_eq!(metrics.get("zedid_tars_breaker_transitions_total{to=\"half_open\"}"), 1);
+        assert_eq!(metrics.get("zedid_tars_breaker_transitions_total{to=\"open\"}"), 2);
+
+        client.breaker.record_success(&metrics);
+        assert_eq!(client.breaker_status().unwrap().state, BreakerState::Closed);
+        assert!(TarsClient::new("simulation://tars.tetrate.io", None).with_breaker(2, Duration::ZERO).breaker_status().is_none());
+    }
+
+    #[test]
+    fn test_simulation_matches_requested_kind() {
+        let client = TarsClient::new("simulation://tars.tetrate.io", None);
+        let prompt = "SECURITY INTENT:\nAllow checkout to read inventory\n\nCONTEXT:\n- Namespace: production\nSubjects: spiffe://tetrate.io/ns/production/sa/checkout\nResources: inventory-service\nActions: GET, LIST\n";
+
+        let (cedar, _, _, _) = client.simulate_response(prompt, &PolicyKind::Cedar);
+        assert!(cedar.contains("permit ("));
+        assert!(cedar.contains("spiffe://tetrate.io/ns/production/sa/checkout"));
+
+        for kind in [PolicyKind::IstioAuthz, PolicyKind::RbacYaml] {
+            let (content, _, _, _) = client.simulate_response(prompt, &kind);
+            let yaml = &content[content.find("---POLICY---").unwrap() + 12..content.find("---EXPLANATION---").unwrap()];
+            let doc: serde_yaml::Value = serde_yaml::from_str(yaml).expect("valid YAML");
+            assert!(doc["kind"].is_string());
+        }
+    }
+
+    #[test]
+    fn test_redact_intent_masks_only_the_intent() {
+        let prompt = "SECURITY INTENT:\nLet payroll read salaries\n\nCONTEXT:\n- Namespace: hr";
+        let redacted = redact_intent(prompt);
+        assert!(!redacted.contains("salaries"));
+        assert!(redacted.contains("[REDACTED]"));
+        assert!(redacted.contains("- Namespace: hr"));
+    }
+
+    #[test]
+    fn test_model_routing_table() {
+        let routes = vec!["cedar=claude-sonnet".to_string(), "default=gpt-4o-mini".to_string()];
+        let routing = ModelRouting::from_routes(&routes).unwrap().with_long_prompt_model(100, "gpt-4o");
+
+        assert_eq!(routing.route(&PolicyKind::Cedar, "short").0, "claude-sonnet");
+        assert_eq!(routing.route(&PolicyKind::Rego, "short").0, "gpt-4o-mini");
+        let (model, reason) = routing.route(&PolicyKind::Cedar, &"x".repeat(150));
+        assert_eq!(model, "gpt-4o");
+        assert!(reason.contains("150 chars"));
+        assert!(ModelRouting::from_routes(&["yaml=gpt-4o".to_string()]).is_err());
     }
 }
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+/// Substitute `{{ name }}` placeholders from `vars`. Unknown names are left
+/// verbatim so a missing variable is visible in the rendered output.
+pub fn render(template: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open..].find("}}") else {
+            break;
+        };
+        let name = rest[open + 2..open + close].trim();
+        out.push_str(&rest[..open]);
+        match vars.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[open..open + close + 2]),
+        }
+        rest = &rest[open + close + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Whether the text contains any `{{ ... }}` placeholder
+pub fn is_template(text: &str) -> bool {
+    text.find("{{")
+        .is_some_and(|open| text[open..].contains("}}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_substitutes_known_and_keeps_unknown() {
+        let vars = HashMap::from([
+            ("trust_domain".to_string(), "tetrate.io".to_string()),
+            ("min_trust".to_string(), "3".to_string()),
+        ]);
+        let rendered = render(
+            "spiffe://{{ trust_domain }}/ns/x\ninput.trust_level >= {{min_trust}} # {{ missing }}",
+            &vars,
+        );
+        assert_eq!(
+            rendered,
+            "spiffe://tetrate.io/ns/x\ninput.trust_level >= 3 # {{ missing }}"
+        );
+    }
+}